@@ -0,0 +1,476 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use common::Answer;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{read_to_string, write};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const SALT_LENGTH: usize = 16;
+const HASH_LENGTH: usize = 32;
+/// Argon2id with 19 MiB of memory and two passes, so guessing an answer from
+/// its hash costs a noticeable fraction of a second per guess.
+const MEMORY_KIB: u32 = 19 * 1024;
+const PASSES: u32 = 2;
+
+type Records = BTreeMap<(u8, u8), Record>;
+
+/// How a `SaltedHash` was computed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Scheme {
+    /// `argon2id(answer, salt)`, used for everything written now.
+    Argon2,
+    /// `sha256(salt + answer)`, from older files. Still checked, but cheap
+    /// enough to brute-force, so record the answer again to replace it.
+    Sha256,
+}
+
+impl Scheme {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Argon2 => "argon2",
+            Self::Sha256 => "sha256",
+        }
+    }
+
+    fn digest(&self, salt: &[u8], answer: &Answer) -> Vec<u8> {
+        let answer = answer.to_string();
+        match self {
+            Self::Argon2 => {
+                let params = Params::new(MEMORY_KIB, PASSES, 1, Some(HASH_LENGTH))
+                    .expect("Argon2 parameters are valid");
+                let mut hash = vec![0; HASH_LENGTH];
+                Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password_into(answer.as_bytes(), salt, &mut hash)
+                    .expect("Salt and hash lengths are fixed");
+                hash
+            }
+            Self::Sha256 => {
+                let mut hasher = Sha256::new();
+                hasher.update(salt);
+                hasher.update(answer.as_bytes());
+                hasher.finalize().to_vec()
+            }
+        }
+    }
+}
+
+/// An answer or guess stored as a salted Argon2 hash, so it can be checked
+/// against a computed answer without being readable from the file.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SaltedHash {
+    scheme: Scheme,
+    salt: Vec<u8>,
+    hash: Vec<u8>,
+}
+
+impl SaltedHash {
+    /// Hashes `answer` with a fresh random salt.
+    pub fn new(answer: &Answer) -> Self {
+        let mut salt = vec![0; SALT_LENGTH];
+        getrandom::getrandom(&mut salt).expect("Unable to generate a salt");
+        Self::with_salt(salt, answer)
+    }
+
+    fn with_salt(salt: Vec<u8>, answer: &Answer) -> Self {
+        let hash = Scheme::Argon2.digest(&salt, answer);
+        Self {
+            scheme: Scheme::Argon2,
+            salt,
+            hash,
+        }
+    }
+
+    pub fn matches(&self, answer: &Answer) -> bool {
+        self.scheme.digest(&self.salt, answer) == self.hash
+    }
+}
+
+impl fmt::Display for SaltedHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}",
+            self.scheme.name(),
+            hex::encode(&self.salt),
+            hex::encode(&self.hash)
+        )
+    }
+}
+
+impl FromStr for SaltedHash {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut fields = input.split(':');
+        let scheme = match fields.next() {
+            Some("argon2") => Scheme::Argon2,
+            Some("sha256") => Scheme::Sha256,
+            _ => return Err(format!("Expected argon2:<salt>:<hash>, found {:?}", input)),
+        };
+        match (fields.next(), fields.next(), fields.next()) {
+            (Some(salt), Some(hash), None) => Ok(Self {
+                scheme,
+                salt: hex::decode(salt).map_err(|error| error.to_string())?,
+                hash: hex::decode(hash).map_err(|error| error.to_string())?,
+            }),
+            _ => Err(format!("Expected argon2:<salt>:<hash>, found {:?}", input)),
+        }
+    }
+}
+
+/// How a computed answer compares with what is known about a part.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Check {
+    /// Nothing known rules the answer in or out.
+    Unknown,
+    Correct,
+    Incorrect,
+    /// The same as a guess that was too high.
+    TooHigh,
+    /// The same as a guess that was too low.
+    TooLow,
+}
+
+impl Check {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Unknown => "unknown",
+            Self::Correct => "correct",
+            Self::Incorrect => "incorrect",
+            Self::TooHigh => "too high",
+            Self::TooLow => "too low",
+        }
+    }
+}
+
+/// What submissions have taught us about one part.
+///
+/// Wrong guesses are hashed like the answer, so they only recognize an
+/// answer that was already guessed; storing them as numbers would bound the
+/// answer closely enough to find it.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Record {
+    pub answer: Option<SaltedHash>,
+    /// Guesses that were too high.
+    pub too_high: Vec<SaltedHash>,
+    /// Guesses that were too low.
+    pub too_low: Vec<SaltedHash>,
+}
+
+impl Record {
+    pub fn check(&self, answer: &Answer) -> Check {
+        if let Some(hash) = &self.answer {
+            if hash.matches(answer) {
+                return Check::Correct;
+            }
+        }
+        if self.too_high.iter().any(|guess| guess.matches(answer)) {
+            Check::TooHigh
+        } else if self.too_low.iter().any(|guess| guess.matches(answer)) {
+            Check::TooLow
+        } else if self.answer.is_some() {
+            Check::Incorrect
+        } else {
+            Check::Unknown
+        }
+    }
+}
+
+/// What is known about each part's answer, stored one fact per line as
+/// `day part argon2:<salt>:<hash>`, `day part too-high argon2:<salt>:<hash>`
+/// or `day part too-low argon2:<salt>:<hash>`. Plain answers and guesses from
+/// older files are hashed when they are loaded.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct AnswerDatabase {
+    path: PathBuf,
+    records: Records,
+    /// Whether the file had plain answers or guesses that only the next save
+    /// will hash.
+    has_plain_answers: bool,
+}
+
+impl AnswerDatabase {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let contents = match read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error),
+        };
+        let (records, has_plain_answers) = parse(&contents)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            records,
+            has_plain_answers,
+        })
+    }
+
+    pub fn has_plain_answers(&self) -> bool {
+        self.has_plain_answers
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&Record> {
+        self.records.get(&(day, part))
+    }
+
+    pub fn is_solved(&self, day: u8, part: u8) -> bool {
+        self.get(day, part)
+            .is_some_and(|record| record.answer.is_some())
+    }
+
+    pub fn check(&self, day: u8, part: u8, answer: &Answer) -> Check {
+        self.get(day, part)
+            .map_or(Check::Unknown, |record| record.check(answer))
+    }
+
+    pub fn insert(&mut self, day: u8, part: u8, answer: &Answer) {
+        self.records.entry((day, part)).or_default().answer = Some(SaltedHash::new(answer));
+    }
+
+    /// Remembers a guess that was too high, unless it already is.
+    pub fn insert_too_high(&mut self, day: u8, part: u8, guess: &Answer) {
+        let record = self.records.entry((day, part)).or_default();
+        if !record.too_high.iter().any(|known| known.matches(guess)) {
+            record.too_high.push(SaltedHash::new(guess));
+        }
+    }
+
+    /// Remembers a guess that was too low, unless it already is.
+    pub fn insert_too_low(&mut self, day: u8, part: u8, guess: &Answer) {
+        let record = self.records.entry((day, part)).or_default();
+        if !record.too_low.iter().any(|known| known.matches(guess)) {
+            record.too_low.push(SaltedHash::new(guess));
+        }
+    }
+
+    pub fn save(&mut self) -> io::Result<()> {
+        write(&self.path, serialize(&self.records))?;
+        self.has_plain_answers = false;
+        Ok(())
+    }
+}
+
+fn malformed(index: usize, line: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Malformed answer on line {}: {:?}", index + 1, line),
+    )
+}
+
+/// Parses the records, also reporting whether any answer was still plain.
+fn parse(contents: &str) -> io::Result<(Records, bool)> {
+    let mut records = Records::new();
+    let mut has_plain_answers = false;
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.splitn(3, ' ');
+        let day = parts.next().and_then(|day| day.parse::<u8>().ok());
+        let part = parts.next().and_then(|part| part.parse::<u8>().ok());
+        let (Some(day), Some(part), Some(value)) = (day, part, parts.next()) else {
+            return Err(malformed(index, line));
+        };
+        let record = records.entry((day, part)).or_default();
+        // Hashes a value unless it already is one, noting that it was plain.
+        let mut hash = |value: &str, is_guess: bool| {
+            if ["argon2:", "sha256:"]
+                .iter()
+                .any(|scheme| value.starts_with(scheme))
+            {
+                return value.parse().map_err(|_| malformed(index, line));
+            }
+            let answer: Answer = value.parse().unwrap();
+            if is_guess && matches!(answer, Answer::Text(_)) {
+                return Err(malformed(index, line));
+            }
+            has_plain_answers = true;
+            Ok(SaltedHash::new(&answer))
+        };
+        if let Some(guess) = value.strip_prefix("too-high ") {
+            record.too_high.push(hash(guess, true)?);
+        } else if let Some(guess) = value.strip_prefix("too-low ") {
+            record.too_low.push(hash(guess, true)?);
+        } else {
+            record.answer = Some(hash(value, false)?);
+        }
+    }
+    Ok((records, has_plain_answers))
+}
+
+fn serialize(records: &Records) -> String {
+    let mut contents = String::new();
+    for ((day, part), record) in records {
+        if let Some(answer) = &record.answer {
+            contents += &format!("{} {} {}\n", day, part, answer);
+        }
+        for guess in &record.too_high {
+            contents += &format!("{} {} too-high {}\n", day, part, guess);
+        }
+        for guess in &record.too_low {
+            contents += &format!("{} {} too-low {}\n", day, part, guess);
+        }
+    }
+    contents
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::temp_dir;
+
+    #[test]
+    fn it_hashes_answers() {
+        let hash = SaltedHash::with_salt(vec![1; SALT_LENGTH], &Answer::from(11));
+        assert!(hash.matches(&Answer::from(11)));
+        assert!(!hash.matches(&Answer::from(12)));
+        assert!(hash.to_string().starts_with("argon2:"));
+        assert!(!hash.to_string().contains(":11"));
+        assert_eq!(Ok(hash.clone()), hash.to_string().parse());
+        assert!("md5:00:00".parse::<SaltedHash>().is_err());
+    }
+
+    #[test]
+    fn it_checks_older_sha256_hashes() {
+        let hash = SaltedHash {
+            scheme: Scheme::Sha256,
+            salt: vec![1, 2],
+            hash: Scheme::Sha256.digest(&[1, 2], &Answer::from(11)),
+        };
+        assert!(hash.matches(&Answer::from(11)));
+        assert!(!hash.matches(&Answer::from(12)));
+        assert!(hash.to_string().starts_with("sha256:0102:"));
+        assert_eq!(Ok(hash.clone()), hash.to_string().parse());
+    }
+
+    #[test]
+    fn it_salts_every_hash() {
+        let answer = Answer::from("6,1");
+        let first = SaltedHash::new(&answer);
+        let second = SaltedHash::new(&answer);
+        assert_ne!(first, second);
+        assert!(first.matches(&answer) && second.matches(&answer));
+    }
+
+    #[test]
+    fn it_parses_records() {
+        let hash = SaltedHash::with_salt(vec![7; SALT_LENGTH], &Answer::from(31));
+        let (records, has_plain_answers) = parse(&format!(
+            "# comment\n1 1 11\n\n1 2 {}\n1 2 too-high 50\n1 2 too-low 20\n13 1 6,1\n",
+            hash
+        ))
+        .unwrap();
+        assert!(has_plain_answers);
+        assert!(records[&(1, 1)]
+            .answer
+            .as_ref()
+            .unwrap()
+            .matches(&Answer::from(11)));
+        assert_eq!(Some(hash), records[&(1, 2)].answer);
+        assert!(records[&(1, 2)].too_high[0].matches(&Answer::from(50)));
+        assert!(records[&(1, 2)].too_low[0].matches(&Answer::from(20)));
+        assert!(records[&(13, 1)]
+            .answer
+            .as_ref()
+            .unwrap()
+            .matches(&Answer::from("6,1")));
+    }
+
+    #[test]
+    fn it_rejects_malformed_lines() {
+        assert!(parse("1 11\n").is_err());
+        assert!(parse("one 1 11\n").is_err());
+        assert!(parse("1 1 too-high lots\n").is_err());
+        assert!(parse("1 1 sha256:zz\n").is_err());
+        assert!(parse("1 1 argon2:00\n").is_err());
+    }
+
+    #[test]
+    fn it_checks_answers() {
+        let record = Record {
+            answer: Some(SaltedHash::new(&Answer::from(31))),
+            too_high: vec![SaltedHash::new(&Answer::from(50))],
+            too_low: vec![SaltedHash::new(&Answer::from(-3))],
+        };
+        assert_eq!(Check::Correct, record.check(&Answer::from(31)));
+        assert_eq!(Check::Incorrect, record.check(&Answer::from(30)));
+        assert_eq!(Check::TooHigh, record.check(&Answer::from(50)));
+        assert_eq!(Check::TooLow, record.check(&Answer::from(-3)));
+        assert_eq!(Check::Incorrect, record.check(&Answer::from(60)));
+        assert_eq!(Check::Incorrect, record.check(&Answer::from("abc")));
+        assert_eq!(Check::Unknown, Record::default().check(&Answer::from(31)));
+    }
+
+    #[test]
+    fn it_remembers_each_guess_once() {
+        let mut database =
+            AnswerDatabase::load(&temp_dir().join("aoc-answers-missing.txt")).unwrap();
+        database.insert_too_high(1, 1, &Answer::from(100));
+        database.insert_too_high(1, 1, &Answer::from(80));
+        database.insert_too_high(1, 1, &Answer::from(100));
+        database.insert_too_low(1, 1, &Answer::from(10));
+        assert_eq!(2, database.get(1, 1).unwrap().too_high.len());
+        assert_eq!(1, database.get(1, 1).unwrap().too_low.len());
+        assert_eq!(Check::TooHigh, database.check(1, 1, &Answer::from(80)));
+        assert_eq!(Check::TooLow, database.check(1, 1, &Answer::from(10)));
+        assert_eq!(Check::Unknown, database.check(1, 1, &Answer::from(50)));
+        assert!(!database.is_solved(1, 1));
+    }
+
+    #[test]
+    fn it_round_trips_through_disk() {
+        let path = temp_dir().join("aoc-answers-round-trip.txt");
+        let _ = std::fs::remove_file(&path);
+        let mut database = AnswerDatabase::load(&path).unwrap();
+        assert_eq!(None, database.get(1, 1));
+        database.insert(1, 1, &Answer::from(11));
+        database.insert_too_low(1, 2, &Answer::from(-40));
+        database.insert(1, 2, &Answer::from(-31));
+        database.save().unwrap();
+        assert_eq!(database, AnswerDatabase::load(&path).unwrap());
+        let contents = read_to_string(&path).unwrap();
+        assert!(contents.starts_with("1 1 argon2:"));
+        assert!(contents.contains("\n1 2 too-low argon2:"));
+        assert!(!AnswerDatabase::load(&path).unwrap().has_plain_answers());
+        assert!(!contents.contains("-31"));
+        assert!(!contents.contains("-40"));
+        assert!(database.is_solved(1, 2));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn it_hashes_plain_answers_and_bounds_on_save() {
+        let path = temp_dir().join("aoc-answers-plain.txt");
+        write(&path, "1 1 11\n1 2 too-high 4700\n1 2 too-low 3100\n").unwrap();
+        let mut database = AnswerDatabase::load(&path).unwrap();
+        assert!(database.has_plain_answers());
+        database.save().unwrap();
+        assert!(!database.has_plain_answers());
+        let contents = read_to_string(&path).unwrap();
+        assert!(!contents.contains(" 11"));
+        assert!(!contents.contains("4700"));
+        assert!(!contents.contains("3100"));
+        let database = AnswerDatabase::load(&path).unwrap();
+        assert_eq!(Check::Correct, database.check(1, 1, &Answer::from(11)));
+        assert_eq!(Check::TooHigh, database.check(1, 2, &Answer::from(4700)));
+        assert_eq!(Check::TooLow, database.check(1, 2, &Answer::from(3100)));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,299 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::registry::Solution;
+use crate::runner::{run_on_worker, run_part, solve, Format, Outcome};
+use common::Arithmetic;
+use serde_json::{json, Value};
+use std::fs::{read_to_string, write};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Timings from running one part repeatedly on the same input.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Benchmark {
+    pub day: u8,
+    pub part: u8,
+    pub samples: Vec<Duration>,
+}
+
+impl Benchmark {
+    fn sorted(&self) -> Vec<Duration> {
+        let mut samples = self.samples.clone();
+        samples.sort_unstable();
+        samples
+    }
+
+    pub fn min(&self) -> Duration {
+        self.samples.iter().min().copied().unwrap_or_default()
+    }
+
+    pub fn median(&self) -> Duration {
+        let sorted = self.sorted();
+        match sorted.len() {
+            0 => Duration::ZERO,
+            length if length % 2 == 1 => sorted[length / 2],
+            length => (sorted[length / 2 - 1] + sorted[length / 2]) / 2,
+        }
+    }
+
+    pub fn mean(&self) -> Duration {
+        match self.samples.len() {
+            0 => Duration::ZERO,
+            length => self.samples.iter().sum::<Duration>() / length as u32,
+        }
+    }
+
+    /// Sample standard deviation, zero with fewer than two samples.
+    pub fn standard_deviation(&self) -> Duration {
+        if self.samples.len() < 2 {
+            return Duration::ZERO;
+        }
+        let mean = self.mean().as_secs_f64();
+        let variance = self
+            .samples
+            .iter()
+            .map(|sample| (sample.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / (self.samples.len() - 1) as f64;
+        Duration::from_secs_f64(variance.sqrt())
+    }
+}
+
+/// Runs a part once to make sure it finishes within `limit`, then times
+/// `samples` more runs back to back on one worker.
+pub fn bench_part(
+    solution: &Solution,
+    part: u8,
+    input: &str,
+    samples: usize,
+    limit: Option<Duration>,
+) -> Result<Benchmark, Outcome> {
+    let (outcome, _) = run_part(solution, part, input, Arithmetic::default(), limit);
+    if !matches!(outcome, Outcome::Solved(_)) {
+        return Err(outcome);
+    }
+    let (solution, input) = (*solution, input.to_string());
+    let (timings, _) = run_on_worker(
+        format!("day-{:02}-part-{}-bench", solution.day, part),
+        None,
+        move || {
+            (0..samples)
+                .map(|_| {
+                    let input = input.clone();
+                    let start = Instant::now();
+                    solve(&solution, part, input, Arithmetic::default());
+                    start.elapsed()
+                })
+                .collect()
+        },
+    );
+    Ok(Benchmark {
+        day: solution.day,
+        part,
+        samples: timings?,
+    })
+}
+
+/// The latest benchmark of every part, kept in `baseline.json` so reports
+/// and comparisons don't need to rerun slow parts.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Baseline {
+    path: PathBuf,
+    benchmarks: Vec<Benchmark>,
+}
+
+fn parse_benchmark(value: &Value) -> Option<Benchmark> {
+    Some(Benchmark {
+        day: u8::try_from(value["day"].as_u64()?).ok()?,
+        part: u8::try_from(value["part"].as_u64()?).ok()?,
+        samples: value["samples_ns"]
+            .as_array()?
+            .iter()
+            .map(|sample| sample.as_u64().map(Duration::from_nanos))
+            .collect::<Option<_>>()?,
+    })
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let contents = match read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => "[]".to_string(),
+            Err(error) => return Err(error),
+        };
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        let values: Vec<Value> = serde_json::from_str(&contents)
+            .map_err(|error| invalid(format!("{}: {}", path.display(), error)))?;
+        let benchmarks = values
+            .iter()
+            .map(|value| {
+                parse_benchmark(value)
+                    .ok_or_else(|| invalid(format!("{}: bad benchmark {}", path.display(), value)))
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            benchmarks,
+        })
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&Benchmark> {
+        self.benchmarks
+            .iter()
+            .find(|benchmark| benchmark.day == day && benchmark.part == part)
+    }
+
+    /// Replaces any earlier benchmark of the same part.
+    pub fn insert(&mut self, benchmark: Benchmark) {
+        self.benchmarks
+            .retain(|old| (old.day, old.part) != (benchmark.day, benchmark.part));
+        self.benchmarks.push(benchmark);
+        self.benchmarks
+            .sort_by_key(|benchmark| (benchmark.day, benchmark.part));
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let values: Vec<Value> = self
+            .benchmarks
+            .iter()
+            .map(|benchmark| {
+                let samples: Vec<u64> = benchmark
+                    .samples
+                    .iter()
+                    .map(|sample| sample.as_nanos() as u64)
+                    .collect();
+                json!({"day": benchmark.day, "part": benchmark.part, "samples_ns": samples})
+            })
+            .collect();
+        let contents = serde_json::to_string_pretty(&values).expect("JSON values always serialize");
+        write(&self.path, contents + "\n")
+    }
+}
+
+pub fn render(benchmarks: &[Benchmark], format: Format) -> String {
+    match format {
+        Format::Text => benchmarks
+            .iter()
+            .map(|benchmark| {
+                format!(
+                    "Day {:02} Part {}: median {:.3?} (mean {:.3?} ± {:.3?}, min {:.3?}, {} samples)\n",
+                    benchmark.day,
+                    benchmark.part,
+                    benchmark.median(),
+                    benchmark.mean(),
+                    benchmark.standard_deviation(),
+                    benchmark.min(),
+                    benchmark.samples.len()
+                )
+            })
+            .collect(),
+        Format::Json => {
+            let benchmarks: Vec<_> = benchmarks
+                .iter()
+                .map(|benchmark| {
+                    json!({
+                        "day": benchmark.day,
+                        "part": benchmark.part,
+                        "samples": benchmark.samples.len(),
+                        "median_ms": benchmark.median().as_secs_f64() * 1000.0,
+                        "mean_ms": benchmark.mean().as_secs_f64() * 1000.0,
+                        "stddev_ms": benchmark.standard_deviation().as_secs_f64() * 1000.0,
+                        "min_ms": benchmark.min().as_secs_f64() * 1000.0,
+                    })
+                })
+                .collect();
+            format!("{}\n", Value::Array(benchmarks))
+        }
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+    use std::env::temp_dir;
+    use std::fs::remove_file;
+
+    fn benchmark(samples: &[u64]) -> Benchmark {
+        Benchmark {
+            day: 1,
+            part: 2,
+            samples: samples.iter().copied().map(Duration::from_millis).collect(),
+        }
+    }
+
+    #[test]
+    fn it_summarizes_samples() {
+        let benchmark = benchmark(&[4, 1, 3, 2]);
+        assert_eq!(Duration::from_millis(1), benchmark.min());
+        assert_eq!(Duration::from_micros(2500), benchmark.median());
+        assert_eq!(Duration::from_micros(2500), benchmark.mean());
+        let deviation = benchmark.standard_deviation().as_secs_f64();
+        assert!((deviation - 0.001291).abs() < 1e-6);
+        assert_eq!(
+            Duration::from_millis(3),
+            self::benchmark(&[5, 3, 1]).median()
+        );
+        assert_eq!(Duration::ZERO, self::benchmark(&[5]).standard_deviation());
+        assert_eq!(Duration::ZERO, self::benchmark(&[]).median());
+    }
+
+    #[test]
+    fn it_benchmarks_parts() {
+        let solution = registry::find(2024, 1).unwrap();
+        let benchmark = bench_part(&solution, 2, "3   4\n4   3", 3, None).unwrap();
+        assert_eq!(
+            (1, 2, 3),
+            (benchmark.day, benchmark.part, benchmark.samples.len())
+        );
+        assert!(bench_part(&solution, 1, "3   x", 3, None).is_err());
+    }
+
+    #[test]
+    fn it_saves_and_loads_baselines() {
+        let path = temp_dir().join(format!("aoc-baseline-{}.json", std::process::id()));
+        let mut baseline = Baseline::load(&path).unwrap();
+        assert!(baseline.get(1, 2).is_none());
+        baseline.insert(benchmark(&[3, 1]));
+        baseline.insert(benchmark(&[2]));
+        baseline.insert(Benchmark {
+            part: 1,
+            ..benchmark(&[7])
+        });
+        baseline.save().unwrap();
+        let loaded = Baseline::load(&path).unwrap();
+        assert_eq!(baseline, loaded);
+        assert_eq!(&benchmark(&[2]), loaded.get(1, 2).unwrap());
+        assert_eq!(1, loaded.benchmarks[0].part);
+        write(&path, r#"[{"day": 1}]"#).unwrap();
+        assert!(Baseline::load(&path).is_err());
+        remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn it_renders_benchmarks() {
+        let text = render(&[benchmark(&[1, 3])], Format::Text);
+        assert_eq!(
+            "Day 01 Part 2: median 2.000ms (mean 2.000ms ± 1.414ms, min 1.000ms, 2 samples)\n",
+            text
+        );
+        let json: Value =
+            serde_json::from_str(&render(&[benchmark(&[1, 3])], Format::Json)).unwrap();
+        assert_eq!(2.0, json[0]["median_ms"]);
+        assert_eq!(2, json[0]["samples"]);
+    }
+}
@@ -0,0 +1,484 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::runner::Format;
+use crate::workspace;
+use serde_json::{json, Value};
+use std::env;
+use std::fs::{create_dir_all, write};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// A part's timings as `aoc bench --format json` summarizes them, in
+/// milliseconds.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Summary {
+    pub samples: usize,
+    pub mean: f64,
+    pub stddev: f64,
+}
+
+impl Summary {
+    /// The time both parts of a day take together, treating them as
+    /// independent.
+    fn plus(&self, other: &Self) -> Self {
+        Self {
+            samples: self.samples.min(other.samples),
+            mean: self.mean + other.mean,
+            stddev: self.stddev.hypot(other.stddev),
+        }
+    }
+}
+
+fn parse_summary(value: &Value) -> Option<(u8, u8, Summary)> {
+    Some((
+        u8::try_from(value["day"].as_u64()?).ok()?,
+        u8::try_from(value["part"].as_u64()?).ok()?,
+        Summary {
+            samples: usize::try_from(value["samples"].as_u64()?).ok()?,
+            mean: value["mean_ms"].as_f64()?,
+            stddev: value["stddev_ms"].as_f64()?,
+        },
+    ))
+}
+
+/// Reads `aoc bench --format json` into `(day, part, summary)`s.
+pub fn parse_bench(json: &str) -> io::Result<Vec<(u8, u8, Summary)>> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let values: Vec<Value> = serde_json::from_str(json)
+        .map_err(|error| invalid(format!("Unable to read benchmarks: {}", error)))?;
+    values
+        .iter()
+        .map(|value| {
+            parse_summary(value)
+                .ok_or_else(|| invalid(format!("Unable to read benchmark {}", value)))
+        })
+        .collect()
+}
+
+/// `ln Γ(x)` for `x > 0`, by the Lanczos approximation.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (index, coefficient)| {
+            sum + coefficient / (x + index as f64 + 1.0)
+        });
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+/// The continued fraction in the regularized incomplete beta function.
+fn beta_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    d = 1.0 / if d.abs() < TINY { TINY } else { d };
+    let mut fraction = d;
+    for m in 1..=300 {
+        let m = f64::from(m);
+        let steps = [
+            m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m)),
+            -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0)),
+        ];
+        for step in steps {
+            d = 1.0 + step * d;
+            d = 1.0 / if d.abs() < TINY { TINY } else { d };
+            c = 1.0 + step / c;
+            c = if c.abs() < TINY { TINY } else { c };
+            fraction *= c * d;
+        }
+        if (c * d - 1.0).abs() < 1e-15 {
+            break;
+        }
+    }
+    fraction
+}
+
+/// The regularized incomplete beta function `I_x(a, b)`.
+fn incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// The two-sided p-value of Welch's t-test that `a` and `b` have the same
+/// mean. Without two samples on each side there is nothing to test, so the
+/// answer is 1.
+pub fn welch_p_value(a: &Summary, b: &Summary) -> f64 {
+    if a.samples < 2 || b.samples < 2 {
+        return 1.0;
+    }
+    let variance_a = a.stddev.powi(2) / a.samples as f64;
+    let variance_b = b.stddev.powi(2) / b.samples as f64;
+    let variance = variance_a + variance_b;
+    if variance == 0.0 {
+        return if a.mean == b.mean { 1.0 } else { 0.0 };
+    }
+    let t = (a.mean - b.mean) / variance.sqrt();
+    let freedom = variance.powi(2)
+        / (variance_a.powi(2) / (a.samples - 1) as f64
+            + variance_b.powi(2) / (b.samples - 1) as f64);
+    incomplete_beta(freedom / 2.0, 0.5, freedom / (freedom + t * t))
+}
+
+/// One part, or with no part a whole day, before and after.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Comparison {
+    pub day: u8,
+    pub part: Option<u8>,
+    pub before: Summary,
+    pub after: Summary,
+}
+
+impl Comparison {
+    /// How much slower `after` is, in percent of `before`.
+    pub fn change(&self) -> f64 {
+        (self.after.mean - self.before.mean) / self.before.mean * 100.0
+    }
+
+    pub fn p_value(&self) -> f64 {
+        welch_p_value(&self.before, &self.after)
+    }
+
+    pub fn is_significant(&self, alpha: f64) -> bool {
+        self.p_value() < alpha
+    }
+
+    /// Whether this got slower by more than `threshold` percent, and not by
+    /// chance.
+    pub fn is_regression(&self, threshold: f64, alpha: f64) -> bool {
+        self.change() > threshold && self.is_significant(alpha)
+    }
+
+    fn verdict(&self, threshold: f64, alpha: f64) -> &'static str {
+        match (self.is_significant(alpha), self.change() > 0.0) {
+            _ if self.is_regression(threshold, alpha) => "regression",
+            (true, true) => "slower",
+            (true, false) => "faster",
+            (false, _) => "no change",
+        }
+    }
+}
+
+/// Pairs up the parts benchmarked on both sides, adding a row for each day
+/// with both of its parts.
+pub fn compare(before: &[(u8, u8, Summary)], after: &[(u8, u8, Summary)]) -> Vec<Comparison> {
+    let mut comparisons: Vec<Comparison> = before
+        .iter()
+        .filter_map(|(day, part, before)| {
+            let (_, _, after) = after.iter().find(|(d, p, _)| (d, p) == (day, part))?;
+            Some(Comparison {
+                day: *day,
+                part: Some(*part),
+                before: *before,
+                after: *after,
+            })
+        })
+        .collect();
+    comparisons.sort_by_key(|comparison| (comparison.day, comparison.part));
+    let days: Vec<Comparison> = comparisons
+        .windows(2)
+        .filter(|pair| pair[0].day == pair[1].day)
+        .map(|pair| Comparison {
+            day: pair[0].day,
+            part: None,
+            before: pair[0].before.plus(&pair[1].before),
+            after: pair[0].after.plus(&pair[1].after),
+        })
+        .collect();
+    comparisons.extend(days);
+    comparisons
+        .sort_by_key(|comparison| (comparison.day, comparison.part.is_none(), comparison.part));
+    comparisons
+}
+
+pub fn render(comparisons: &[Comparison], threshold: f64, alpha: f64, format: Format) -> String {
+    match format {
+        Format::Text => comparisons
+            .iter()
+            .map(|comparison| {
+                let name = match comparison.part {
+                    Some(part) => format!("Day {:02} Part {}:", comparison.day, part),
+                    None => format!("Day {:02} total:", comparison.day),
+                };
+                format!(
+                    "{:<14} {:>10.3}ms -> {:>10.3}ms {:>+8.1}% (p = {:.3}) {}\n",
+                    name,
+                    comparison.before.mean,
+                    comparison.after.mean,
+                    comparison.change(),
+                    comparison.p_value(),
+                    comparison.verdict(threshold, alpha)
+                )
+            })
+            .collect(),
+        Format::Json => {
+            let comparisons: Vec<_> = comparisons
+                .iter()
+                .map(|comparison| {
+                    json!({
+                        "day": comparison.day,
+                        "part": comparison.part,
+                        "before_mean_ms": comparison.before.mean,
+                        "after_mean_ms": comparison.after.mean,
+                        "change_percent": comparison.change(),
+                        "p_value": comparison.p_value(),
+                        "significant": comparison.is_significant(alpha),
+                        "regression": comparison.is_regression(threshold, alpha),
+                    })
+                })
+                .collect();
+            format!("{}\n", Value::Array(comparisons))
+        }
+    }
+}
+
+fn git(root: &Path) -> Command {
+    let mut command = Command::new(env::var("GIT").unwrap_or_else(|_| "git".to_string()));
+    command.arg("-C").arg(root);
+    command
+}
+
+/// Runs `command`, returning what it printed or an error naming `what`.
+fn output(mut command: Command, what: &str) -> io::Result<String> {
+    let output = command.stderr(Stdio::inherit()).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "{} exited with {}",
+            what, output.status
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// A detached checkout of one revision, removed when dropped.
+#[derive(Debug)]
+pub struct Worktree {
+    root: PathBuf,
+    pub path: PathBuf,
+    pub commit: String,
+}
+
+impl Worktree {
+    /// Checks out `revision` of the repository at `root` into a worktree
+    /// under `scratch`.
+    pub fn add(root: &Path, scratch: &Path, revision: &str) -> io::Result<Self> {
+        let mut resolve = git(root);
+        resolve.args(["rev-parse", "--verify", "--quiet"]);
+        resolve.arg(format!("{}^{{commit}}", revision));
+        let commit = output(resolve, &format!("git rev-parse {}", revision))
+            .map_err(|_| io::Error::other(format!("Unknown revision {}", revision)))?
+            .trim()
+            .to_string();
+        let path = scratch.join("worktrees").join(&commit);
+        let mut add = git(root);
+        add.args(["worktree", "add", "--detach", "--force", "--quiet"]);
+        add.arg(&path).arg(&commit);
+        output(add, "git worktree add")?;
+        Ok(Self {
+            root: root.to_path_buf(),
+            path,
+            commit,
+        })
+    }
+}
+
+impl Drop for Worktree {
+    fn drop(&mut self) {
+        let mut remove = git(&self.root);
+        remove
+            .args(["worktree", "remove", "--force"])
+            .arg(&self.path);
+        if let Err(error) = output(remove, "git worktree remove") {
+            eprintln!("Unable to remove {}: {}", self.path.display(), error);
+        }
+    }
+}
+
+/// Benchmarks every day of `year` at `revision` on the contents of each
+/// day's input in `inputs`, building in `scratch` with a target directory
+/// shared between runs. `arguments` go to that revision's `aoc bench`.
+pub fn bench_revision(
+    root: &Path,
+    scratch: &Path,
+    revision: &str,
+    year: u16,
+    inputs: &[(u8, String)],
+    arguments: &[String],
+) -> io::Result<Vec<(u8, u8, Summary)>> {
+    create_dir_all(scratch)?;
+    let worktree = Worktree::add(root, scratch, revision)?;
+    eprintln!("Benchmarking {} at {}", revision, &worktree.commit[..12]);
+    for (day, input) in inputs {
+        // Days the revision does not have are left out of the comparison.
+        if workspace::day_directory(&worktree.path, year, *day).is_dir() {
+            write(workspace::input_path(&worktree.path, year, *day), input)?;
+        }
+    }
+    let mut bench = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()));
+    bench
+        .current_dir(&worktree.path)
+        .env("CARGO_TARGET_DIR", scratch.join("target"))
+        .args(["run", "--release", "--quiet", "--package", "aoc", "--"])
+        .args(["bench", "--all", "--format", "json", "--year"])
+        .arg(year.to_string())
+        .args(arguments);
+    parse_bench(&output(bench, &format!("aoc bench at {}", revision))?)
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::remove_dir_all;
+
+    fn summary(samples: usize, mean: f64, stddev: f64) -> Summary {
+        Summary {
+            samples,
+            mean,
+            stddev,
+        }
+    }
+
+    #[test]
+    fn it_computes_p_values() {
+        let close =
+            |expected: f64, actual: f64| assert!((expected - actual).abs() < 1e-6, "{}", actual);
+        close(0.0, ln_gamma(1.0));
+        close(120_f64.ln(), ln_gamma(6.0));
+        close(std::f64::consts::PI.sqrt().ln(), ln_gamma(0.5));
+        close(0.5, incomplete_beta(2.0, 2.0, 0.5));
+        close(0.104, incomplete_beta(2.0, 2.0, 0.2));
+        // Equal samples with t = 1 and 18 degrees of freedom.
+        close(
+            0.330_565,
+            welch_p_value(&summary(10, 1.0, 1.0), &summary(10, 1.0 + 0.447_213_6, 1.0)),
+        );
+        // t = 2 with 10 degrees of freedom.
+        let p = welch_p_value(
+            &summary(6, 0.0, 1.0),
+            &summary(6, 2.0 * (1.0_f64 / 3.0).sqrt(), 1.0),
+        );
+        close(0.073_388, p);
+        assert_eq!(
+            1.0,
+            welch_p_value(&summary(1, 1.0, 0.0), &summary(5, 9.0, 1.0))
+        );
+        assert_eq!(
+            0.0,
+            welch_p_value(&summary(5, 1.0, 0.0), &summary(5, 2.0, 0.0))
+        );
+        assert_eq!(
+            1.0,
+            welch_p_value(&summary(5, 1.0, 0.0), &summary(5, 1.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn it_compares_parts_and_days() {
+        let before = parse_bench(
+            r#"[{"day": 1, "part": 1, "samples": 20, "mean_ms": 10.0, "stddev_ms": 0.1},
+                {"day": 1, "part": 2, "samples": 20, "mean_ms": 30.0, "stddev_ms": 5.0},
+                {"day": 2, "part": 1, "samples": 20, "mean_ms": 1.0, "stddev_ms": 0.1}]"#,
+        )
+        .unwrap();
+        let after = parse_bench(
+            r#"[{"day": 1, "part": 1, "samples": 20, "mean_ms": 12.0, "stddev_ms": 0.1},
+                {"day": 1, "part": 2, "samples": 20, "mean_ms": 30.5, "stddev_ms": 5.0},
+                {"day": 3, "part": 1, "samples": 20, "mean_ms": 1.0, "stddev_ms": 0.1}]"#,
+        )
+        .unwrap();
+        let comparisons = compare(&before, &after);
+        let rows: Vec<_> = comparisons.iter().map(|c| (c.day, c.part)).collect();
+        assert_eq!(vec![(1, Some(1)), (1, Some(2)), (1, None)], rows);
+        assert!(comparisons[0].is_regression(5.0, 0.05));
+        assert!(!comparisons[0].is_regression(25.0, 0.05));
+        assert!(!comparisons[1].is_significant(0.05));
+        assert_eq!(40.0, comparisons[2].before.mean);
+        let text = render(&comparisons, 5.0, 0.05, Format::Text);
+        assert!(text.starts_with(
+            "Day 01 Part 1:     10.000ms ->     12.000ms    +20.0% (p = 0.000) regression\n"
+        ));
+        assert!(text.contains(
+            "Day 01 Part 2:     30.000ms ->     30.500ms     +1.7% (p = 0.754) no change\n"
+        ));
+        assert!(text.contains(
+            "Day 01 total:      40.000ms ->     42.500ms     +6.2% (p = 0.122) no change\n"
+        ));
+        let json: Value =
+            serde_json::from_str(&render(&comparisons, 25.0, 0.05, Format::Json)).unwrap();
+        assert_eq!(true, json[0]["significant"]);
+        assert_eq!(false, json[0]["regression"]);
+        assert_eq!(Value::Null, json[2]["part"]);
+        assert!(parse_bench(r#"[{"day": 1}]"#).is_err());
+    }
+
+    #[test]
+    fn it_checks_out_revisions_in_worktrees() {
+        let directory = env::temp_dir().join(format!("aoc-compare-{}", std::process::id()));
+        let _ = remove_dir_all(&directory);
+        let repository = directory.join("repository");
+        create_dir_all(&repository).unwrap();
+        let run = |arguments: &[&str]| {
+            let status = git(&repository)
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(arguments)
+                .stdout(Stdio::null())
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        run(&["init", "--quiet"]);
+        write(repository.join("file.txt"), "first").unwrap();
+        run(&["add", "file.txt"]);
+        run(&["commit", "--quiet", "-m", "First"]);
+        let scratch = directory.join("scratch");
+        let worktree = Worktree::add(&repository, &scratch, "HEAD").unwrap();
+        assert_eq!(40, worktree.commit.len());
+        assert!(worktree.path.join("file.txt").is_file());
+        let path = worktree.path.clone();
+        drop(worktree);
+        assert!(!path.exists());
+        assert!(Worktree::add(&repository, &scratch, "no-such-revision").is_err());
+        remove_dir_all(directory).unwrap();
+    }
+}
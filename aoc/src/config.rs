@@ -0,0 +1,591 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `aoc.toml`: settings from the workspace root, layered over the same file
+//! in the user's config directory.
+//!
+//! ```toml
+//! year = 2024
+//! input_dir = "~/aoc-inputs"
+//! cache_dir = ".cache"
+//! format = "json"
+//! time_limit = 30
+//! budget = 2
+//!
+//! [cookie]
+//! command = ["secret-tool", "lookup", "service", "adventofcode"]
+//!
+//! [days.2024.6]
+//! time_limit = 120
+//! budget = 5
+//! ```
+
+use crate::runner::Format;
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::fs::read_to_string;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+pub const FILE_NAME: &str = "aoc.toml";
+/// Seconds a part may run when neither the command line nor `aoc.toml` says.
+pub const DEFAULT_TIME_LIMIT: f64 = 60.0;
+/// Holds the session cookie when `aoc.toml` names no other source.
+pub const COOKIE_VARIABLE: &str = "SESSION_COOKIE";
+/// Seconds a part may take on its input before the budget tests fail.
+pub const DEFAULT_BUDGET: f64 = 1.0;
+/// How many percent slower a part may get before `bench-compare` fails.
+pub const DEFAULT_BENCH_THRESHOLD: f64 = 5.0;
+
+/// One `aoc.toml` as written, before validation.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawConfig {
+    year: Option<i64>,
+    input_dir: Option<String>,
+    cache_dir: Option<String>,
+    format: Option<String>,
+    time_limit: Option<f64>,
+    budget: Option<f64>,
+    bench_threshold: Option<f64>,
+    cookie: Option<RawCookie>,
+    #[serde(default)]
+    days: BTreeMap<String, BTreeMap<String, RawDay>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawCookie {
+    file: Option<String>,
+    env: Option<String>,
+    command: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawDay {
+    time_limit: Option<f64>,
+    budget: Option<f64>,
+}
+
+/// Where the adventofcode.com session cookie comes from.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Cookie {
+    /// A file holding the cookie.
+    File(PathBuf),
+    /// An environment variable holding the cookie.
+    Env(String),
+    /// A command that prints the cookie, such as a keyring lookup.
+    Command(Vec<String>),
+}
+
+impl Default for Cookie {
+    fn default() -> Self {
+        Self::Env(COOKIE_VARIABLE.to_string())
+    }
+}
+
+impl fmt::Display for Cookie {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "file {}", path.display()),
+            Self::Env(variable) => write!(f, "environment variable {}", variable),
+            Self::Command(command) => write!(f, "command {}", command.join(" ")),
+        }
+    }
+}
+
+/// Settings for one day of one year.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct DayConfig {
+    pub time_limit: Option<f64>,
+    pub budget: Option<f64>,
+}
+
+/// Validated settings. Anything unset falls back to the defaults below.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Config {
+    pub year: Option<u16>,
+    pub input_dir: Option<PathBuf>,
+    pub cache_dir: Option<PathBuf>,
+    pub format: Option<Format>,
+    pub time_limit: Option<f64>,
+    pub budget: Option<f64>,
+    pub bench_threshold: Option<f64>,
+    pub cookie: Option<Cookie>,
+    pub days: BTreeMap<(u16, u8), DayConfig>,
+}
+
+fn invalid(origin: &Path, message: String) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{}: {}", origin.display(), message),
+    )
+}
+
+/// Resolves `~/` against the home directory and anything else relative
+/// against `base`, the directory holding the file that named the path.
+fn resolve(path: &str, base: &Path) -> PathBuf {
+    match (path.strip_prefix("~/"), env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => base.join(path),
+    }
+}
+
+fn check_time_limit(origin: &Path, key: &str, seconds: f64) -> io::Result<f64> {
+    if seconds.is_finite() && 0.0 <= seconds {
+        Ok(seconds)
+    } else {
+        Err(invalid(
+            origin,
+            format!(
+                "{} must be a number of seconds, or 0 for no limit; found {}",
+                key, seconds
+            ),
+        ))
+    }
+}
+
+fn check_year(origin: &Path, key: &str, year: i64) -> io::Result<u16> {
+    u16::try_from(year)
+        .ok()
+        .filter(|year| 2015 <= *year)
+        .ok_or_else(|| {
+            invalid(
+                origin,
+                format!("{} must be 2015 or later; found {}", key, year),
+            )
+        })
+}
+
+impl Config {
+    /// Validates the text of an `aoc.toml`. `origin` names the file in
+    /// errors, and relative paths are resolved against its directory.
+    pub fn parse(text: &str, origin: &Path) -> io::Result<Self> {
+        let raw: RawConfig =
+            toml::from_str(text).map_err(|error| invalid(origin, error.message().to_string()))?;
+        let base = origin.parent().unwrap_or(Path::new(""));
+        let format = match raw.format {
+            Some(name) => Some(Format::from_str(&name, true).map_err(|_| {
+                let names: Vec<String> = Format::value_variants()
+                    .iter()
+                    .filter_map(|format| format.to_possible_value())
+                    .map(|value| value.get_name().to_string())
+                    .collect();
+                invalid(
+                    origin,
+                    format!("format must be {}; found {:?}", names.join(" or "), name),
+                )
+            })?),
+            None => None,
+        };
+        let cookie = match raw.cookie {
+            None => None,
+            Some(RawCookie {
+                file: Some(file),
+                env: None,
+                command: None,
+            }) => Some(Cookie::File(resolve(&file, base))),
+            Some(RawCookie {
+                file: None,
+                env: Some(variable),
+                command: None,
+            }) if !variable.is_empty() => Some(Cookie::Env(variable)),
+            Some(RawCookie {
+                file: None,
+                env: None,
+                command: Some(command),
+            }) if !command.is_empty() => Some(Cookie::Command(command)),
+            Some(_) => {
+                return Err(invalid(
+                    origin,
+                    "cookie needs exactly one of file = \"path\", env = \"VARIABLE\" or \
+                     command = [\"program\", \"argument\"]"
+                        .to_string(),
+                ))
+            }
+        };
+        let mut days = BTreeMap::new();
+        for (year, entries) in raw.days {
+            let key = format!("days.{}", year);
+            let number = year
+                .parse()
+                .map_err(|_| invalid(origin, format!("{} must be a year", key)))?;
+            let year = check_year(origin, &key, number)?;
+            for (day, entry) in entries {
+                let key = format!("{}.{}", key, day);
+                let day = day
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|day| (1..=25).contains(day))
+                    .ok_or_else(|| {
+                        invalid(origin, format!("{} must be a day from 1 to 25", key))
+                    })?;
+                let time_limit = match entry.time_limit {
+                    Some(seconds) => Some(check_time_limit(
+                        origin,
+                        &format!("{}.time_limit", key),
+                        seconds,
+                    )?),
+                    None => None,
+                };
+                let budget = match entry.budget {
+                    Some(seconds) => Some(check_time_limit(
+                        origin,
+                        &format!("{}.budget", key),
+                        seconds,
+                    )?),
+                    None => None,
+                };
+                days.insert((year, day), DayConfig { time_limit, budget });
+            }
+        }
+        Ok(Self {
+            year: raw
+                .year
+                .map(|year| check_year(origin, "year", year))
+                .transpose()?,
+            input_dir: raw.input_dir.map(|path| resolve(&path, base)),
+            cache_dir: raw.cache_dir.map(|path| resolve(&path, base)),
+            format,
+            time_limit: raw
+                .time_limit
+                .map(|seconds| check_time_limit(origin, "time_limit", seconds))
+                .transpose()?,
+            budget: raw
+                .budget
+                .map(|seconds| check_time_limit(origin, "budget", seconds))
+                .transpose()?,
+            bench_threshold: raw
+                .bench_threshold
+                .map(|percent| match percent.is_finite() && 0.0 <= percent {
+                    true => Ok(percent),
+                    false => Err(invalid(
+                        origin,
+                        format!("bench_threshold must be a percentage; found {}", percent),
+                    )),
+                })
+                .transpose()?,
+            cookie,
+            days,
+        })
+    }
+
+    /// Reads and validates one `aoc.toml`; a missing file sets nothing.
+    pub fn load_file(path: &Path) -> io::Result<Self> {
+        match read_to_string(path) {
+            Ok(text) => Self::parse(&text, path),
+            Err(error) if io::ErrorKind::NotFound == error.kind() => Ok(Self::default()),
+            Err(error) => Err(invalid(path, error.to_string())),
+        }
+    }
+
+    /// The user's `aoc.toml`, in `$XDG_CONFIG_HOME/aoc` or `~/.config/aoc`.
+    pub fn user_path() -> Option<PathBuf> {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .map(|directory| directory.join("aoc").join(FILE_NAME))
+    }
+
+    /// The user's settings with the workspace's `aoc.toml` on top.
+    pub fn load(root: &Path) -> io::Result<Self> {
+        let user = match Self::user_path() {
+            Some(path) => Self::load_file(&path)?,
+            None => Self::default(),
+        };
+        Ok(user.merge(Self::load_file(&root.join(FILE_NAME))?))
+    }
+
+    /// These settings with any set in `other` taking their place.
+    pub fn merge(mut self, other: Self) -> Self {
+        for (key, day) in other.days {
+            let entry = self.days.entry(key).or_default();
+            entry.time_limit = day.time_limit.or(entry.time_limit);
+            entry.budget = day.budget.or(entry.budget);
+        }
+        Self {
+            year: other.year.or(self.year),
+            input_dir: other.input_dir.or(self.input_dir),
+            cache_dir: other.cache_dir.or(self.cache_dir),
+            format: other.format.or(self.format),
+            time_limit: other.time_limit.or(self.time_limit),
+            budget: other.budget.or(self.budget),
+            bench_threshold: other.bench_threshold.or(self.bench_threshold),
+            cookie: other.cookie.or(self.cookie),
+            days: self.days,
+        }
+    }
+
+    /// Where inputs live, laid out like the workspace as
+    /// `year-YYYY/day-NN/input.txt`; the workspace itself by default.
+    pub fn inputs(&self, root: &Path) -> PathBuf {
+        self.input_dir.clone().unwrap_or_else(|| root.to_path_buf())
+    }
+
+    /// Scratch space, such as the worktrees `bench-compare` builds in;
+    /// `.cache` in the workspace by default.
+    pub fn cache(&self, root: &Path) -> PathBuf {
+        self.cache_dir
+            .clone()
+            .unwrap_or_else(|| root.join(".cache"))
+    }
+
+    pub fn format(&self, chosen: Option<Format>) -> Format {
+        chosen.or(self.format).unwrap_or(Format::Text)
+    }
+
+    /// How long a part of `day` may run: `seconds` from the command line,
+    /// then the day's `time_limit`, then the file's, then a minute. Zero
+    /// means no limit.
+    pub fn time_limit(&self, year: u16, day: u8, seconds: Option<f64>) -> Option<Duration> {
+        self.default_time_limit(seconds.or_else(|| self.days.get(&(year, day))?.time_limit))
+    }
+
+    /// Like `time_limit`, for work that is not tied to one day.
+    pub fn default_time_limit(&self, seconds: Option<f64>) -> Option<Duration> {
+        let seconds = seconds.or(self.time_limit).unwrap_or(DEFAULT_TIME_LIMIT);
+        (seconds > 0.0).then(|| Duration::from_secs_f64(seconds))
+    }
+
+    /// How long a part of `day` may take on its input in a release build
+    /// before the budget tests fail: the day's `budget`, then the file's, then
+    /// a second. Zero means no budget.
+    pub fn budget(&self, year: u16, day: u8) -> Option<Duration> {
+        self.default_budget(self.days.get(&(year, day)).and_then(|day| day.budget))
+    }
+
+    /// Like `budget`, for a day with `seconds` of its own or none.
+    pub fn default_budget(&self, seconds: Option<f64>) -> Option<Duration> {
+        let seconds = seconds.or(self.budget).unwrap_or(DEFAULT_BUDGET);
+        (seconds > 0.0).then(|| Duration::from_secs_f64(seconds))
+    }
+
+    /// How many percent slower `bench-compare` lets a part get: `percent`
+    /// from the command line, then the file's `bench_threshold`, then 5.
+    pub fn bench_threshold(&self, percent: Option<f64>) -> f64 {
+        percent
+            .or(self.bench_threshold)
+            .unwrap_or(DEFAULT_BENCH_THRESHOLD)
+    }
+
+    /// The session cookie as `session=<value>`, whether or not the source
+    /// includes the `session=` prefix.
+    pub fn cookie(&self) -> io::Result<String> {
+        let source = self.cookie.clone().unwrap_or_default();
+        let value = match &source {
+            Cookie::File(path) => read_to_string(path).map_err(|error| {
+                io::Error::new(
+                    error.kind(),
+                    format!(
+                        "Unable to read the session cookie from {}: {}",
+                        path.display(),
+                        error
+                    ),
+                )
+            })?,
+            Cookie::Env(variable) => env::var(variable).map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!(
+                        "{} is not set; set it to your session cookie or name another source in {}",
+                        variable, FILE_NAME
+                    ),
+                )
+            })?,
+            Cookie::Command(command) => {
+                let output = Command::new(&command[0])
+                    .args(&command[1..])
+                    .output()
+                    .map_err(|error| {
+                        io::Error::new(
+                            error.kind(),
+                            format!("Unable to run {}: {}", command[0], error),
+                        )
+                    })?;
+                if !output.status.success() {
+                    return Err(io::Error::other(format!(
+                        "{} exited with {} instead of printing the session cookie",
+                        command[0], output.status
+                    )));
+                }
+                String::from_utf8_lossy(&output.stdout).to_string()
+            }
+        };
+        let value = value.trim();
+        let value = value.strip_prefix("session=").unwrap_or(value);
+        if value.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("The session cookie from {} is empty", source),
+            ));
+        }
+        Ok(format!("session={}", value))
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, remove_dir_all, write};
+
+    fn parse(text: &str) -> io::Result<Config> {
+        Config::parse(text, Path::new("/repo/aoc.toml"))
+    }
+
+    fn error(text: &str) -> String {
+        parse(text).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn it_parses_every_setting() {
+        let config = parse(
+            "year = 2015\ninput_dir = \"inputs\"\ncache_dir = \"/tmp/aoc\"\nformat = \"json\"\n\
+             time_limit = 30\nbudget = 2\nbench_threshold = 2.5\n\
+             [cookie]\ncommand = [\"pass\", \"aoc\"]\n\
+             [days.2015.6]\ntime_limit = 0.5\nbudget = 0\n[days.2015.7]\nbudget = 0.25\n",
+        )
+        .unwrap();
+        assert_eq!(Some(2015), config.year);
+        assert_eq!(Some(PathBuf::from("/repo/inputs")), config.input_dir);
+        assert_eq!(PathBuf::from("/tmp/aoc"), config.cache(Path::new("/repo")));
+        assert_eq!(Format::Json, config.format(None));
+        assert_eq!(Format::Text, config.format(Some(Format::Text)));
+        assert_eq!(
+            Some(Cookie::Command(vec!["pass".to_string(), "aoc".to_string()])),
+            config.cookie
+        );
+        assert_eq!(
+            Some(Duration::from_millis(500)),
+            config.time_limit(2015, 6, None)
+        );
+        assert_eq!(
+            Some(Duration::from_secs(30)),
+            config.time_limit(2015, 7, None)
+        );
+        assert_eq!(None, config.time_limit(2015, 6, Some(0.0)));
+        assert_eq!(None, config.budget(2015, 6));
+        assert_eq!(Some(Duration::from_millis(250)), config.budget(2015, 7));
+        assert_eq!(Some(Duration::from_secs(2)), config.budget(2015, 8));
+        assert_eq!(2.5, config.bench_threshold(None));
+        assert_eq!(10.0, config.bench_threshold(Some(10.0)));
+    }
+
+    #[test]
+    fn it_defaults_everything() {
+        let config = parse("").unwrap();
+        let root = Path::new("/repo");
+        assert_eq!(root, config.inputs(root));
+        assert_eq!(root.join(".cache"), config.cache(root));
+        assert_eq!(Format::Text, config.format(None));
+        assert_eq!(
+            Some(Duration::from_secs(60)),
+            config.time_limit(2024, 1, None)
+        );
+        assert_eq!(Cookie::Env("SESSION_COOKIE".to_string()), Cookie::default());
+        assert_eq!(5.0, config.bench_threshold(None));
+        assert_eq!(Some(Duration::from_secs(1)), config.budget(2024, 6));
+    }
+
+    #[test]
+    fn it_explains_bad_settings() {
+        assert!(error("year = 2014").ends_with("year must be 2015 or later; found 2014"));
+        assert!(error("format = \"xml\"").ends_with("format must be text or json; found \"xml\""));
+        assert!(error("time_limit = -1").contains("time_limit must be a number of seconds"));
+        assert!(error("bench_threshold = -5")
+            .ends_with("bench_threshold must be a percentage; found -5"));
+        assert!(error("[days.2024.26]").ends_with("days.2024.26 must be a day from 1 to 25"));
+        assert!(error("[days.next.1]").ends_with("days.next must be a year"));
+        assert!(error("[days.2024.6]\ntime_limit = -2")
+            .contains("days.2024.6.time_limit must be a number of seconds"));
+        assert!(error("[days.2024.6]\nbudget = -1")
+            .contains("days.2024.6.budget must be a number of seconds"));
+        assert!(error("[cookie]\nfile = \"a\"\nenv = \"B\"").contains("exactly one of"));
+        assert!(error("[cookie]\ncommand = []").contains("exactly one of"));
+        assert!(error("colour = \"red\"").contains("unknown field `colour`"));
+        assert!(error("year = \"2024\"").starts_with("/repo/aoc.toml: invalid type"));
+    }
+
+    #[test]
+    fn it_layers_the_workspace_over_the_user() {
+        let user = parse("year = 2023\nformat = \"json\"\n[days.2023.1]\ntime_limit = 5").unwrap();
+        let workspace =
+            parse("year = 2024\n[days.2023.1]\nbudget = 3\n[days.2023.2]\ntime_limit = 7").unwrap();
+        let config = user.merge(workspace);
+        assert_eq!(Some(2024), config.year);
+        assert_eq!(Some(Format::Json), config.format);
+        assert_eq!(
+            Some(Duration::from_secs(5)),
+            config.time_limit(2023, 1, None)
+        );
+        assert_eq!(
+            Some(Duration::from_secs(7)),
+            config.time_limit(2023, 2, None)
+        );
+        assert_eq!(Some(Duration::from_secs(3)), config.budget(2023, 1));
+    }
+
+    #[test]
+    fn it_loads_files() {
+        let directory = env::temp_dir().join(format!("aoc-config-{}", std::process::id()));
+        let _ = remove_dir_all(&directory);
+        create_dir_all(&directory).unwrap();
+        assert_eq!(
+            Config::default(),
+            Config::load_file(&directory.join(FILE_NAME)).unwrap()
+        );
+        write(directory.join(FILE_NAME), "cache_dir = \"cache\"\n").unwrap();
+        let config = Config::load(&directory).unwrap();
+        assert_eq!(directory.join("cache"), config.cache(&directory));
+        remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn it_reads_cookies_from_every_source() {
+        let directory = env::temp_dir().join(format!("aoc-cookie-{}", std::process::id()));
+        let _ = remove_dir_all(&directory);
+        create_dir_all(&directory).unwrap();
+        let file = directory.join("cookie");
+        write(&file, "session=abc123\n").unwrap();
+        let from = |cookie| Config {
+            cookie: Some(cookie),
+            ..Config::default()
+        };
+        assert_eq!("session=abc123", from(Cookie::File(file)).cookie().unwrap());
+        let command = Cookie::Command(vec!["echo".to_string(), "abc123".to_string()]);
+        assert_eq!("session=abc123", from(command).cookie().unwrap());
+        assert!(from(Cookie::Command(vec!["false".to_string()]))
+            .cookie()
+            .unwrap_err()
+            .to_string()
+            .contains("instead of printing the session cookie"));
+        let missing = Cookie::Env("AOC_TEST_MISSING_COOKIE".to_string());
+        assert!(from(missing)
+            .cookie()
+            .unwrap_err()
+            .to_string()
+            .starts_with("AOC_TEST_MISSING_COOKIE is not set"));
+        write(directory.join("empty"), "session=\n").unwrap();
+        assert!(from(Cookie::File(directory.join("empty")))
+            .cookie()
+            .is_err());
+        remove_dir_all(directory).unwrap();
+    }
+}
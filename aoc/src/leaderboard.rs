@@ -0,0 +1,466 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::progress::{format_duration, format_time, release};
+use crate::runner::Format;
+use clap::ValueEnum;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::read_to_string;
+use std::io;
+use std::path::Path;
+
+/// One star in the export, `completion_day_level.<day>.<part>`.
+#[derive(Debug, Deserialize)]
+struct RawStar {
+    get_star_ts: u64,
+    #[serde(default)]
+    star_index: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawMember {
+    id: u64,
+    name: Option<String>,
+    #[serde(default)]
+    local_score: u64,
+    #[serde(default)]
+    completion_day_level: BTreeMap<String, BTreeMap<String, RawStar>>,
+}
+
+/// A private leaderboard as exported from its `.json` link.
+#[derive(Debug, Deserialize)]
+struct RawLeaderboard {
+    event: String,
+    members: BTreeMap<String, RawMember>,
+}
+
+/// When one member earned one star.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Star {
+    pub time: u64,
+    /// The site's global order of stars, which breaks ties in `time`.
+    pub index: u64,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Member {
+    pub id: u64,
+    /// The member's name, or the site's stand-in for anonymous members.
+    pub name: String,
+    /// The score the site reported when the export was taken.
+    pub local_score: u64,
+    pub stars: BTreeMap<(u8, u8), Star>,
+}
+
+impl Member {
+    pub fn star(&self, day: u8, part: u8) -> Option<Star> {
+        self.stars.get(&(day, part)).copied()
+    }
+
+    /// Seconds between a member's first and second star of a day.
+    pub fn delta(&self, day: u8) -> Option<u64> {
+        Some(
+            self.star(day, 2)?
+                .time
+                .saturating_sub(self.star(day, 1)?.time),
+        )
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Leaderboard {
+    pub year: u16,
+    pub members: Vec<Member>,
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl Leaderboard {
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let raw: RawLeaderboard =
+            serde_json::from_str(text).map_err(|error| invalid(error.to_string()))?;
+        let year = raw
+            .event
+            .parse()
+            .map_err(|_| invalid(format!("Unknown event {:?}", raw.event)))?;
+        let members = raw
+            .members
+            .into_values()
+            .map(|member| {
+                let mut stars = BTreeMap::new();
+                for (day, parts) in member.completion_day_level {
+                    for (part, star) in parts {
+                        let key = match (day.parse(), part.parse()) {
+                            (Ok(day @ 1..=25), Ok(part @ 1..=2)) => (day, part),
+                            _ => return Err(invalid(format!("Unknown star {}.{}", day, part))),
+                        };
+                        let star = Star {
+                            time: star.get_star_ts,
+                            index: star.star_index,
+                        };
+                        stars.insert(key, star);
+                    }
+                }
+                Ok(Member {
+                    id: member.id,
+                    name: member
+                        .name
+                        .unwrap_or_else(|| format!("(anonymous user #{})", member.id)),
+                    local_score: member.local_score,
+                    stars,
+                })
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Self { year, members })
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::parse(&read_to_string(path)?)
+            .map_err(|error| invalid(format!("{}: {}", path.display(), error)))
+    }
+
+    /// Days on which anyone earned a star.
+    pub fn days(&self) -> BTreeSet<u8> {
+        self.members
+            .iter()
+            .flat_map(|member| member.stars.keys().map(|(day, _)| *day))
+            .collect()
+    }
+}
+
+/// How to turn stars into points.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, ValueEnum)]
+pub enum Scoring {
+    /// The site's local score: with N members, the first to earn each star
+    /// gets N points, the next N - 1, and so on.
+    #[default]
+    Local,
+    /// One point per star.
+    Stars,
+    /// Like local, but ranking each day by the time from part 1 to part 2.
+    Delta,
+}
+
+/// Awards N points to the first of `ranked` and one fewer to each after.
+fn award(scores: &mut [u64], members: usize, ranked: impl Iterator<Item = usize>) {
+    for (place, member) in ranked.enumerate() {
+        scores[member] += (members - place) as u64;
+    }
+}
+
+/// Every member's points under `scoring`, leaving out `skip`ped days the
+/// way the site drops days with broken puzzles.
+pub fn scores(leaderboard: &Leaderboard, scoring: Scoring, skip: &[u8]) -> Vec<u64> {
+    let members = &leaderboard.members;
+    let mut scores = vec![0; members.len()];
+    let days = (1..=25).filter(|day| !skip.contains(day));
+    for day in days {
+        match scoring {
+            Scoring::Local => {
+                for part in 1..=2 {
+                    let mut ranked: Vec<_> = (0..members.len())
+                        .filter_map(|member| {
+                            let star = members[member].star(day, part)?;
+                            Some(((star.time, star.index), member))
+                        })
+                        .collect();
+                    ranked.sort_unstable();
+                    award(
+                        &mut scores,
+                        members.len(),
+                        ranked.into_iter().map(|(_, m)| m),
+                    );
+                }
+            }
+            Scoring::Stars => {
+                for (member, score) in members.iter().zip(&mut scores) {
+                    *score += (1..=2)
+                        .filter(|part| member.star(day, *part).is_some())
+                        .count() as u64;
+                }
+            }
+            Scoring::Delta => {
+                let mut ranked: Vec<_> = (0..members.len())
+                    .filter_map(|member| Some((members[member].delta(day)?, member)))
+                    .collect();
+                ranked.sort_unstable();
+                award(
+                    &mut scores,
+                    members.len(),
+                    ranked.into_iter().map(|(_, m)| m),
+                );
+            }
+        }
+    }
+    scores
+}
+
+/// One line of the standings.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Standing<'a> {
+    pub rank: usize,
+    pub member: &'a Member,
+    pub score: u64,
+}
+
+/// Members from highest score to lowest; ties go to whoever has more stars,
+/// then to whoever earned their last star first.
+pub fn standings<'a>(
+    leaderboard: &'a Leaderboard,
+    scoring: Scoring,
+    skip: &[u8],
+) -> Vec<Standing<'a>> {
+    let scores = scores(leaderboard, scoring, skip);
+    let mut standings: Vec<_> = leaderboard.members.iter().zip(scores).collect();
+    standings.sort_by_key(|(member, score)| {
+        let last = member.stars.values().map(|star| star.time).max();
+        (
+            std::cmp::Reverse(*score),
+            std::cmp::Reverse(member.stars.len()),
+            last,
+            member.id,
+        )
+    });
+    standings
+        .into_iter()
+        .enumerate()
+        .map(|(index, (member, score))| Standing {
+            rank: index + 1,
+            member,
+            score,
+        })
+        .collect()
+}
+
+/// Members with a star on `day`, fastest to the second star first.
+fn finishers(leaderboard: &Leaderboard, day: u8) -> Vec<&Member> {
+    let mut members: Vec<_> = leaderboard
+        .members
+        .iter()
+        .filter(|member| member.star(day, 1).is_some())
+        .collect();
+    members.sort_by_key(|member| {
+        let time = |part| member.star(day, part).map(|star| (star.time, star.index));
+        (time(2).is_none(), time(2), time(1), member.id)
+    });
+    members
+}
+
+pub fn render(leaderboard: &Leaderboard, scoring: Scoring, skip: &[u8], format: Format) -> String {
+    let standings = standings(leaderboard, scoring, skip);
+    let year = leaderboard.year;
+    let since_release =
+        |day, star: Option<Star>| star.map(|star| star.time.saturating_sub(release(year, day)));
+    match format {
+        Format::Text => {
+            let width = leaderboard
+                .members
+                .iter()
+                .map(|member| member.name.chars().count())
+                .chain([6])
+                .max()
+                .unwrap_or_default();
+            let mut text = format!(
+                "{:>4}  {:<width$}  {:>5}  {:>5}  {:>6}\n",
+                "Rank", "Member", "Stars", "Score", "Site",
+            );
+            for standing in &standings {
+                text += &format!(
+                    "{:>4}  {:<width$}  {:>5}  {:>5}  {:>6}\n",
+                    standing.rank,
+                    standing.member.name,
+                    standing.member.stars.len(),
+                    standing.score,
+                    standing.member.local_score,
+                );
+            }
+            let show = |seconds: Option<u64>| seconds.map_or("-".to_string(), format_duration);
+            for day in leaderboard.days() {
+                text += &format!(
+                    "\nDay {:02} (unlocked {} UTC)\n{:<width$}  {:>10}  {:>10}  {:>10}\n",
+                    day,
+                    format_time(release(year, day)),
+                    "Member",
+                    "Part 1",
+                    "Part 2",
+                    "Delta",
+                );
+                for member in finishers(leaderboard, day) {
+                    text += &format!(
+                        "{:<width$}  {:>10}  {:>10}  {:>10}\n",
+                        member.name,
+                        show(since_release(day, member.star(day, 1))),
+                        show(since_release(day, member.star(day, 2))),
+                        show(member.delta(day)),
+                    );
+                }
+            }
+            text
+        }
+        Format::Json => {
+            let standings: Vec<_> = standings
+                .iter()
+                .map(|standing| {
+                    json!({
+                        "rank": standing.rank,
+                        "id": standing.member.id,
+                        "name": standing.member.name,
+                        "stars": standing.member.stars.len(),
+                        "score": standing.score,
+                        "site_local_score": standing.member.local_score,
+                    })
+                })
+                .collect();
+            let days: Vec<_> = leaderboard
+                .days()
+                .into_iter()
+                .map(|day| {
+                    let members: Vec<_> = finishers(leaderboard, day)
+                        .into_iter()
+                        .map(|member| {
+                            let time = |part| member.star(day, part).map(|star| star.time);
+                            json!({
+                                "id": member.id,
+                                "name": member.name,
+                                "part1": time(1),
+                                "part2": time(2),
+                                "part1_seconds": since_release(day, member.star(day, 1)),
+                                "part2_seconds": since_release(day, member.star(day, 2)),
+                                "delta_seconds": member.delta(day),
+                            })
+                        })
+                        .collect();
+                    json!({"day": day, "released": release(year, day), "members": members})
+                })
+                .collect();
+            let scoring = scoring
+                .to_possible_value()
+                .expect("Scoring is named")
+                .get_name()
+                .to_string();
+            let result = json!({
+                "year": year,
+                "scoring": scoring,
+                "skipped_days": skip,
+                "standings": standings,
+                "days": days,
+            });
+            format!("{}\n", result)
+        }
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    /// Three members: Ann finishes day 1 first, Bob is faster at part 2, and
+    /// an anonymous member only gets the first star.
+    const EXPORT: &str = r#"{
+        "event": "2024",
+        "owner_id": 1,
+        "members": {
+            "1": {"id": 1, "name": "Ann", "stars": 3, "local_score": 8,
+                  "completion_day_level": {
+                      "1": {"1": {"get_star_ts": 1733029300, "star_index": 10},
+                            "2": {"get_star_ts": 1733029900, "star_index": 30}},
+                      "2": {"1": {"get_star_ts": 1733116000, "star_index": 50}}}},
+            "2": {"id": 2, "name": "Bob", "stars": 2, "local_score": 5,
+                  "completion_day_level": {
+                      "1": {"1": {"get_star_ts": 1733029500, "star_index": 20},
+                            "2": {"get_star_ts": 1733029800, "star_index": 25}}}},
+            "3": {"id": 3, "name": null, "stars": 1, "local_score": 1,
+                  "completion_day_level": {
+                      "1": {"1": {"get_star_ts": 1733030000, "star_index": 40}}}}
+        }
+    }"#;
+
+    fn scores_by_name(scoring: Scoring, skip: &[u8]) -> Vec<(String, u64)> {
+        let leaderboard = Leaderboard::parse(EXPORT).unwrap();
+        standings(&leaderboard, scoring, skip)
+            .into_iter()
+            .map(|standing| (standing.member.name.clone(), standing.score))
+            .collect()
+    }
+
+    #[test]
+    fn it_parses_exports() {
+        let leaderboard = Leaderboard::parse(EXPORT).unwrap();
+        assert_eq!(2024, leaderboard.year);
+        assert_eq!("(anonymous user #3)", leaderboard.members[2].name);
+        assert_eq!(Some(600), leaderboard.members[0].delta(1));
+        assert_eq!(None, leaderboard.members[0].delta(2));
+        assert_eq!(BTreeSet::from([1, 2]), leaderboard.days());
+        assert!(Leaderboard::parse(r#"{"event": "soon", "members": {}}"#).is_err());
+        assert!(Leaderboard::parse(
+            r#"{"event": "2024", "members": {"1": {"id": 1, "name": "Ann",
+                "completion_day_level": {"26": {"1": {"get_star_ts": 1}}}}}}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn it_scores_by_each_rule() {
+        let local = |names: &[(&str, u64)]| {
+            names
+                .iter()
+                .map(|(name, score)| (name.to_string(), *score))
+                .collect::<Vec<_>>()
+        };
+        // The site's own scores: Ann 3 + 2 + 3, Bob 2 + 3, anonymous 1.
+        assert_eq!(
+            local(&[("Ann", 8), ("Bob", 5), ("(anonymous user #3)", 1)]),
+            scores_by_name(Scoring::Local, &[])
+        );
+        assert_eq!(
+            local(&[("Ann", 3), ("Bob", 2), ("(anonymous user #3)", 1)]),
+            scores_by_name(Scoring::Stars, &[])
+        );
+        assert_eq!(
+            local(&[("Bob", 3), ("Ann", 2), ("(anonymous user #3)", 0)]),
+            scores_by_name(Scoring::Delta, &[])
+        );
+        assert_eq!(
+            local(&[("Ann", 3), ("Bob", 0), ("(anonymous user #3)", 0)]),
+            scores_by_name(Scoring::Local, &[1])
+        );
+    }
+
+    #[test]
+    fn it_renders_tables_and_json() {
+        let leaderboard = Leaderboard::parse(EXPORT).unwrap();
+        let text = render(&leaderboard, Scoring::Local, &[], Format::Text);
+        assert!(text.starts_with(
+            "Rank  Member               Stars  Score    Site\n   1  Ann                      3      8       8\n"
+        ));
+        assert!(text.contains("\nDay 01 (unlocked 2024-12-01 05:00 UTC)\n"));
+        assert!(text.contains("\nBob                    00:05:00    00:10:00    00:05:00\n"));
+        assert!(text.contains("\n(anonymous user #3)    00:13:20           -           -\n"));
+        let json: Value =
+            serde_json::from_str(&render(&leaderboard, Scoring::Delta, &[2], Format::Json))
+                .unwrap();
+        assert_eq!("delta", json["scoring"]);
+        assert_eq!("Bob", json["standings"][0]["name"]);
+        assert_eq!(2, json["skipped_days"][0]);
+        assert_eq!("Bob", json["days"][0]["members"][0]["name"]);
+        assert_eq!(300, json["days"][0]["members"][0]["delta_seconds"]);
+        assert_eq!(Value::Null, json["days"][1]["members"][0]["part2"]);
+    }
+}
@@ -0,0 +1,32 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub mod answers;
+pub mod bench;
+pub mod compare;
+pub mod config;
+pub mod leaderboard;
+pub mod minimize;
+pub mod profile;
+pub mod progress;
+pub mod puzzle;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod serve;
+pub mod submit;
+pub mod tui;
+pub mod vault;
+pub mod verify;
+pub mod workspace;
@@ -0,0 +1,966 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use aoc::answers::{AnswerDatabase, Check};
+use aoc::bench::{self, Baseline};
+use aoc::compare;
+use aoc::config::{self, Config};
+use aoc::leaderboard::{self, Leaderboard, Scoring};
+use aoc::minimize::{self, Failure};
+use aoc::profile;
+use aoc::progress::{self, Event, Progress};
+use aoc::puzzle;
+use aoc::registry::{self, Solution};
+use aoc::report;
+use aoc::runner::{self, Format, Outcome, PartResult, Status};
+use aoc::serve::Server;
+use aoc::submit::{self, Verdict};
+use aoc::tui::Dashboard;
+use aoc::vault;
+use aoc::verify;
+use aoc::workspace;
+use clap::{Parser, Subcommand, ValueEnum};
+use common::alloc::CountingAllocator;
+use common::Arithmetic;
+use std::env::{current_dir, var_os};
+use std::fs::{create_dir_all, read_to_string, write};
+use std::io::{self, stdout, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::time::Duration;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator::new();
+
+#[derive(Parser)]
+#[command(about = "Runs and submits Advent of Code solutions")]
+struct Cli {
+    /// Year to work on; defaults to the latest year with registered days
+    #[arg(long, global = true)]
+    year: Option<u16>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run solutions against their input.txt
+    Run {
+        /// Day to run; defaults to the latest day
+        #[arg(long, conflicts_with = "all")]
+        day: Option<u8>,
+        /// Run every registered day
+        #[arg(long)]
+        all: bool,
+        /// Only run this part
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Output format; defaults to format in aoc.toml, or text
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+        /// Number type for days that support checked arithmetic: u64, u128 or big
+        #[arg(long)]
+        arithmetic: Option<Arithmetic>,
+        /// Seconds each part may run before it is reported as TIMEOUT; 0 disables the limit.
+        /// Defaults to the day's time_limit in aoc.toml, or 60
+        #[arg(long)]
+        timeout: Option<f64>,
+        /// Run this registered variant instead of the default part
+        #[arg(long)]
+        variant: Option<String>,
+        /// Solve inputs even when they fail validation
+        #[arg(long)]
+        no_validate: bool,
+        /// Explain how each answer was reached, for days that can
+        #[arg(long)]
+        explain: bool,
+        /// Most lines of explanation to show for each part
+        #[arg(long, default_value_t = 20, requires = "explain")]
+        explain_lines: usize,
+        /// Solve this file instead of the day's input.txt, without recording progress
+        #[arg(long, requires = "day")]
+        input: Option<PathBuf>,
+    },
+    /// Check computed answers against the recorded hashes and wrong guesses in answers.txt
+    Check {
+        /// Day to check; defaults to every day
+        #[arg(long)]
+        day: Option<u8>,
+        /// Output format; defaults to format in aoc.toml, or text
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+        /// Seconds each part may run before it is reported as TIMEOUT; 0 disables the limit.
+        /// Defaults to the day's time_limit in aoc.toml, or 60
+        #[arg(long)]
+        timeout: Option<f64>,
+    },
+    /// Check the shape of each day's input.txt and list every problem
+    Validate {
+        /// Day to check; defaults to the latest day
+        #[arg(long, conflicts_with = "all")]
+        day: Option<u8>,
+        /// Check every registered day
+        #[arg(long)]
+        all: bool,
+    },
+    /// Run every variant of each part and report any that disagree
+    Verify {
+        /// Day to verify; defaults to every day with variants
+        #[arg(long)]
+        day: Option<u8>,
+        /// Output format; defaults to format in aoc.toml, or text
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+        /// Seconds each variant may run before it is reported as TIMEOUT; 0 disables the limit.
+        /// Defaults to the day's time_limit in aoc.toml, or 60
+        #[arg(long)]
+        timeout: Option<f64>,
+    },
+    /// Shrink an input while a part keeps failing and write the smallest reproducer
+    Minimize {
+        #[arg(long)]
+        day: u8,
+        /// Only check this part; by default either part failing counts
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Failure the smaller input has to keep showing
+        #[arg(long, value_enum, default_value_t = Failure::Panic)]
+        failure: Failure,
+        /// Input to shrink; defaults to the day's input.txt
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// Where to write the reproducer; defaults to minimized.txt in the day's directory
+        #[arg(long)]
+        output: Option<PathBuf>,
+        #[arg(long)]
+        arithmetic: Option<Arithmetic>,
+        /// Seconds each attempt may run; a timeout only counts as the failure with --failure timeout
+        #[arg(long, default_value_t = 10.0)]
+        timeout: f64,
+    },
+    /// Measure time and heap use of each day's parser and parts
+    Profile {
+        /// Day to profile; defaults to the latest day
+        #[arg(long, conflicts_with = "all")]
+        day: Option<u8>,
+        /// Profile every registered day
+        #[arg(long)]
+        all: bool,
+        /// Output format; defaults to format in aoc.toml, or text
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+        /// Seconds each phase may run before it is reported as timed out; 0 disables the limit.
+        /// Defaults to the day's time_limit in aoc.toml, or 60
+        #[arg(long)]
+        timeout: Option<f64>,
+    },
+    /// Time repeated runs of each part and save them to baseline.json
+    Bench {
+        /// Day to benchmark; defaults to the latest day
+        #[arg(long, conflicts_with = "all")]
+        day: Option<u8>,
+        /// Benchmark every registered day
+        #[arg(long)]
+        all: bool,
+        /// Timed runs of each part, after one untimed run
+        #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+        samples: u32,
+        /// Output format; defaults to format in aoc.toml, or text
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+        /// Seconds the untimed run may take before the part is skipped; 0 disables the limit.
+        /// Defaults to the day's time_limit in aoc.toml, or 60
+        #[arg(long)]
+        timeout: Option<f64>,
+    },
+    /// Benchmark two git revisions on the same inputs and compare them
+    BenchCompare {
+        /// Revision to compare against, such as main
+        before: String,
+        /// Revision to check, such as a feature branch
+        after: String,
+        /// Timed runs of each part at each revision
+        #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u32).range(2..))]
+        samples: u32,
+        /// Percent slower a part or day may get before the comparison fails.
+        /// Defaults to bench_threshold in aoc.toml, or 5
+        #[arg(long)]
+        threshold: Option<f64>,
+        /// Largest p-value of Welch's t-test that counts as a real change
+        #[arg(long, default_value_t = 0.05)]
+        alpha: f64,
+        /// Output format; defaults to format in aoc.toml, or text
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+        /// Seconds the untimed run may take before the part is skipped; 0 disables the limit.
+        /// Defaults to each revision's own default
+        #[arg(long)]
+        timeout: Option<f64>,
+    },
+    /// Write a static HTML report of every day's answers, timings and visualizations
+    Report {
+        /// Directory to write index.html and the day pages to
+        #[arg(long, default_value = "site")]
+        out: PathBuf,
+        /// Seconds each part may run before it is reported as TIMEOUT; 0 disables the limit.
+        /// Defaults to the day's time_limit in aoc.toml, or 60
+        #[arg(long)]
+        timeout: Option<f64>,
+    },
+    /// Serve the solutions over HTTP on localhost
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        address: String,
+        /// Seconds each request may run before it is answered with a timeout; 0 disables the
+        /// limit. Defaults to time_limit in aoc.toml, or 60
+        #[arg(long)]
+        timeout: Option<f64>,
+    },
+    /// Browse every day's stars, answers, run times and tests in the terminal
+    Tui {
+        /// Seconds each part may run before it is reported as TIMEOUT; 0 disables the limit.
+        /// Defaults to the day's time_limit in aoc.toml, or 60
+        #[arg(long)]
+        timeout: Option<f64>,
+    },
+    /// Encrypt each day's input.txt into input.txt.enc so it can be committed
+    Encrypt {
+        /// Day to encrypt; defaults to every day with an input.txt
+        #[arg(long)]
+        day: Option<u8>,
+    },
+    /// Restore each day's input.txt from its committed input.txt.enc
+    Decrypt {
+        /// Day to decrypt; defaults to every day with an input.txt.enc
+        #[arg(long)]
+        day: Option<u8>,
+    },
+    /// Show the year's stars as a calendar, with when each part was solved
+    Status {
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+    },
+    /// Analyze a private leaderboard saved from its JSON link
+    Leaderboard {
+        /// The saved export
+        file: PathBuf,
+        #[arg(long, value_enum, default_value_t = Scoring::Local)]
+        scoring: Scoring,
+        /// Day to leave out of the scores, as the site does for broken puzzles
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        skip_day: Vec<u8>,
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+    },
+    /// Download a day's input.txt and puzzle page with the session cookie from aoc.toml
+    Fetch {
+        #[arg(long)]
+        day: u8,
+        /// Replace an input that is already there
+        #[arg(long)]
+        force: bool,
+    },
+    /// Read a day's puzzle from the page saved by fetch, without going online
+    Show {
+        #[arg(long)]
+        day: u8,
+        /// Mark emphasis and code with * and ` instead of colors
+        #[arg(long)]
+        plain: bool,
+    },
+    /// Show the settings from aoc.toml after filling in defaults
+    Config,
+    /// Submit an answer and record it when it is correct
+    Submit {
+        #[arg(long)]
+        day: u8,
+        /// Part to submit; defaults to the first part without a known answer
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+    },
+}
+
+fn find_solution(year: u16, day: u8) -> Solution {
+    registry::find(year, day).unwrap_or_else(|| {
+        eprintln!("Day {} of {} is not registered", day, year);
+        exit(1);
+    })
+}
+
+fn select_solutions(year: u16, day: Option<u8>, all: bool) -> Vec<Solution> {
+    let solutions = registry::solutions_in(year);
+    if solutions.is_empty() {
+        eprintln!("No days of {} are registered", year);
+        exit(1);
+    }
+    match (day, all) {
+        (_, true) => solutions,
+        (Some(day), false) => vec![find_solution(year, day)],
+        (None, false) => solutions.last().copied().into_iter().collect(),
+    }
+}
+
+fn time_limit(timeout: f64) -> Option<Duration> {
+    (timeout > 0.0).then(|| Duration::from_secs_f64(timeout))
+}
+
+/// Prints every problem with a day's input, returning whether it had none.
+fn report_problems(solution: &Solution, input: &str) -> bool {
+    let problems = (solution.validate)(input);
+    if !problems.is_empty() {
+        eprintln!("Day {:02} input is invalid:", solution.day);
+        for problem in &problems {
+            eprintln!("  {}", problem);
+        }
+    }
+    problems.is_empty()
+}
+
+/// Lists the days of `year` to encrypt or decrypt whose file named by `path`
+/// is in `inputs`.
+fn select_days(
+    root: &Path,
+    inputs: &Path,
+    year: u16,
+    day: Option<u8>,
+    path: fn(&Path, u16, u8) -> PathBuf,
+) -> Vec<u8> {
+    match day {
+        Some(day) => vec![day],
+        None => vault::days(root, year)
+            .expect("Unable to list days")
+            .into_iter()
+            .filter(|day| path(inputs, year, *day).is_file())
+            .collect(),
+    }
+}
+
+fn encrypted_input_path(inputs: &Path, year: u16, day: u8) -> PathBuf {
+    vault::encrypted_path(&workspace::input_path(inputs, year, day))
+}
+
+fn read_input(root: &Path, inputs: &Path, year: u16, day: u8) -> Option<String> {
+    vault::read_input(root, inputs, year, day).unwrap_or_else(|error| {
+        eprintln!("Unable to read day {:02} input: {}", day, error);
+        None
+    })
+}
+
+fn load_progress(root: &Path, year: u16) -> Progress {
+    Progress::load(&workspace::progress_path(root, year)).expect("Unable to read progress")
+}
+
+/// Reads the year's answers.txt, hashing any plain answers and guesses in it
+/// first.
+fn load_answers(root: &Path, year: u16) -> AnswerDatabase {
+    let mut database =
+        AnswerDatabase::load(&workspace::answers_path(root, year)).expect("Unable to read answers");
+    if database.has_plain_answers() {
+        database.save().expect("Unable to save answers");
+        eprintln!("Replaced the plain answers and guesses in answers.txt with salted hashes");
+    }
+    database
+}
+
+/// Notes what each run showed in the year's progress.
+fn record_results(root: &Path, year: u16, results: &[PartResult]) {
+    progress::record_results(&workspace::progress_path(root, year), results)
+        .expect("Unable to save progress");
+}
+
+/// Downloads a day's puzzle page beside its input.
+fn save_puzzle(inputs: &Path, year: u16, day: u8, cookie: &str) -> io::Result<PathBuf> {
+    let page = submit::fetch_puzzle(year, day, cookie)?;
+    let path = workspace::puzzle_path(inputs, year, day);
+    write(&path, page)?;
+    Ok(path)
+}
+
+fn require_cookie(config: &Config) -> String {
+    config.cookie().unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        exit(1);
+    })
+}
+
+fn require_secret(root: &Path) -> Vec<u8> {
+    vault::require_secret(root).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        exit(1);
+    })
+}
+
+#[cfg(not(tarpaulin_include))]
+fn main() {
+    let cli = Cli::parse();
+    let root = workspace::find_root(&current_dir().expect("Unable to read current directory"))
+        .expect("Unable to find workspace root");
+    let config = Config::load(&root).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        exit(1);
+    });
+    let year = cli
+        .year
+        .or(config.year)
+        .unwrap_or_else(registry::latest_year);
+    let inputs = config.inputs(&root);
+    match cli.command {
+        Command::Run {
+            day,
+            all,
+            part,
+            format,
+            arithmetic,
+            timeout,
+            variant,
+            no_validate,
+            explain,
+            explain_lines,
+            input: input_path,
+        } => {
+            let database = load_answers(&root, year);
+            let mut results = Vec::new();
+            let mut explanations = Vec::new();
+            for solution in select_solutions(year, day, all) {
+                let input = match &input_path {
+                    Some(path) => Some(read_to_string(path).unwrap_or_else(|error| {
+                        eprintln!("Unable to read {}: {}", path.display(), error);
+                        exit(1);
+                    })),
+                    None => read_input(&root, &inputs, year, solution.day),
+                };
+                let Some(input) = input else {
+                    eprintln!("Skipping day {:02}: no input.txt", solution.day);
+                    continue;
+                };
+                let limit = config.time_limit(year, solution.day, timeout);
+                if !no_validate && !report_problems(&solution, &input) {
+                    eprintln!(
+                        "Skipping day {:02}; pass --no-validate to solve it anyway",
+                        solution.day
+                    );
+                    continue;
+                }
+                if explain && solution.explain.is_none() {
+                    eprintln!("Day {:02} has no explanation", solution.day);
+                }
+                if arithmetic.is_some() && solution.checked.is_none() {
+                    eprintln!(
+                        "Day {:02} has no checked arithmetic; running it as is",
+                        solution.day
+                    );
+                }
+                for current_part in part.map_or(vec![1, 2], |part| vec![part]) {
+                    let name = variant.as_deref().unwrap_or(registry::DEFAULT_VARIANT);
+                    let Some(solution) = solution.with_variant(current_part, name) else {
+                        eprintln!(
+                            "Skipping day {:02} part {}: no variant {:?}",
+                            solution.day, current_part, name
+                        );
+                        continue;
+                    };
+                    results.push(runner::solve_part(
+                        &database,
+                        &solution,
+                        current_part,
+                        &input,
+                        arithmetic.unwrap_or_default(),
+                        limit,
+                    ));
+                    explanations.push((explain && solution.explain.is_some()).then(|| {
+                        runner::explain_part(&solution, current_part, &input, explain_lines, limit)
+                    }));
+                }
+            }
+            print!(
+                "{}",
+                runner::render_explained(&results, &explanations, config.format(format))
+            );
+            if input_path.is_none() {
+                record_results(&root, year, &results);
+            }
+        }
+        Command::Check {
+            day,
+            format,
+            timeout,
+        } => {
+            let database = load_answers(&root, year);
+            let mut results = Vec::new();
+            for solution in select_solutions(year, day, day.is_none()) {
+                let Some(input) = read_input(&root, &inputs, year, solution.day) else {
+                    eprintln!("Skipping day {:02}: no input.txt", solution.day);
+                    continue;
+                };
+                let limit = config.time_limit(year, solution.day, timeout);
+                for part in 1..=2 {
+                    results.push(runner::solve_part(
+                        &database,
+                        &solution,
+                        part,
+                        &input,
+                        Arithmetic::default(),
+                        limit,
+                    ));
+                }
+            }
+            print!("{}", runner::render(&results, config.format(format)));
+            record_results(&root, year, &results);
+            let failed = results
+                .iter()
+                .any(|result| !matches!(result.status(), Status::Correct | Status::Unverified));
+            if failed {
+                exit(1);
+            }
+        }
+        Command::Validate { day, all } => {
+            let mut valid = true;
+            for solution in select_solutions(year, day, all) {
+                let Some(input) = read_input(&root, &inputs, year, solution.day) else {
+                    eprintln!("Skipping day {:02}: no input.txt", solution.day);
+                    continue;
+                };
+                if report_problems(&solution, &input) {
+                    println!("Day {:02} input is valid", solution.day);
+                } else {
+                    valid = false;
+                }
+            }
+            if !valid {
+                exit(1);
+            }
+        }
+        Command::Verify {
+            day,
+            format,
+            timeout,
+        } => {
+            let solutions = match day {
+                Some(day) => vec![find_solution(year, day)],
+                None => registry::solutions_in(year)
+                    .into_iter()
+                    .filter(|solution| !solution.variants.is_empty())
+                    .collect(),
+            };
+            let mut verifications = Vec::new();
+            for solution in solutions {
+                let Some(input) = read_input(&root, &inputs, year, solution.day) else {
+                    eprintln!("Skipping day {:02}: no input.txt", solution.day);
+                    continue;
+                };
+                let limit = config.time_limit(year, solution.day, timeout);
+                for part in 1..=2 {
+                    if solution.variant_names(part).len() > 1 {
+                        verifications.push(verify::verify_part(
+                            &solution,
+                            part,
+                            &input,
+                            Arithmetic::default(),
+                            limit,
+                        ));
+                    }
+                }
+            }
+            print!("{}", verify::render(&verifications, config.format(format)));
+            if !verifications.iter().all(verify::Verification::agrees) {
+                exit(1);
+            }
+        }
+        Command::Minimize {
+            day,
+            part,
+            failure,
+            input,
+            output,
+            arithmetic,
+            timeout,
+        } => {
+            let solution = find_solution(year, day);
+            let input_path = input.unwrap_or_else(|| workspace::input_path(&inputs, year, day));
+            let input = read_to_string(&input_path).unwrap_or_else(|error| {
+                eprintln!("Unable to read {}: {}", input_path.display(), error);
+                exit(1);
+            });
+            let parts = part.map_or(vec![1, 2], |part| vec![part]);
+            let arithmetic = arithmetic.unwrap_or_default();
+            let limit = time_limit(timeout);
+            // Only keep candidates as well-formed as the input we started with.
+            let must_be_valid = (solution.validate)(&input).is_empty();
+            let Some(expected) = failure.observe(&solution, &parts, &input, arithmetic, limit)
+            else {
+                eprintln!("{} does not show that failure", input_path.display());
+                exit(1);
+            };
+            println!("Shrinking while {}", expected);
+            // Every attempt that still fails would print a panic message.
+            std::panic::set_hook(Box::new(|_| {}));
+            let mut attempts = 0;
+            let mut fails = |candidate: &str| {
+                attempts += 1;
+                (!must_be_valid || (solution.validate)(candidate).is_empty())
+                    && Some(&expected)
+                        == failure
+                            .observe(&solution, &parts, candidate, arithmetic, limit)
+                            .as_ref()
+            };
+            let minimized = minimize::minimize(solution.shape, &input, &mut fails);
+            let output = output.unwrap_or_else(|| {
+                workspace::day_directory(&root, year, day).join("minimized.txt")
+            });
+            write(&output, &minimized).expect("Unable to write reproducer");
+            println!(
+                "Shrank {} lines to {} in {} attempts; wrote {}",
+                input.lines().count(),
+                minimized.lines().count(),
+                attempts,
+                output.display()
+            );
+        }
+        Command::Profile {
+            day,
+            all,
+            format,
+            timeout,
+        } => {
+            let mut profiles = Vec::new();
+            for solution in select_solutions(year, day, all) {
+                let Some(input) = read_input(&root, &inputs, year, solution.day) else {
+                    eprintln!("Skipping day {:02}: no input.txt", solution.day);
+                    continue;
+                };
+                let limit = config.time_limit(year, solution.day, timeout);
+                profiles.extend(profile::profile_day(
+                    &ALLOCATOR,
+                    &solution,
+                    &input,
+                    Arithmetic::default(),
+                    limit,
+                ));
+            }
+            print!("{}", profile::render(&profiles, config.format(format)));
+        }
+        Command::Bench {
+            day,
+            all,
+            samples,
+            format,
+            timeout,
+        } => {
+            let mut baseline = Baseline::load(&workspace::baseline_path(&root, year))
+                .expect("Unable to read baseline");
+            let mut benchmarks = Vec::new();
+            for solution in select_solutions(year, day, all) {
+                let Some(input) = read_input(&root, &inputs, year, solution.day) else {
+                    eprintln!("Skipping day {:02}: no input.txt", solution.day);
+                    continue;
+                };
+                if !report_problems(&solution, &input) {
+                    continue;
+                }
+                let limit = config.time_limit(year, solution.day, timeout);
+                for part in 1..=2 {
+                    match bench::bench_part(&solution, part, &input, samples as usize, limit) {
+                        Ok(benchmark) => benchmarks.push(benchmark),
+                        Err(outcome) => {
+                            eprintln!(
+                                "Skipping day {:02} part {}: {}",
+                                solution.day, part, outcome
+                            )
+                        }
+                    }
+                }
+            }
+            print!("{}", bench::render(&benchmarks, config.format(format)));
+            for benchmark in benchmarks {
+                baseline.insert(benchmark);
+            }
+            baseline.save().expect("Unable to save baseline");
+        }
+        Command::BenchCompare {
+            before,
+            after,
+            samples,
+            threshold,
+            alpha,
+            format,
+            timeout,
+        } => {
+            let days: Vec<_> = vault::days(&root, year)
+                .expect("Unable to list days")
+                .into_iter()
+                .filter_map(|day| Some((day, read_input(&root, &inputs, year, day)?)))
+                .collect();
+            let mut arguments = vec!["--samples".to_string(), samples.to_string()];
+            if let Some(timeout) = timeout {
+                arguments.extend(["--timeout".to_string(), timeout.to_string()]);
+            }
+            let scratch = config.cache(&root).join("bench-compare");
+            let [before, after] = [&before, &after].map(|revision| {
+                compare::bench_revision(&root, &scratch, revision, year, &days, &arguments)
+                    .unwrap_or_else(|error| {
+                        eprintln!("Unable to benchmark {}: {}", revision, error);
+                        exit(1);
+                    })
+            });
+            let comparisons = compare::compare(&before, &after);
+            let threshold = config.bench_threshold(threshold);
+            let format = config.format(format);
+            print!(
+                "{}",
+                compare::render(&comparisons, threshold, alpha, format)
+            );
+            if comparisons
+                .iter()
+                .any(|comparison| comparison.is_regression(threshold, alpha))
+            {
+                eprintln!("Some parts got more than {}% slower", threshold);
+                exit(1);
+            }
+        }
+        Command::Report { out, timeout } => {
+            let database = load_answers(&root, year);
+            let baseline = Baseline::load(&workspace::baseline_path(&root, year))
+                .expect("Unable to read baseline");
+            let days: Vec<_> = registry::solutions_in(year)
+                .iter()
+                .map(|solution| {
+                    eprintln!("Reporting on day {:02}", solution.day);
+                    report::day_report(
+                        &ALLOCATOR, &root, &config, &database, &baseline, solution, timeout,
+                    )
+                })
+                .collect();
+            report::write_site(&out, year, &days).expect("Unable to write report");
+            println!("Wrote {}", out.join("index.html").display());
+        }
+        Command::Serve { address, timeout } => {
+            let limit = config.default_time_limit(timeout);
+            let server = Server::bind(&address, limit).unwrap_or_else(|error| {
+                eprintln!("Unable to listen on {}: {}", address, error);
+                exit(1);
+            });
+            eprintln!("Listening on http://{}", server.address());
+            server.run();
+        }
+        Command::Tui { timeout } => {
+            let database = load_answers(&root, year);
+            let dashboard = Dashboard::new(&root, year, config, database, timeout);
+            let mut terminal = ratatui::init();
+            // Panics in solver workers show up on the dashboard; only a
+            // panic in the dashboard itself should restore the terminal.
+            let restore = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                let worker = std::thread::current()
+                    .name()
+                    .is_some_and(|name| name.starts_with("day-"));
+                if !worker {
+                    restore(info);
+                }
+            }));
+            let result = dashboard.run(&mut terminal);
+            ratatui::restore();
+            result.expect("Unable to draw the dashboard");
+        }
+        Command::Encrypt { day } => {
+            let secret = require_secret(&root);
+            for day in select_days(&root, &inputs, year, day, workspace::input_path) {
+                match vault::encrypt_file(&secret, &workspace::input_path(&inputs, year, day)) {
+                    Ok(path) => println!("Encrypted day {:02} into {}", day, path.display()),
+                    Err(error) => {
+                        eprintln!("Unable to encrypt day {:02} input: {}", day, error);
+                        exit(1);
+                    }
+                }
+            }
+        }
+        Command::Decrypt { day } => {
+            let secret = require_secret(&root);
+            for day in select_days(&root, &inputs, year, day, encrypted_input_path) {
+                let path = workspace::input_path(&inputs, year, day);
+                if let Err(error) = vault::decrypt_file(&secret, &path) {
+                    eprintln!("Unable to decrypt day {:02} input: {}", day, error);
+                    exit(1);
+                }
+                println!("Decrypted day {:02} into {}", day, path.display());
+            }
+        }
+        Command::Status { format } => {
+            let database = load_answers(&root, year);
+            let progress = load_progress(&root, year);
+            let format = config.format(format);
+            print!(
+                "{}",
+                progress::render(year, &database, &progress, progress::now(), format)
+            );
+        }
+        Command::Leaderboard {
+            file,
+            scoring,
+            skip_day,
+            format,
+        } => {
+            let board = Leaderboard::load(&file).unwrap_or_else(|error| {
+                eprintln!("Unable to read leaderboard: {}", error);
+                exit(1);
+            });
+            let format = config.format(format);
+            print!(
+                "{}",
+                leaderboard::render(&board, scoring, &skip_day, format)
+            );
+        }
+        Command::Fetch { day, force } => {
+            let cookie = require_cookie(&config);
+            create_dir_all(workspace::day_directory(&inputs, year, day))
+                .expect("Unable to create the input directory");
+            let path = workspace::input_path(&inputs, year, day);
+            if path.is_file() && !force {
+                println!("Keeping {}; pass --force to replace it", path.display());
+            } else {
+                let input = submit::fetch_input(year, day, &cookie).unwrap_or_else(|error| {
+                    eprintln!("Unable to fetch day {} of {}: {}", day, year, error);
+                    exit(1);
+                });
+                write(&path, input).expect("Unable to write input");
+                println!("Fetched day {:02} into {}", day, path.display());
+            }
+            match save_puzzle(&inputs, year, day, &cookie) {
+                Ok(path) => println!("Saved day {:02} puzzle into {}", day, path.display()),
+                Err(error) => {
+                    eprintln!("Unable to save day {} of {} puzzle: {}", day, year, error);
+                    exit(1);
+                }
+            }
+        }
+        Command::Show { day, plain } => {
+            let path = workspace::puzzle_path(&inputs, year, day);
+            let page = read_to_string(&path).unwrap_or_else(|error| {
+                eprintln!(
+                    "Unable to read {}: {}; save it with aoc fetch --day {}",
+                    path.display(),
+                    error,
+                    day
+                );
+                exit(1);
+            });
+            let color = !plain && stdout().is_terminal() && var_os("NO_COLOR").is_none();
+            print!("{}", puzzle::render(&page, color));
+            if puzzle::is_missing_part2(&page) && load_answers(&root, year).is_solved(day, 1) {
+                eprintln!(
+                    "This page was saved before part 1 was solved; run aoc fetch --day {} for part 2",
+                    day
+                );
+            }
+        }
+        Command::Config => {
+            for file in [Config::user_path(), Some(root.join(config::FILE_NAME))]
+                .into_iter()
+                .flatten()
+            {
+                let state = if file.is_file() { "read" } else { "missing" };
+                println!("{:<12}{} ({})", "file", file.display(), state);
+            }
+            println!("{:<12}{}", "year", year);
+            println!("{:<12}{}", "inputs", inputs.display());
+            println!("{:<12}{}", "cache", config.cache(&root).display());
+            let format = config.format(None).to_possible_value();
+            println!(
+                "{:<12}{}",
+                "format",
+                format.expect("Format is named").get_name()
+            );
+            match config.default_time_limit(None) {
+                Some(limit) => println!("{:<12}{:?}", "time limit", limit),
+                None => println!("{:<12}none", "time limit"),
+            }
+            match config.default_budget(None) {
+                Some(budget) => println!("{:<12}{:?}", "budget", budget),
+                None => println!("{:<12}none", "budget"),
+            }
+            println!("{:<12}{}%", "threshold", config.bench_threshold(None));
+            println!(
+                "{:<12}{}",
+                "cookie",
+                config.cookie.clone().unwrap_or_default()
+            );
+            for ((year, day), settings) in &config.days {
+                if let Some(seconds) = settings.time_limit {
+                    println!("{:<12}{} day {:02}: {}s", "time limit", year, day, seconds);
+                }
+                if let Some(seconds) = settings.budget {
+                    println!("{:<12}{} day {:02}: {}s", "budget", year, day, seconds);
+                }
+            }
+        }
+        Command::Submit { day, part } => {
+            let mut database = load_answers(&root, year);
+            let solution = find_solution(year, day);
+            let part = part.unwrap_or_else(|| {
+                (1..=2)
+                    .find(|part| !database.is_solved(day, *part))
+                    .unwrap_or_else(|| {
+                        eprintln!("Both parts of day {} are already solved", day);
+                        exit(1);
+                    })
+            });
+            let input = read_input(&root, &inputs, year, day).unwrap_or_else(|| {
+                eprintln!("Day {:02} has no input.txt", day);
+                exit(1);
+            });
+            let (outcome, _) =
+                runner::run_part(&solution, part, &input, Arithmetic::default(), None);
+            let Outcome::Solved(answer) = outcome else {
+                eprintln!("Day {} part {} failed: {}", day, part, outcome);
+                exit(1);
+            };
+            let check = database.check(day, part, &answer);
+            if Check::Unknown != check {
+                eprintln!(
+                    "Not submitting day {} part {}: earlier submissions show {} is {}",
+                    day,
+                    part,
+                    answer,
+                    check.label()
+                );
+                exit(1);
+            }
+            let cookie = require_cookie(&config);
+            println!("Submitting day {} part {}: {}", day, part, answer);
+            let verdict =
+                submit::submit(year, day, part, &answer, &cookie).expect("Unable to submit answer");
+            println!("{:?}", verdict);
+            match verdict {
+                Verdict::Correct => {
+                    database.insert(day, part, &answer);
+                    if part == 1 {
+                        if let Err(error) = save_puzzle(&inputs, year, day, &cookie) {
+                            eprintln!("Unable to save the puzzle with part 2: {}", error);
+                        }
+                    }
+                    let mut progress = load_progress(&root, year);
+                    let now = progress::now();
+                    progress.record(day, part, Event::Solved, now);
+                    progress.record(day, part, Event::Verified, now);
+                    progress.record(day, part, Event::Submitted, now);
+                    progress.save().expect("Unable to save progress");
+                }
+                Verdict::TooHigh => database.insert_too_high(day, part, &answer),
+                Verdict::TooLow => database.insert_too_low(day, part, &answer),
+                _ => return,
+            }
+            database.save().expect("Unable to save answers");
+        }
+    }
+}
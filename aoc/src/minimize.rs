@@ -0,0 +1,402 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::registry::{Shape, Solution};
+use crate::runner::{run_part, Outcome};
+use crate::verify::verify_part;
+use clap::ValueEnum;
+use common::Arithmetic;
+use std::env::{current_exe, temp_dir};
+use std::fs::{remove_file, write};
+use std::io::{self, Read};
+use std::process::{self, Child, Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// How long past its limit an `aoc run` may take to start and report.
+const STARTUP: Duration = Duration::from_secs(5);
+
+/// Numbers the candidate files written for `aoc run` so none are reused.
+static CANDIDATES: AtomicUsize = AtomicUsize::new(0);
+
+/// What has to keep happening for a smaller input to count as a reproducer.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, ValueEnum)]
+pub enum Failure {
+    /// A part panics.
+    Panic,
+    /// A part runs past the time limit.
+    Timeout,
+    /// A part's variants disagree.
+    Mismatch,
+}
+
+impl Failure {
+    /// Describes how `input` shows this failure on the first of `parts` that
+    /// does, or `None` if none do.
+    ///
+    /// A panic is described by its message, so a smaller input that panics
+    /// somewhere else is not mistaken for the same bug. A timeout is run in
+    /// its own `aoc run` that exits at the limit, since a thread past it
+    /// can't be stopped and every slow candidate would keep a core busy until
+    /// the search ends.
+    pub fn observe(
+        &self,
+        solution: &Solution,
+        parts: &[u8],
+        input: &str,
+        arithmetic: Arithmetic,
+        limit: Option<Duration>,
+    ) -> Option<String> {
+        parts.iter().find_map(|&part| match self {
+            Self::Panic => match run_part(solution, part, input, arithmetic, limit).0 {
+                Outcome::Panicked(message) => Some(format!("part {} panicked: {}", part, message)),
+                _ => None,
+            },
+            Self::Timeout => limit
+                .filter(|&limit| {
+                    times_out(solution, part, input, arithmetic, limit)
+                        .expect("Unable to run a candidate")
+                })
+                .map(|_| format!("part {} timed out", part)),
+            Self::Mismatch => (!verify_part(solution, part, input, arithmetic, limit).agrees())
+                .then(|| format!("part {} variants disagree", part)),
+        })
+    }
+}
+
+/// Runs `part` of `solution` on `input` in a fresh `aoc run` that times
+/// itself, so starting the process doesn't count against `limit`, and
+/// returns whether it timed out.
+///
+/// One that is still going `STARTUP` after the limit is killed and counted
+/// as timed out too.
+fn times_out(
+    solution: &Solution,
+    part: u8,
+    input: &str,
+    arithmetic: Arithmetic,
+    limit: Duration,
+) -> io::Result<bool> {
+    let path = temp_dir().join(format!(
+        "aoc-minimize-{}-{}.txt",
+        process::id(),
+        CANDIDATES.fetch_add(1, Ordering::Relaxed)
+    ));
+    write(&path, input)?;
+    let timed_out = Command::new(current_exe()?)
+        .args(["run", "--no-validate", "--format", "json"])
+        .args(["--timeout", &limit.as_secs_f64().to_string()])
+        .args(["--year", &solution.year.to_string()])
+        .args(["--day", &solution.day.to_string()])
+        .args(["--part", &part.to_string()])
+        .args(["--arithmetic", &arithmetic.to_string()])
+        .arg("--input")
+        .arg(&path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .and_then(|mut child| {
+            if wait_or_kill(&mut child, limit + STARTUP)? {
+                return Ok(true);
+            }
+            let mut output = String::new();
+            if let Some(mut stdout) = child.stdout.take() {
+                stdout.read_to_string(&mut output)?;
+            }
+            let results: serde_json::Value = serde_json::from_str(&output).unwrap_or_default();
+            Ok(results[0]["status"] == "timeout")
+        });
+    remove_file(&path)?;
+    timed_out
+}
+
+/// Waits up to `limit` for `child` to exit, killing it if it doesn't, and
+/// returns whether it was killed.
+fn wait_or_kill(child: &mut Child, limit: Duration) -> io::Result<bool> {
+    let deadline = Instant::now() + limit;
+    while child.try_wait()?.is_none() {
+        if Instant::now() >= deadline {
+            child.kill()?;
+            child.wait()?;
+            return Ok(true);
+        }
+        sleep(Duration::from_millis(5));
+    }
+    Ok(false)
+}
+
+/// Delta debugging: repeatedly drops chunks of `units`, halving the chunk
+/// size when nothing can go, and keeps any smaller list that still `fails`.
+///
+/// The result is 1-minimal: removing any single unit makes it pass.
+pub fn ddmin<T: Clone>(mut units: Vec<T>, fails: &mut impl FnMut(&[T]) -> bool) -> Vec<T> {
+    let mut granularity = 2;
+    while !units.is_empty() {
+        let chunk = units.len().div_ceil(granularity);
+        let reduced = (0..units.len()).step_by(chunk).find_map(|start| {
+            let mut complement = units[..start].to_vec();
+            complement.extend_from_slice(&units[(start + chunk).min(units.len())..]);
+            fails(&complement).then_some(complement)
+        });
+        match reduced {
+            Some(complement) => {
+                units = complement;
+                granularity = (granularity - 1).max(2);
+            }
+            None if chunk == 1 => break,
+            None => granularity = (granularity * 2).min(units.len()),
+        }
+    }
+    units
+}
+
+fn join_lines(lines: &[String]) -> String {
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+/// Minimizes each line's pieces in turn, holding every other line fixed.
+fn minimize_within_lines(
+    lines: Vec<String>,
+    split: fn(&str) -> Vec<String>,
+    separator: &str,
+    fails: &mut impl FnMut(&str) -> bool,
+) -> Vec<String> {
+    let mut lines = lines;
+    for index in 0..lines.len() {
+        let pieces = split(&lines[index]);
+        let kept = ddmin(pieces, &mut |pieces: &[String]| {
+            let mut candidate = lines.clone();
+            candidate[index] = pieces.join(separator);
+            fails(&join_lines(&candidate))
+        });
+        lines[index] = kept.join(separator);
+    }
+    lines
+}
+
+fn minimize_columns(rows: Vec<String>, fails: &mut impl FnMut(&str) -> bool) -> Vec<String> {
+    let grid: Vec<Vec<char>> = rows.iter().map(|row| row.chars().collect()).collect();
+    let width = grid.iter().map(Vec::len).min().unwrap_or(0);
+    let build = |columns: &[usize]| -> Vec<String> {
+        grid.iter()
+            .map(|row| columns.iter().map(|&column| row[column]).collect())
+            .collect()
+    };
+    let columns = ddmin((0..width).collect(), &mut |columns: &[usize]| {
+        fails(&join_lines(&build(columns)))
+    });
+    build(&columns)
+}
+
+fn minimize_sections(input: &str, fails: &mut impl FnMut(&str) -> bool) -> String {
+    let render = |lines: &[(usize, String)]| -> String {
+        let sections = lines.last().map_or(0, |(section, _)| section + 1);
+        let text = (0..sections)
+            .map(|section| {
+                lines
+                    .iter()
+                    .filter(|(line_section, _)| *line_section == section)
+                    .map(|(_, line)| line.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        format!("{}\n", text)
+    };
+    let lines: Vec<(usize, String)> = input
+        .trim()
+        .split("\n\n")
+        .enumerate()
+        .flat_map(|(section, text)| text.lines().map(move |line| (section, line.to_string())))
+        .collect();
+    let sections = lines.last().map_or(0, |(section, _)| section + 1);
+    let kept = ddmin(lines, &mut |lines: &[(usize, String)]| {
+        // Dropping a whole section would merge its neighbours.
+        let kept_sections = (0..sections).all(|section| {
+            lines
+                .iter()
+                .any(|(line_section, _)| *line_section == section)
+        });
+        kept_sections && fails(&render(lines))
+    });
+    render(&kept)
+}
+
+/// Shrinks `input` along `shape` while `fails` holds, repeating until a
+/// whole round removes nothing.
+///
+/// `fails` must hold for `input` itself.
+pub fn minimize(shape: Shape, input: &str, fails: &mut impl FnMut(&str) -> bool) -> String {
+    let mut current = input.to_string();
+    loop {
+        let lines: Vec<String> = current.lines().map(str::to_string).collect();
+        let next = match shape {
+            Shape::Sections => minimize_sections(&current, fails),
+            _ => {
+                let lines = ddmin(lines, &mut |lines: &[String]| fails(&join_lines(lines)));
+                let lines = match shape {
+                    Shape::Tokens => minimize_within_lines(
+                        lines,
+                        |line| line.split_whitespace().map(str::to_string).collect(),
+                        " ",
+                        fails,
+                    ),
+                    Shape::Characters => minimize_within_lines(
+                        lines,
+                        |line| line.chars().map(String::from).collect(),
+                        "",
+                        fails,
+                    ),
+                    Shape::Grid => minimize_columns(lines, fails),
+                    Shape::Lines | Shape::Sections => lines,
+                };
+                join_lines(&lines)
+            }
+        };
+        if next.len() >= current.len() {
+            return if fails(&next) { next } else { current };
+        }
+        current = next;
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use common::Answer;
+
+    #[test]
+    fn it_finds_minimal_subsets() {
+        let mut probes = 0;
+        let kept = ddmin((1..=20).collect(), &mut |units: &[u32]| {
+            probes += 1;
+            units.contains(&3) && units.contains(&17)
+        });
+        assert_eq!(vec![3, 17], kept);
+        assert!(probes < 100);
+        assert_eq!(
+            Vec::<u32>::new(),
+            ddmin(vec![1, 2, 3], &mut |_: &[u32]| true)
+        );
+    }
+
+    #[test]
+    fn it_minimizes_lines() {
+        let input = "1\n2\nboom\n4\n5\n";
+        assert_eq!(
+            "boom\n",
+            minimize(Shape::Lines, input, &mut |text: &str| text.contains("boom"))
+        );
+    }
+
+    #[test]
+    fn it_minimizes_tokens() {
+        let input = "1 2 3\n4 99 6\n7 8 9\n";
+        let minimized = minimize(Shape::Tokens, input, &mut |text: &str| {
+            text.split_whitespace().any(|token| "99" == token)
+        });
+        assert_eq!("99\n", minimized);
+    }
+
+    #[test]
+    fn it_keeps_grids_rectangular() {
+        let input = "....\n.#..\n..^.\n....\n";
+        let minimized = minimize(Shape::Grid, input, &mut |text: &str| {
+            let widths: Vec<usize> = text.lines().map(str::len).collect();
+            widths.windows(2).all(|pair| pair[0] == pair[1])
+                && text.contains('#')
+                && text.contains('^')
+        });
+        assert_eq!("#.\n.^\n", minimized);
+    }
+
+    #[test]
+    fn it_keeps_every_section() {
+        let input = "47|53\n97|13\n61|13\n\n75,47,61\n97,61,53\n";
+        let minimized = minimize(Shape::Sections, input, &mut |text: &str| {
+            text.contains("97,61,53")
+        });
+        assert_eq!("61|13\n\n97,61,53\n", minimized);
+    }
+
+    fn fake_solution() -> Solution {
+        Solution {
+            year: 2024,
+            day: 1,
+            part1: |input| {
+                if input.contains('x') {
+                    panic!("found an x");
+                }
+                Answer::from(input.len())
+            },
+            part2: |input| Answer::from(input.len()),
+            checked: None,
+            parse: None,
+            variants: &[],
+            validate: |_| Vec::new(),
+            shape: Shape::Characters,
+            visualize: None,
+            explain: None,
+        }
+    }
+
+    #[test]
+    fn it_observes_failures() {
+        let solution = fake_solution();
+        let observe = |input: &str, parts: &[u8]| {
+            Failure::Panic.observe(&solution, parts, input, Arithmetic::U64, None)
+        };
+        assert_eq!(
+            Some("part 1 panicked: found an x".to_string()),
+            observe("abx", &[2, 1])
+        );
+        assert_eq!(None, observe("abx", &[2]));
+        assert_eq!(None, observe("abc", &[1, 2]));
+        assert_eq!(
+            None,
+            Failure::Mismatch.observe(&solution, &[1], "abc", Arithmetic::U64, None)
+        );
+        assert_eq!(
+            None,
+            Failure::Timeout.observe(&solution, &[1], "abc", Arithmetic::U64, None)
+        );
+    }
+
+    #[test]
+    fn it_kills_candidates_at_the_limit() {
+        let started = Instant::now();
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        assert!(wait_or_kill(&mut child, Duration::from_millis(50)).unwrap());
+        assert!(started.elapsed() < Duration::from_secs(10));
+        let mut child = Command::new("true").spawn().unwrap();
+        assert!(!wait_or_kill(&mut child, Duration::from_secs(10)).unwrap());
+    }
+
+    #[test]
+    fn it_minimizes_panicking_input() {
+        let solution = fake_solution();
+        let minimized = minimize(solution.shape, "abc\ndxf\nghi\n", &mut |input: &str| {
+            Failure::Panic
+                .observe(&solution, &[1], input, Arithmetic::U64, None)
+                .is_some()
+        });
+        assert_eq!("x\n", minimized);
+    }
+}
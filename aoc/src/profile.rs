@@ -0,0 +1,267 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::registry::Solution;
+use crate::runner::{run_on_worker, solve, Format};
+use common::alloc::{Allocations, CountingAllocator};
+use common::Arithmetic;
+use serde_json::json;
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Phase {
+    Parse,
+    Part(u8),
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse => write!(f, "parse"),
+            Self::Part(part) => write!(f, "part{}", part),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PhaseProfile {
+    pub day: u8,
+    pub phase: Phase,
+    pub elapsed: Duration,
+    pub memory: Allocations,
+    /// Set when the phase overflowed, panicked or ran out of time; `memory`
+    /// then only covers what happened before that.
+    pub error: Option<String>,
+}
+
+/// Runs a day's parser, when it registers one, then both parts, measuring
+/// each phase on its own worker with `allocator`.
+///
+/// `allocator` must be the global allocator, or nothing is counted.
+pub fn profile_day(
+    allocator: &'static CountingAllocator,
+    solution: &Solution,
+    input: &str,
+    arithmetic: Arithmetic,
+    limit: Option<Duration>,
+) -> Vec<PhaseProfile> {
+    let mut phases = Vec::new();
+    if solution.parse.is_some() {
+        phases.push(Phase::Parse);
+    }
+    phases.extend([Phase::Part(1), Phase::Part(2)]);
+    phases
+        .into_iter()
+        .map(|phase| profile_phase(allocator, solution, phase, input, arithmetic, limit))
+        .collect()
+}
+
+fn profile_phase(
+    allocator: &'static CountingAllocator,
+    solution: &Solution,
+    phase: Phase,
+    input: &str,
+    arithmetic: Arithmetic,
+    limit: Option<Duration>,
+) -> PhaseProfile {
+    let solution = *solution;
+    let input = input.to_string();
+    let span = allocator.start();
+    let (result, elapsed) = run_on_worker(
+        format!("day-{:02}-{}", solution.day, phase),
+        limit,
+        move || {
+            allocator.charge(span, || match phase {
+                Phase::Parse => {
+                    let parse = solution.parse.expect("Day has no parser");
+                    parse(input);
+                    None
+                }
+                Phase::Part(part) => solve(&solution, part, input, arithmetic).error(),
+            })
+        },
+    );
+    // Stopped here rather than on the worker, which may have been abandoned;
+    // it can then no longer add to later phases.
+    let memory = allocator.stop(span);
+    let error = result.unwrap_or_else(|failure| failure.error());
+    PhaseProfile {
+        day: solution.day,
+        phase,
+        elapsed,
+        memory,
+        error,
+    }
+}
+
+pub fn render(profiles: &[PhaseProfile], format: Format) -> String {
+    match format {
+        Format::Text => profiles
+            .iter()
+            .map(|profile| {
+                let error = profile
+                    .error
+                    .as_ref()
+                    .map_or(String::new(), |error| format!(" ({})", error));
+                format!(
+                    "Day {:02} {:<6} [{:.3?}] {}{}\n",
+                    profile.day,
+                    profile.phase.to_string(),
+                    profile.elapsed,
+                    profile.memory,
+                    error
+                )
+            })
+            .collect(),
+        Format::Json => {
+            let profiles: Vec<_> = profiles
+                .iter()
+                .map(|profile| {
+                    json!({
+                        "day": profile.day,
+                        "phase": profile.phase.to_string(),
+                        "elapsed_ms": profile.elapsed.as_secs_f64() * 1000.0,
+                        "allocations": profile.memory.allocations,
+                        "bytes": profile.memory.bytes,
+                        "peak_bytes": profile.memory.peak,
+                        "error": profile.error,
+                    })
+                })
+                .collect();
+            format!("{}\n", serde_json::Value::Array(profiles))
+        }
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::registry::Shape;
+
+    static ALLOCATOR: CountingAllocator = CountingAllocator::new();
+
+    fn fake_solution() -> Solution {
+        Solution {
+            year: 2024,
+            day: 9,
+            part1: |input| common::Answer::from(input.len()),
+            part2: |_| panic!("not yet"),
+            checked: None,
+            parse: Some(|input| {
+                let _ = input.chars().collect::<Vec<_>>();
+            }),
+            variants: &[],
+            validate: |_| Vec::new(),
+            shape: Shape::Lines,
+            visualize: None,
+            explain: None,
+        }
+    }
+
+    #[test]
+    fn it_profiles_each_phase() {
+        let profiles = profile_day(&ALLOCATOR, &fake_solution(), "12345", Arithmetic::U64, None);
+        assert_eq!(
+            vec![Phase::Parse, Phase::Part(1), Phase::Part(2)],
+            profiles
+                .iter()
+                .map(|profile| profile.phase)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(None, profiles[0].error);
+        assert_eq!(Some("not yet".to_string()), profiles[2].error);
+    }
+
+    #[test]
+    fn it_skips_parse_without_a_parser() {
+        let solution = Solution {
+            parse: None,
+            ..fake_solution()
+        };
+        let profiles = profile_day(&ALLOCATOR, &solution, "12345", Arithmetic::U64, None);
+        assert_eq!(Phase::Part(1), profiles[0].phase);
+        assert_eq!(2, profiles.len());
+    }
+
+    #[test]
+    fn it_keeps_abandoned_phases_out_of_later_ones() {
+        use std::alloc::{GlobalAlloc, Layout};
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        static SEPARATE: CountingAllocator = CountingAllocator::new();
+        static FINISHED: AtomicBool = AtomicBool::new(false);
+        let solution = Solution {
+            // Keeps allocating after it is abandoned, until the test ends.
+            part1: |_| {
+                let layout = Layout::from_size_align(1024, 8).unwrap();
+                while !FINISHED.load(Ordering::Relaxed) {
+                    unsafe { SEPARATE.dealloc(SEPARATE.alloc(layout), layout) };
+                }
+                common::Answer::from(0)
+            },
+            part2: |_| {
+                let layout = Layout::from_size_align(64, 8).unwrap();
+                unsafe { SEPARATE.dealloc(SEPARATE.alloc(layout), layout) };
+                common::Answer::from(1)
+            },
+            parse: None,
+            ..fake_solution()
+        };
+        let profiles = profile_day(
+            &SEPARATE,
+            &solution,
+            "12345",
+            Arithmetic::U64,
+            Some(Duration::from_millis(50)),
+        );
+        FINISHED.store(true, Ordering::Relaxed);
+        assert_eq!(Some("time limit exceeded".to_string()), profiles[0].error);
+        assert_eq!(None, profiles[1].error);
+        assert_eq!(
+            Allocations {
+                allocations: 1,
+                bytes: 64,
+                peak: 64,
+            },
+            profiles[1].memory
+        );
+    }
+
+    #[test]
+    fn it_renders_profiles() {
+        let profiles = vec![PhaseProfile {
+            day: 9,
+            phase: Phase::Parse,
+            elapsed: Duration::from_millis(2),
+            memory: Allocations {
+                allocations: 3,
+                bytes: 2048,
+                peak: 1024,
+            },
+            error: None,
+        }];
+        assert_eq!(
+            "Day 09 parse  [2.000ms] 3 allocations, 2.0 KiB allocated, 1.0 KiB peak\n",
+            render(&profiles, Format::Text)
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&profiles, Format::Json)).unwrap();
+        assert_eq!("parse", json[0]["phase"]);
+        assert_eq!(2048, json[0]["bytes"]);
+        assert_eq!(1024, json[0]["peak_bytes"]);
+    }
+}
@@ -0,0 +1,445 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::answers::AnswerDatabase;
+use crate::runner::{Format, Outcome, PartResult, Status};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs::{read_to_string, write};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+/// Puzzles unlock at midnight US Eastern Standard Time, 05:00 UTC.
+const RELEASE_HOUR_UTC: i64 = 5;
+
+/// Days since 1970-01-01 of a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// The date `days` after 1970-01-01 as `(year, month, day)`.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    (year_of_era + era * 400 + i64::from(month <= 2), month, day)
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("The clock is after 1970")
+        .as_secs()
+}
+
+/// When a day's puzzle unlocks, in seconds since the Unix epoch.
+pub fn release(year: u16, day: u8) -> u64 {
+    let date = days_from_civil(i64::from(year), 12, i64::from(day));
+    (date * SECONDS_PER_DAY + RELEASE_HOUR_UTC * 60 * 60) as u64
+}
+
+/// A Unix timestamp as `YYYY-MM-DD HH:MM` in UTC.
+pub fn format_time(timestamp: u64) -> String {
+    let timestamp = timestamp as i64;
+    let (year, month, day) = civil_from_days(timestamp.div_euclid(SECONDS_PER_DAY));
+    let seconds = timestamp.rem_euclid(SECONDS_PER_DAY);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60
+    )
+}
+
+/// Seconds as `HH:MM:SS`, letting the hours run past a day the way the
+/// site's personal stats do.
+pub fn format_duration(seconds: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Event {
+    /// A run produced an answer.
+    Solved,
+    /// A run produced the answer known to be correct.
+    Verified,
+    /// The site accepted a submitted answer.
+    Submitted,
+}
+
+/// When each event first happened to one part, in seconds since the Unix
+/// epoch.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct PartProgress {
+    pub solved: Option<u64>,
+    pub verified: Option<u64>,
+    pub submitted: Option<u64>,
+}
+
+/// What the runner and submitter have seen of each part, kept in
+/// `progress.json` beside the year's answers.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Progress {
+    path: PathBuf,
+    parts: BTreeMap<(u8, u8), PartProgress>,
+}
+
+fn parse_part(value: &Value) -> Option<((u8, u8), PartProgress)> {
+    let time = |key: &str| match &value[key] {
+        Value::Null => Some(None),
+        time => time.as_u64().map(Some),
+    };
+    Some((
+        (
+            u8::try_from(value["day"].as_u64()?).ok()?,
+            u8::try_from(value["part"].as_u64()?).ok()?,
+        ),
+        PartProgress {
+            solved: time("solved")?,
+            verified: time("verified")?,
+            submitted: time("submitted")?,
+        },
+    ))
+}
+
+impl Progress {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let contents = match read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => "[]".to_string(),
+            Err(error) => return Err(error),
+        };
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        let values: Vec<Value> = serde_json::from_str(&contents)
+            .map_err(|error| invalid(format!("{}: {}", path.display(), error)))?;
+        let parts = values
+            .iter()
+            .map(|value| {
+                parse_part(value)
+                    .ok_or_else(|| invalid(format!("{}: bad progress {}", path.display(), value)))
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            parts,
+        })
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> PartProgress {
+        self.parts.get(&(day, part)).copied().unwrap_or_default()
+    }
+
+    /// Notes that `event` happened at `time`, keeping the first time it did.
+    pub fn record(&mut self, day: u8, part: u8, event: Event, time: u64) {
+        let progress = self.parts.entry((day, part)).or_default();
+        let slot = match event {
+            Event::Solved => &mut progress.solved,
+            Event::Verified => &mut progress.verified,
+            Event::Submitted => &mut progress.submitted,
+        };
+        slot.get_or_insert(time);
+    }
+
+    /// Notes what a run of a part showed.
+    pub fn record_result(&mut self, result: &PartResult, time: u64) {
+        if let Outcome::Solved(_) = result.outcome {
+            self.record(result.day, result.part, Event::Solved, time);
+        }
+        if Status::Correct == result.status() {
+            self.record(result.day, result.part, Event::Verified, time);
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let values: Vec<Value> = self
+            .parts
+            .iter()
+            .map(|((day, part), progress)| {
+                json!({
+                    "day": day,
+                    "part": part,
+                    "solved": progress.solved,
+                    "verified": progress.verified,
+                    "submitted": progress.submitted,
+                })
+            })
+            .collect();
+        let contents = serde_json::to_string_pretty(&values).expect("JSON values always serialize");
+        write(&self.path, contents + "\n")
+    }
+}
+
+/// Notes what each run showed in the progress at `path`, leaving the file
+/// alone when there were none.
+pub fn record_results(path: &Path, results: &[PartResult]) -> io::Result<()> {
+    if results.is_empty() {
+        return Ok(());
+    }
+    let mut progress = Progress::load(path)?;
+    let time = now();
+    for result in results {
+        progress.record_result(result, time);
+    }
+    progress.save()
+}
+
+/// Whether a part has earned its star: the site accepted it, or its answer
+/// is known from before progress was tracked.
+fn has_star(database: &AnswerDatabase, progress: &Progress, day: u8, part: u8) -> bool {
+    database.is_solved(day, part) || progress.get(day, part).submitted.is_some()
+}
+
+fn stars(database: &AnswerDatabase, progress: &Progress, day: u8) -> usize {
+    (1..=2)
+        .filter(|part| has_star(database, progress, day, *part))
+        .count()
+}
+
+/// How one part stands, for the table below the calendar.
+fn describe_part(
+    database: &AnswerDatabase,
+    progress: &Progress,
+    year: u16,
+    day: u8,
+    part: u8,
+) -> String {
+    let state = progress.get(day, part);
+    match state.submitted {
+        Some(time) => format!(
+            "{}  +{}",
+            format_time(time),
+            format_duration(time.saturating_sub(release(year, day)))
+        ),
+        None if has_star(database, progress, day, part) => "solved".to_string(),
+        None if state.solved.is_some() => "not submitted".to_string(),
+        None => "-".to_string(),
+    }
+}
+
+/// A December calendar of the year's stars, followed by when each part was
+/// solved and how long after its release.
+pub fn render(
+    year: u16,
+    database: &AnswerDatabase,
+    progress: &Progress,
+    now: u64,
+    format: Format,
+) -> String {
+    let total: usize = (1..=25).map(|day| stars(database, progress, day)).sum();
+    match format {
+        Format::Text => {
+            let plural = if total == 1 { "" } else { "s" };
+            let mut text = format!("Advent of Code {}: {} star{}\n\n", year, total, plural);
+            text += " Sun   Mon   Tue   Wed   Thu   Fri   Sat\n";
+            // 1970-01-01 was a Thursday.
+            let first = (days_from_civil(i64::from(year), 12, 1) + 4).rem_euclid(7) as usize;
+            let mut cells = vec![" ".repeat(6); first];
+            cells.extend((1..=25).map(|day| {
+                let marker = if now < release(year, day) {
+                    " .".to_string()
+                } else {
+                    "*".repeat(stars(database, progress, day))
+                };
+                format!("{:>4}{:<2}", day, marker)
+            }));
+            for week in cells.chunks(7) {
+                text += week.concat().trim_end();
+                text += "\n";
+            }
+            text += &format!(
+                "\n{:<5}{:<32}{}\n",
+                "Day", "Part 1 (UTC, time taken)", "Part 2"
+            );
+            for day in 1..=25 {
+                let line = if now < release(year, day) {
+                    format!("unlocks {}", format_time(release(year, day)))
+                } else {
+                    format!(
+                        "{:<32}{}",
+                        describe_part(database, progress, year, day, 1),
+                        describe_part(database, progress, year, day, 2)
+                    )
+                };
+                text += format!("{:<5}{}", format!("{:02}", day), line).trim_end();
+                text += "\n";
+            }
+            text
+        }
+        Format::Json => {
+            let days: Vec<_> = (1..=25)
+                .map(|day| {
+                    let parts: Vec<_> = (1..=2)
+                        .map(|part| {
+                            let state = progress.get(day, part);
+                            json!({
+                                "part": part,
+                                "star": has_star(database, progress, day, part),
+                                "solved": state.solved,
+                                "verified": state.verified,
+                                "submitted": state.submitted,
+                                "seconds_after_release": state
+                                    .submitted
+                                    .map(|time| time.saturating_sub(release(year, day))),
+                            })
+                        })
+                        .collect();
+                    json!({
+                        "day": day,
+                        "released": release(year, day) <= now,
+                        "stars": stars(database, progress, day),
+                        "parts": parts,
+                    })
+                })
+                .collect();
+            format!("{}\n", json!({"year": year, "stars": total, "days": days}))
+        }
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answers::Check;
+    use common::Answer;
+    use std::env::temp_dir;
+    use std::fs::remove_file;
+    use std::time::Duration;
+
+    fn temp_path(name: &str) -> PathBuf {
+        temp_dir().join(format!("aoc-{}-{}.json", name, std::process::id()))
+    }
+
+    #[test]
+    fn it_converts_dates() {
+        assert_eq!(0, days_from_civil(1970, 1, 1));
+        assert_eq!((1970, 1, 1), civil_from_days(0));
+        assert_eq!((2024, 2, 29), civil_from_days(days_from_civil(2024, 2, 29)));
+        assert_eq!((2000, 3, 1), civil_from_days(days_from_civil(2000, 3, 1)));
+        assert_eq!(1733029200, release(2024, 1));
+        assert_eq!("2024-12-01 05:00", format_time(release(2024, 1)));
+        assert_eq!("2015-12-25 05:00", format_time(release(2015, 25)));
+        assert_eq!("00:12:03", format_duration(723));
+        assert_eq!("26:00:01", format_duration(93601));
+    }
+
+    #[test]
+    fn it_keeps_the_first_time() {
+        let path = temp_path("progress");
+        let mut progress = Progress::load(&path).unwrap();
+        assert_eq!(PartProgress::default(), progress.get(1, 1));
+        progress.record(1, 1, Event::Solved, 10);
+        progress.record(1, 1, Event::Solved, 20);
+        progress.record_result(
+            &PartResult {
+                day: 1,
+                part: 2,
+                outcome: Outcome::Solved(Answer::from(5_u8)),
+                elapsed: Duration::ZERO,
+                check: Check::Correct,
+            },
+            30,
+        );
+        progress.record_result(
+            &PartResult {
+                day: 2,
+                part: 1,
+                outcome: Outcome::Timeout,
+                elapsed: Duration::ZERO,
+                check: Check::Unknown,
+            },
+            30,
+        );
+        assert_eq!(Some(10), progress.get(1, 1).solved);
+        assert_eq!(
+            PartProgress {
+                solved: Some(30),
+                verified: Some(30),
+                submitted: None
+            },
+            progress.get(1, 2)
+        );
+        assert_eq!(PartProgress::default(), progress.get(2, 1));
+        progress.save().unwrap();
+        assert_eq!(progress, Progress::load(&path).unwrap());
+        write(&path, r#"[{"day": 1, "part": 1, "solved": "soon"}]"#).unwrap();
+        assert!(Progress::load(&path).is_err());
+        remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn it_records_results() {
+        let path = temp_path("recorded");
+        record_results(&path, &[]).unwrap();
+        assert!(!path.exists());
+        let result = PartResult {
+            day: 3,
+            part: 1,
+            outcome: Outcome::Solved(Answer::from(5_u8)),
+            elapsed: Duration::ZERO,
+            check: Check::Unknown,
+        };
+        record_results(&path, &[result]).unwrap();
+        let progress = Progress::load(&path).unwrap();
+        assert!(progress.get(3, 1).solved.is_some());
+        assert_eq!(None, progress.get(3, 1).verified);
+        remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn it_renders_the_calendar() {
+        let database = AnswerDatabase::load(&temp_path("missing-answers")).unwrap();
+        let mut progress = Progress::load(&temp_path("missing-progress")).unwrap();
+        progress.record(1, 1, Event::Submitted, release(2024, 1) + 723);
+        progress.record(1, 2, Event::Solved, release(2024, 1) + 900);
+        let now = release(2024, 3) - 1;
+        let text = render(2024, &database, &progress, now, Format::Text);
+        assert!(text.starts_with("Advent of Code 2024: 1 star\n"));
+        // December 2024 starts on a Sunday.
+        assert!(text.contains("\n   1*    2     3 .   4 .   5 .   6 .   7 .\n"));
+        assert!(text.contains("\n01   2024-12-01 05:12  +00:12:03     not submitted\n"));
+        assert!(text.contains("\n02   -                               -\n"));
+        assert!(text.contains("\n03   unlocks 2024-12-03 05:00\n"));
+        let json: Value =
+            serde_json::from_str(&render(2024, &database, &progress, now, Format::Json)).unwrap();
+        assert_eq!(1, json["stars"]);
+        assert_eq!(723, json["days"][0]["parts"][0]["seconds_after_release"]);
+        assert_eq!(false, json["days"][0]["parts"][1]["star"]);
+        assert_eq!(false, json["days"][2]["released"]);
+    }
+}
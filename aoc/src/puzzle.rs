@@ -0,0 +1,425 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Renders a saved puzzle page as text for reading in the terminal.
+
+/// Paragraphs are wrapped to this many columns; code blocks are left alone.
+pub const WIDTH: usize = 80;
+
+/// Starts and ends bold text, for emphasized answers.
+const BOLD: &str = "\x1b[1m";
+/// Starts and ends cyan text, for code.
+const CYAN: &str = "\x1b[36m";
+const BOLD_CYAN: &str = "\x1b[1;36m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token<'a> {
+    /// An opening tag's name and the rest of what is inside it.
+    Open(String, &'a str),
+    Close(String),
+    Text(&'a str),
+}
+
+/// Splits HTML into tags and text, dropping comments, doctypes and the
+/// contents of scripts and styles.
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+        }
+        rest = &rest[start..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::Close(name.trim().to_ascii_lowercase()));
+        } else if !tag.starts_with('!') {
+            let split = tag
+                .find(|c: char| c.is_whitespace() || c == '/')
+                .unwrap_or(tag.len());
+            let name = tag[..split].to_ascii_lowercase();
+            if name == "script" || name == "style" {
+                let close = format!("</{}", name);
+                rest = rest.find(&close).map_or("", |end| &rest[end..]);
+            }
+            tokens.push(Token::Open(name, &tag[split..]));
+        }
+    }
+    tokens
+}
+
+/// Replaces the character references the site uses.
+fn decode(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded += &rest[..start];
+        rest = &rest[start..];
+        let reference = rest[1..]
+            .find(';')
+            .map(|end| &rest[1..end + 1])
+            .filter(|reference| reference.len() <= 8);
+        let character = reference.and_then(|reference| match reference {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => {
+                let number = reference.strip_prefix('#')?;
+                let code = match number.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => number.parse().ok()?,
+                };
+                char::from_u32(code)
+            }
+        });
+        match (character, reference) {
+            (Some(character), Some(reference)) => {
+                decoded.push(character);
+                rest = &rest[reference.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded + rest
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+struct Style {
+    em: bool,
+    code: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Kind {
+    Heading,
+    Paragraph,
+    Item,
+    Code,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Block {
+    kind: Kind,
+    spans: Vec<(String, Style)>,
+}
+
+impl Block {
+    fn text(&self) -> String {
+        self.spans.iter().map(|(text, _)| text.as_str()).collect()
+    }
+}
+
+/// The blocks of each part's description along with the "Your puzzle
+/// answer was" lines after them.
+fn blocks(html: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut current: Option<Block> = None;
+    let (mut in_article, mut in_main) = (false, false);
+    let (mut em, mut code) = (0, 0);
+    for token in tokenize(html) {
+        match token {
+            Token::Open(name, attributes) => match name.as_str() {
+                "main" => in_main = true,
+                "article" if attributes.contains("day-desc") => in_article = true,
+                "em" => em += 1,
+                "code" => code += 1,
+                "h2" | "p" | "li" | "pre" if in_main => {
+                    let kind = match name.as_str() {
+                        "h2" => Kind::Heading,
+                        "p" => Kind::Paragraph,
+                        "li" => Kind::Item,
+                        _ => Kind::Code,
+                    };
+                    current = Some(Block {
+                        kind,
+                        spans: Vec::new(),
+                    });
+                }
+                _ => {}
+            },
+            Token::Close(name) => match name.as_str() {
+                "main" => in_main = false,
+                "article" => in_article = false,
+                "em" => em = usize::saturating_sub(em, 1),
+                "code" => code = usize::saturating_sub(code, 1),
+                "h2" | "p" | "li" | "pre" => {
+                    if let Some(block) = current.take() {
+                        if in_article || block.text().trim().starts_with("Your puzzle answer was") {
+                            blocks.push(block);
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Token::Text(text) => {
+                if let Some(block) = &mut current {
+                    let style = Style {
+                        em: em > 0,
+                        code: code > 0,
+                    };
+                    block.spans.push((decode(text), style));
+                }
+            }
+        }
+    }
+    blocks
+}
+
+/// Wraps `text` in the escape codes or plain-text marks for `style`.
+fn decorate(text: &str, style: Style, color: bool) -> String {
+    let (start, end) = match (style.em, style.code, color) {
+        (false, false, _) => ("", ""),
+        (true, false, true) => (BOLD, RESET),
+        (false, true, true) => (CYAN, RESET),
+        (true, true, true) => (BOLD_CYAN, RESET),
+        (true, _, false) => ("*", "*"),
+        (false, true, false) => ("`", "`"),
+    };
+    format!("{}{}{}", start, text, end)
+}
+
+/// Lays out `spans` as words wrapped to `WIDTH`, starting each line after
+/// the first with `indent`. Whitespace runs collapse as they do in a browser.
+fn wrap(spans: &[(String, Style)], first: &str, indent: &str, color: bool) -> String {
+    // Plain marks go around a whole span, so that `a b` is not `a` `b`.
+    let spans: Vec<(String, Style)> = if color {
+        spans.to_vec()
+    } else {
+        spans
+            .iter()
+            .map(|(text, style)| match text.trim() {
+                "" => (text.clone(), Style::default()),
+                trimmed => {
+                    let marked = text.replace(trimmed, &decorate(trimmed, *style, false));
+                    (marked, Style::default())
+                }
+            })
+            .collect()
+    };
+    // Each word is pieces of text in one style, with its visible width.
+    let mut words: Vec<(String, usize)> = Vec::new();
+    let mut word = (String::new(), 0);
+    for (text, style) in &spans {
+        for (index, piece) in text.split(char::is_whitespace).enumerate() {
+            if index > 0 && word.1 > 0 {
+                words.push(std::mem::take(&mut word));
+            }
+            if !piece.is_empty() {
+                word.0 += &decorate(piece, *style, color);
+                word.1 += piece.chars().count();
+            }
+        }
+    }
+    if word.1 > 0 {
+        words.push(word);
+    }
+    let mut text = first.to_string();
+    let mut column = first.chars().count();
+    let mut start = true;
+    for (word, width) in words {
+        if !start && column + 1 + width > WIDTH {
+            text += "\n";
+            text += indent;
+            column = indent.chars().count();
+            start = true;
+        }
+        if !start {
+            text += " ";
+            column += 1;
+        }
+        text += &word;
+        column += width;
+        start = false;
+    }
+    text + "\n"
+}
+
+/// The puzzle descriptions in a saved page as text, with answers in bold
+/// and code in cyan when `color` is set, or marked `*answer*` and `` `code` ``
+/// when it is not. Part 2 shows up once the saved page has it.
+pub fn render(html: &str, color: bool) -> String {
+    let mut text = String::new();
+    let mut previous = None;
+    for block in blocks(html) {
+        let list = previous == Some(Kind::Item) && block.kind == Kind::Item;
+        if previous.is_some() && !list {
+            text += "\n";
+        }
+        match block.kind {
+            Kind::Heading | Kind::Paragraph => text += &wrap(&block.spans, "", "", color),
+            Kind::Item => text += &wrap(&block.spans, "- ", "  ", color),
+            Kind::Code => {
+                // Code blocks are pictures; plain marks would shift them.
+                let code: String = block
+                    .spans
+                    .iter()
+                    .map(|(piece, style)| match color {
+                        true => decorate(
+                            piece,
+                            Style {
+                                code: false,
+                                ..*style
+                            },
+                            true,
+                        ),
+                        false => piece.clone(),
+                    })
+                    .collect();
+                for line in code.trim_end_matches('\n').lines() {
+                    text += format!("    {}", line).trim_end();
+                    text += "\n";
+                }
+            }
+        }
+        previous = Some(block.kind);
+    }
+    text
+}
+
+/// Whether a saved page has only part 1 and needs downloading again once
+/// part 1 is solved.
+pub fn is_missing_part2(html: &str) -> bool {
+    !html.contains("id=\"part2\"")
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html lang="en-us">
+<head><title>Day 6 - Advent of Code 2024</title>
+<script>if (a < b && c > d) { window.x = "<p>not text</p>"; }</script></head>
+<body>
+<header><h1 class="title-global"><a href="/">Advent of Code</a></h1></header>
+<main>
+<script>window.y = 1;</script>
+<article class="day-desc"><h2>--- Day 6: Guard Gallivant ---</h2><p>The map shows the current position of the guard with <code>^</code> (to indicate the guard is currently facing <em>up</em> from the perspective of the map). Any <em>obstructions</em> - crates, desks, alchemical reactors, etc. - are shown as <code>#</code>.</p>
+<pre><code>....#.....
+....<em>^</em>....#
+</code></pre>
+<ul>
+<li>If there is something directly in front of you, turn right 90 degrees.</li>
+<li>Otherwise, take a step forward.</li>
+</ul>
+<p>In this example, the guard will visit <code><em>41</em></code> distinct positions &amp; then leave.</p>
+</article>
+<p>Your puzzle answer was <code>4883</code>.</p><!-- <p>hidden</p> -->
+<p>The first half of this puzzle is complete! It provides one gold star: *</p>
+</main>
+</body>
+</html>"#;
+
+    #[test]
+    fn it_tokenizes_html() {
+        assert_eq!(
+            vec![
+                Token::Open("p".to_string(), " class=\"x\""),
+                Token::Text("a &lt; b"),
+                Token::Close("p".to_string()),
+                Token::Open("br".to_string(), "/"),
+            ],
+            tokenize("<!-- c --><P class=\"x\">a &lt; b</p><br/><script>1 < 2</script")
+                .into_iter()
+                .take(4)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn it_decodes_references() {
+        assert_eq!(
+            "<a> & \"b\" 'c' é — &unknown; & ;",
+            decode("&lt;a&gt; &amp; &quot;b&quot; &#39;c&apos; &#233; &#x2014; &unknown; & ;")
+        );
+    }
+
+    #[test]
+    fn it_renders_plain_text() {
+        assert_eq!(
+            "\
+--- Day 6: Guard Gallivant ---
+
+The map shows the current position of the guard with `^` (to indicate the guard
+is currently facing *up* from the perspective of the map). Any *obstructions* -
+crates, desks, alchemical reactors, etc. - are shown as `#`.
+
+    ....#.....
+    ....^....#
+
+- If there is something directly in front of you, turn right 90 degrees.
+- Otherwise, take a step forward.
+
+In this example, the guard will visit *41* distinct positions & then leave.
+
+Your puzzle answer was `4883`.
+",
+            render(PAGE, false)
+        );
+        assert!(is_missing_part2(PAGE));
+        assert!(!is_missing_part2(
+            r#"<article class="day-desc"><h2 id="part2">--- Part Two ---</h2></article>"#
+        ));
+    }
+
+    #[test]
+    fn it_renders_colors() {
+        let text = render(PAGE, true);
+        assert!(text.contains("facing \x1b[1mup\x1b[0m from"));
+        assert!(text.contains("with \x1b[36m^\x1b[0m (to"));
+        assert!(text.contains("visit \x1b[1;36m41\x1b[0m distinct"));
+        assert!(text.contains("    ....\x1b[1m^\x1b[0m....#\n"));
+    }
+
+    #[test]
+    fn it_wraps_long_words_and_lists() {
+        let long = "x".repeat(90);
+        let spans = [(format!("a {} b", long), Style::default())];
+        assert_eq!(
+            format!("- a\n  {}\n  b\n", long),
+            wrap(&spans, "- ", "  ", false)
+        );
+        assert_eq!("\n", wrap(&[], "", "", false));
+        let em = Style {
+            em: true,
+            code: false,
+        };
+        let spans = [
+            ("a".to_string(), em),
+            (" ".to_string(), em),
+            ("b".to_string(), em),
+        ];
+        assert_eq!("*a* *b*\n", wrap(&spans, "", "", false));
+    }
+}
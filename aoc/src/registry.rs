@@ -0,0 +1,395 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub use common::registry::{CheckedPart, Explain, Parse, Part, Shape, Validate, Visualize};
+use common::registry::{Registered, Registration, REGISTRATIONS};
+use std::sync::OnceLock;
+
+// Every day crate in each range has to register its parts and `validate`
+// with `#[aoc]`. New days need to be added here and to `aoc/Cargo.toml`,
+// and a new year needs a line of its own.
+common::require_days!(2024, 1..=11);
+
+/// Parts of a day that can run on any `Arithmetic` backend and report
+/// overflow instead of panicking.
+#[derive(Debug, Clone, Copy)]
+pub struct Checked {
+    pub part1: CheckedPart,
+    pub part2: CheckedPart,
+}
+
+/// An alternative implementation of one part, such as a naive version kept
+/// beside an optimized one.
+#[derive(Debug, Clone, Copy)]
+pub struct Variant {
+    pub part: u8,
+    pub name: &'static str,
+    pub function: Part,
+}
+
+/// The name `--variant` uses for a day's own `part1` and `part2`.
+pub const DEFAULT_VARIANT: &str = "default";
+
+/// A day's solutions, wrapped so every part returns an `Answer`.
+#[derive(Debug, Clone, Copy)]
+pub struct Solution {
+    pub year: u16,
+    pub day: u8,
+    pub part1: Part,
+    pub part2: Part,
+    pub checked: Option<Checked>,
+    pub parse: Option<Parse>,
+    pub variants: &'static [Variant],
+    pub validate: Validate,
+    pub shape: Shape,
+    pub visualize: Option<Visualize>,
+    pub explain: Option<Explain>,
+}
+
+impl Solution {
+    pub fn part(&self, part: u8) -> Option<Part> {
+        match part {
+            1 => Some(self.part1),
+            2 => Some(self.part2),
+            _ => None,
+        }
+    }
+
+    pub fn checked_part(&self, part: u8) -> Option<CheckedPart> {
+        match (self.checked, part) {
+            (Some(checked), 1) => Some(checked.part1),
+            (Some(checked), 2) => Some(checked.part2),
+            _ => None,
+        }
+    }
+
+    /// Names of every implementation of `part`, starting with the default.
+    pub fn variant_names(&self, part: u8) -> Vec<&'static str> {
+        let variants = self
+            .variants
+            .iter()
+            .filter(|variant| variant.part == part)
+            .map(|variant| variant.name);
+        [DEFAULT_VARIANT].into_iter().chain(variants).collect()
+    }
+
+    /// This day with `part` swapped for the variant called `name`. Variants
+    /// have no checked version, so the copy runs them as they are.
+    pub fn with_variant(&self, part: u8, name: &str) -> Option<Solution> {
+        if DEFAULT_VARIANT == name {
+            return self.part(part).map(|_| *self);
+        }
+        let variant = self
+            .variants
+            .iter()
+            .find(|variant| variant.part == part && variant.name == name)?;
+        let mut solution = Solution {
+            checked: None,
+            ..*self
+        };
+        match part {
+            1 => solution.part1 = variant.function,
+            _ => solution.part2 = variant.function,
+        }
+        Some(solution)
+    }
+}
+
+/// Builds a day's `Solution` from its registrations, or `None` if it is
+/// missing a part or `validate`.
+fn assemble(year: u16, day: u8, registrations: &[&Registration]) -> Option<Solution> {
+    let part = |wanted: u8| {
+        registrations
+            .iter()
+            .find_map(|registration| match registration.registered {
+                Registered::Part {
+                    part,
+                    variant: None,
+                    function,
+                } if part == wanted => Some(function),
+                _ => None,
+            })
+    };
+    let checked_part = |wanted: u8| {
+        registrations
+            .iter()
+            .find_map(|registration| match registration.registered {
+                Registered::Checked { part, function } if part == wanted => Some(function),
+                _ => None,
+            })
+    };
+    let (validate, shape) =
+        registrations
+            .iter()
+            .find_map(|registration| match registration.registered {
+                Registered::Validate { function, shape } => Some((function, shape)),
+                _ => None,
+            })?;
+    let mut variants: Vec<Variant> = registrations
+        .iter()
+        .filter_map(|registration| match registration.registered {
+            Registered::Part {
+                part,
+                variant: Some(name),
+                function,
+            } => Some(Variant {
+                part,
+                name,
+                function,
+            }),
+            _ => None,
+        })
+        .collect();
+    variants.sort_by_key(|variant| (variant.part, variant.name));
+    Some(Solution {
+        year,
+        day,
+        part1: part(1)?,
+        part2: part(2)?,
+        checked: checked_part(1)
+            .zip(checked_part(2))
+            .map(|(part1, part2)| Checked { part1, part2 }),
+        parse: registrations
+            .iter()
+            .find_map(|registration| match registration.registered {
+                Registered::Parse(parse) => Some(parse),
+                _ => None,
+            }),
+        // Assembled once per run, so leaking the few variants is fine.
+        variants: Vec::leak(variants),
+        validate,
+        shape,
+        visualize: registrations
+            .iter()
+            .find_map(|registration| match registration.registered {
+                Registered::Visualize(visualize) => Some(visualize),
+                _ => None,
+            }),
+        explain: registrations
+            .iter()
+            .find_map(|registration| match registration.registered {
+                Registered::Explain(explain) => Some(explain),
+                _ => None,
+            }),
+    })
+}
+
+/// Every day registered with `#[aoc]`, in calendar order across years.
+pub fn solutions() -> Vec<Solution> {
+    static SOLUTIONS: OnceLock<Vec<Solution>> = OnceLock::new();
+    SOLUTIONS
+        .get_or_init(|| {
+            let mut days: Vec<(u16, u8)> = REGISTRATIONS
+                .iter()
+                .map(|registration| (registration.year, registration.day))
+                .collect();
+            days.sort_unstable();
+            days.dedup();
+            days.into_iter()
+                .filter_map(|(year, day)| {
+                    let registrations: Vec<&Registration> = REGISTRATIONS
+                        .iter()
+                        .filter(|registration| registration.year == year && registration.day == day)
+                        .collect();
+                    assemble(year, day, &registrations)
+                })
+                .collect()
+        })
+        .clone()
+}
+
+/// Every year with at least one registered day, oldest first.
+pub fn years() -> Vec<u16> {
+    let mut years: Vec<u16> = solutions().iter().map(|solution| solution.year).collect();
+    years.dedup();
+    years
+}
+
+pub fn latest_year() -> u16 {
+    *years().last().expect("No days are registered")
+}
+
+/// The days registered for `year`, in calendar order.
+pub fn solutions_in(year: u16) -> Vec<Solution> {
+    solutions()
+        .into_iter()
+        .filter(|solution| solution.year == year)
+        .collect()
+}
+
+pub fn find(year: u16, day: u8) -> Option<Solution> {
+    solutions()
+        .into_iter()
+        .find(|solution| solution.year == year && solution.day == day)
+}
+
+pub fn latest(year: u16) -> Option<Solution> {
+    solutions_in(year).last().copied()
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{Answer, Arithmetic};
+
+    #[test]
+    fn it_registers_days_in_order() {
+        let days: Vec<(u16, u8)> = solutions()
+            .iter()
+            .map(|solution| (solution.year, solution.day))
+            .collect();
+        let mut sorted = days.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted, days);
+    }
+
+    #[test]
+    fn it_registers_every_required_day() {
+        let days: Vec<u8> = solutions_in(2024)
+            .iter()
+            .map(|solution| solution.day)
+            .collect();
+        assert_eq!((1..=11).collect::<Vec<u8>>(), days);
+    }
+
+    #[test]
+    fn it_has_no_duplicate_registrations() {
+        let mut seen = Vec::new();
+        for registration in REGISTRATIONS.iter() {
+            let key = match registration.registered {
+                Registered::Part { part, variant, .. } => format!("part {} {:?}", part, variant),
+                Registered::Checked { part, .. } => format!("checked part {}", part),
+                Registered::Parse(_) => "parse".to_string(),
+                Registered::Validate { .. } => "validate".to_string(),
+                Registered::Visualize(_) => "visualize".to_string(),
+                Registered::Explain(_) => "explain".to_string(),
+            };
+            let key = (registration.year, registration.day, key);
+            assert!(
+                !seen.contains(&key),
+                "{} day {} registers {} twice",
+                key.0,
+                key.1,
+                key.2
+            );
+            seen.push(key);
+        }
+    }
+
+    #[test]
+    fn it_finds_days() {
+        let solution = find(2024, 6).unwrap();
+        assert_eq!((2024, 6), (solution.year, solution.day));
+        assert!(find(2024, 26).is_none());
+        assert!(find(2015, 6).is_none());
+        assert_eq!(solutions_in(2024).len(), latest(2024).unwrap().day as usize);
+        assert!(latest(2015).is_none());
+    }
+
+    #[test]
+    fn it_lists_years() {
+        assert!(years().contains(&2024));
+        assert_eq!(*years().last().unwrap(), latest_year());
+        assert!(solutions_in(2015).is_empty());
+    }
+
+    #[test]
+    fn it_wraps_parts_in_answers() {
+        let solution = find(2024, 1).unwrap();
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3".to_string();
+        assert_eq!(Answer::from(11), (solution.part(1).unwrap())(input.clone()));
+        assert_eq!(Answer::from(31), (solution.part(2).unwrap())(input));
+        assert!(solution.part(3).is_none());
+        assert!(solution.checked_part(1).is_none());
+        assert!(solution.parse.is_none());
+    }
+
+    #[test]
+    fn it_registers_parsers() {
+        let parse = find(2024, 9).unwrap().parse.unwrap();
+        parse("2333133121414131402".to_string());
+    }
+
+    #[test]
+    fn it_registers_validators() {
+        let solution = find(2024, 6).unwrap();
+        assert!((solution.validate)("..#\n.^.").is_empty());
+        assert_eq!(1, (solution.validate)("..#\n...").len());
+    }
+
+    #[test]
+    fn it_registers_shapes() {
+        assert_eq!(Shape::Lines, find(2024, 1).unwrap().shape);
+        assert_eq!(Shape::Sections, find(2024, 5).unwrap().shape);
+        assert_eq!(Shape::Grid, find(2024, 6).unwrap().shape);
+        assert_eq!(Shape::Tokens, find(2024, 11).unwrap().shape);
+    }
+
+    #[test]
+    fn it_registers_visualizations() {
+        let visualize = find(2024, 10).unwrap().visualize.unwrap();
+        assert_eq!(
+            "0123456789\n.0........\n",
+            visualize("0123456789\n0000000000")
+        );
+        assert!(find(2024, 1).unwrap().visualize.is_none());
+    }
+
+    #[test]
+    fn it_registers_explanations() {
+        let explain = find(2024, 3).unwrap().explain.unwrap();
+        let mut explanation = common::Explanation::new(1);
+        explain("mul(2,4)mul(3,7)", 1, &mut explanation);
+        assert_eq!(["mul(2,4) = 8"], explanation.lines());
+        assert_eq!(1, explanation.omitted());
+        assert!(find(2024, 1).unwrap().explain.is_none());
+    }
+
+    #[test]
+    fn it_registers_variants() {
+        let solution = find(2024, 1).unwrap();
+        assert_eq!(vec!["default"], solution.variant_names(1));
+        assert_eq!(vec!["default", "counts"], solution.variant_names(2));
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3".to_string();
+        let counts = solution.with_variant(2, "counts").unwrap();
+        assert_eq!(Answer::from(31), (counts.part2)(input.clone()));
+        assert_eq!(Answer::from(11), (counts.part1)(input));
+        assert!(solution.with_variant(2, "default").is_some());
+        assert!(solution.with_variant(1, "counts").is_none());
+        assert!(solution.with_variant(3, "default").is_none());
+    }
+
+    #[test]
+    fn it_combines_options() {
+        let solution = find(2024, 6).unwrap();
+        assert!(solution.parse.is_some());
+        assert_eq!(vec!["default", "states"], solution.variant_names(2));
+        let checked = find(2024, 7).unwrap().with_variant(1, "default").unwrap();
+        assert!(checked.checked_part(1).is_some());
+    }
+
+    #[test]
+    fn it_registers_checked_parts() {
+        let solution = find(2024, 11).unwrap();
+        let checked = solution.checked_part(1).unwrap();
+        assert_eq!(
+            Ok(Answer::from(55312)),
+            checked("125 17".to_string(), Arithmetic::U128)
+        );
+        assert!(solution.checked_part(2).is_some());
+        assert!(solution.checked_part(3).is_none());
+    }
+}
@@ -0,0 +1,539 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `aoc report`: a static HTML site with a page per day and a timing chart,
+//! with its styles and images inline so it opens anywhere without a network.
+
+use crate::answers::AnswerDatabase;
+use crate::bench::{Baseline, Benchmark};
+use crate::config::Config;
+use crate::profile::{self, PhaseProfile};
+use crate::registry::Solution;
+use crate::runner::{self, run_on_worker, PartResult};
+use crate::{vault, workspace};
+use common::alloc::{format_bytes, CountingAllocator};
+use common::Arithmetic;
+use std::fmt::Write;
+use std::fs::{create_dir_all, read_dir, read_to_string, write};
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+const STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em auto; max-width: 60em; color: #222; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.8em; text-align: left; }
+td.number { text-align: right; font-variant-numeric: tabular-nums; }
+.star { color: #d4a017; }
+svg { max-width: 100%; height: auto; }
+";
+
+/// Width and height of one grid cell in a visualization, in pixels.
+const CELL: usize = 4;
+
+/// Everything the report shows about one day.
+#[derive(Debug, Clone)]
+pub struct DayReport {
+    pub year: u16,
+    pub day: u8,
+    pub stars: usize,
+    /// Empty when the day has no input.
+    pub results: Vec<PartResult>,
+    pub profiles: Vec<PhaseProfile>,
+    pub benchmarks: Vec<Benchmark>,
+    pub tests: usize,
+    pub visualization: Option<String>,
+}
+
+/// Counts the `#[test]` functions in a day's sources, along with the tests
+/// `aoc_examples!` generates.
+pub fn count_tests(day_directory: &Path) -> usize {
+    let Ok(entries) = read_dir(day_directory.join("src")) else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| read_to_string(entry.ok()?.path()).ok())
+        .map(|source| count_source_tests(&source))
+        .sum()
+}
+
+/// Counts `#[test]` lines, plus one test per `function: "file" => expected`
+/// entry of each `aoc_examples!` invocation.
+fn count_source_tests(source: &str) -> usize {
+    let functions = source
+        .lines()
+        .filter(|line| "#[test]" == line.trim())
+        .count();
+    let mut examples = 0;
+    for (start, _) in source.match_indices("aoc_examples!") {
+        let invocation = source[start + "aoc_examples!".len()..].trim_start();
+        if !invocation.starts_with(['{', '(', '[']) {
+            continue;
+        }
+        let mut depth = 0;
+        let mut end = invocation.len();
+        for (index, character) in invocation.char_indices() {
+            match character {
+                '{' | '(' | '[' => depth += 1,
+                '}' | ')' | ']' => depth -= 1,
+                _ => {}
+            }
+            if 0 == depth {
+                end = index;
+                break;
+            }
+        }
+        examples += invocation[..end].matches("=>").count();
+    }
+    functions + examples
+}
+
+/// Solves, profiles and visualizes a day on its input, and looks up its
+/// benchmarks in `baseline`. Parts are never benchmarked here; run
+/// `aoc bench` for that. `timeout` overrides the day's limit in `config`.
+pub fn day_report(
+    allocator: &'static CountingAllocator,
+    root: &Path,
+    config: &Config,
+    database: &AnswerDatabase,
+    baseline: &Baseline,
+    solution: &Solution,
+    timeout: Option<f64>,
+) -> DayReport {
+    let (year, day) = (solution.year, solution.day);
+    let limit = config.time_limit(year, day, timeout);
+    let mut report = DayReport {
+        year,
+        day,
+        stars: (1..=2)
+            .filter(|part| database.is_solved(day, *part))
+            .count(),
+        results: Vec::new(),
+        profiles: Vec::new(),
+        benchmarks: (1..=2)
+            .filter_map(|part| baseline.get(day, part).cloned())
+            .collect(),
+        tests: count_tests(&workspace::day_directory(root, year, day)),
+        visualization: None,
+    };
+    let Ok(Some(input)) = vault::read_input(root, &config.inputs(root), year, day) else {
+        return report;
+    };
+    if !(solution.validate)(&input).is_empty() {
+        return report;
+    }
+    let arithmetic = Arithmetic::default();
+    report.results = (1..=2)
+        .map(|part| runner::solve_part(database, solution, part, &input, arithmetic, limit))
+        .collect();
+    report.profiles = profile::profile_day(allocator, solution, &input, arithmetic, limit);
+    if let Some(visualize) = solution.visualize {
+        let (picture, _) = run_on_worker(format!("day-{:02}-visualize", day), limit, move || {
+            visualize(&input)
+        });
+        report.visualization = picture.ok();
+    }
+    report
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>\n{STYLE}</style>\n</head>\n<body>\n{body}</body>\n</html>\n",
+        title = escape(title),
+    )
+}
+
+fn stars(count: usize) -> String {
+    format!("<span class=\"star\">{}</span>", "*".repeat(count))
+}
+
+fn colour(cell: char) -> Option<&'static str> {
+    match cell {
+        '.' => None,
+        '#' => Some("#444"),
+        'X' => Some("#c0392b"),
+        '0'..='9' => Some(
+            [
+                "#08306b", "#08519c", "#2171b5", "#4292c6", "#6baed6", "#9ecae1", "#74c476",
+                "#41ab5d", "#238b45", "#006d2c",
+            ][cell as usize - '0' as usize],
+        ),
+        _ => Some("#2e86c1"),
+    }
+}
+
+/// Draws a text grid from a day's `visualize` as an SVG image, one square per
+/// cell, merging runs of the same cell along each row.
+pub fn grid_svg(picture: &str) -> String {
+    let rows: Vec<Vec<char>> = picture.lines().map(|line| line.chars().collect()).collect();
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+         viewBox=\"0 0 {0} {1}\">\n<rect width=\"100%\" height=\"100%\" fill=\"#f4f4f4\"/>\n",
+        width * CELL,
+        rows.len() * CELL
+    );
+    for (y, row) in rows.iter().enumerate() {
+        let mut x = 0;
+        while x < row.len() {
+            let run = row[x..].iter().take_while(|cell| **cell == row[x]).count();
+            if let Some(fill) = colour(row[x]) {
+                let _ = writeln!(
+                    svg,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                    x * CELL,
+                    y * CELL,
+                    run * CELL,
+                    CELL,
+                    fill
+                );
+            }
+            x += run;
+        }
+    }
+    svg + "</svg>\n"
+}
+
+/// Draws each part's median time from the baselines as a bar on a log scale,
+/// so microsecond and multi-second parts fit on one chart.
+pub fn timing_chart(days: &[DayReport]) -> String {
+    let bars: Vec<(String, Duration)> = days
+        .iter()
+        .flat_map(|day| &day.benchmarks)
+        .map(|benchmark| {
+            (
+                format!("Day {:02} part {}", benchmark.day, benchmark.part),
+                benchmark.median(),
+            )
+        })
+        .collect();
+    if bars.is_empty() {
+        return "<p>No benchmarks yet. Run <code>aoc bench --all</code> first.</p>\n".to_string();
+    }
+    let (label_width, bar_width, bar_height) = (110.0, 480.0, 16.0);
+    let microseconds = |elapsed: &Duration| (elapsed.as_secs_f64() * 1e6).max(1.0);
+    let decades = bars
+        .iter()
+        .map(|(_, elapsed)| microseconds(elapsed).log10().ceil())
+        .fold(1.0, f64::max);
+    let height = bar_height * bars.len() as f64 + 20.0;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-size=\"11\">\n",
+        label_width + bar_width + 90.0,
+        height
+    );
+    for decade in 0..=decades as u32 {
+        let x = label_width + bar_width * f64::from(decade) / decades;
+        let label = [
+            "1µs", "10µs", "100µs", "1ms", "10ms", "100ms", "1s", "10s", "100s",
+        ]
+        .get(decade as usize)
+        .copied()
+        .unwrap_or("");
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{x}\" y1=\"0\" x2=\"{x}\" y2=\"{}\" stroke=\"#ddd\"/>\
+             <text x=\"{x}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+            height - 14.0,
+            height - 2.0,
+            label
+        );
+    }
+    for (index, (label, elapsed)) in bars.iter().enumerate() {
+        let y = bar_height * index as f64;
+        let width = (bar_width * microseconds(elapsed).log10() / decades).max(1.0);
+        let _ = writeln!(
+            svg,
+            "<text x=\"0\" y=\"{}\">{}</text>\
+             <rect x=\"{}\" y=\"{}\" width=\"{:.1}\" height=\"{}\" fill=\"#2e86c1\"/>\
+             <text x=\"{:.1}\" y=\"{}\">{:.2?}</text>",
+            y + 12.0,
+            label,
+            label_width,
+            y + 2.0,
+            width,
+            bar_height - 4.0,
+            label_width + width + 4.0,
+            y + 12.0,
+            elapsed
+        );
+    }
+    svg + "</svg>\n"
+}
+
+pub fn render_index(year: u16, days: &[DayReport]) -> String {
+    let mut body = format!("<h1>Advent of Code {}</h1>\n", year);
+    body += "<h2>Run times</h2>\n";
+    body += &timing_chart(days);
+    body += "<h2>Days</h2>\n<table>\n\
+             <tr><th>Day</th><th>Stars</th><th>Part 1 median</th><th>Part 2 median</th>\
+             <th>Tests</th></tr>\n";
+    for day in days {
+        let median = |part: u8| {
+            day.benchmarks
+                .iter()
+                .find(|benchmark| benchmark.part == part)
+                .map_or(String::new(), |benchmark| {
+                    format!("{:.2?}", benchmark.median())
+                })
+        };
+        let _ = writeln!(
+            body,
+            "<tr><td><a href=\"day-{:02}.html\">{}</a></td><td>{}</td>\
+             <td class=\"number\">{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td></tr>",
+            day.day,
+            day.day,
+            stars(day.stars),
+            median(1),
+            median(2),
+            day.tests
+        );
+    }
+    body += "</table>\n";
+    page(&format!("Advent of Code {}", year), &body)
+}
+
+pub fn render_day(day: &DayReport) -> String {
+    let mut body = format!(
+        "<p><a href=\"index.html\">All days</a></p>\n<h1>Day {} {}</h1>\n\
+         <p><a href=\"https://adventofcode.com/{}/day/{}\">Puzzle</a>. {} tests.</p>\n",
+        day.day,
+        stars(day.stars),
+        day.year,
+        day.day,
+        day.tests
+    );
+    body += "<h2>Answers</h2>\n";
+    if day.results.is_empty() {
+        body += "<p>No valid input.txt, so nothing was run.</p>\n";
+    } else {
+        body += "<table>\n<tr><th>Part</th><th>Answer</th><th>Check</th><th>Time</th></tr>\n";
+        for result in &day.results {
+            let _ = writeln!(
+                body,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"number\">{:.2?}</td></tr>",
+                result.part,
+                escape(&result.outcome.to_string()),
+                result.check.label(),
+                result.elapsed
+            );
+        }
+        body += "</table>\n";
+    }
+    body += "<h2>Benchmarks</h2>\n";
+    if day.benchmarks.is_empty() {
+        body += "<p>No baseline. Run <code>aoc bench</code> first.</p>\n";
+    } else {
+        body += "<table>\n<tr><th>Part</th><th>Median</th><th>Mean</th><th>Std. dev.</th>\
+                 <th>Min</th><th>Samples</th></tr>\n";
+        for benchmark in &day.benchmarks {
+            let _ = writeln!(
+                body,
+                "<tr><td>{}</td><td class=\"number\">{:.2?}</td><td class=\"number\">{:.2?}</td>\
+                 <td class=\"number\">{:.2?}</td><td class=\"number\">{:.2?}</td>\
+                 <td class=\"number\">{}</td></tr>",
+                benchmark.part,
+                benchmark.median(),
+                benchmark.mean(),
+                benchmark.standard_deviation(),
+                benchmark.min(),
+                benchmark.samples.len()
+            );
+        }
+        body += "</table>\n";
+    }
+    if !day.profiles.is_empty() {
+        body += "<h2>Memory</h2>\n<table>\n<tr><th>Phase</th><th>Allocations</th>\
+                 <th>Allocated</th><th>Peak</th></tr>\n";
+        for profile in &day.profiles {
+            let _ = writeln!(
+                body,
+                "<tr><td>{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td>\
+                 <td class=\"number\">{}</td></tr>",
+                profile.phase,
+                profile.memory.allocations,
+                format_bytes(profile.memory.bytes),
+                format_bytes(profile.memory.peak)
+            );
+        }
+        body += "</table>\n";
+    }
+    if let Some(picture) = &day.visualization {
+        body += "<h2>Visualization</h2>\n";
+        body += &grid_svg(picture);
+    }
+    page(&format!("Day {}", day.day), &body)
+}
+
+/// Writes `index.html` and a `day-NN.html` per day of `year` into `out`.
+pub fn write_site(out: &Path, year: u16, days: &[DayReport]) -> io::Result<()> {
+    create_dir_all(out)?;
+    write(out.join("index.html"), render_index(year, days))?;
+    for day in days {
+        write(
+            out.join(format!("day-{:02}.html", day.day)),
+            render_day(day),
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+    use std::env::temp_dir;
+    use std::fs::remove_dir_all;
+    use std::path::PathBuf;
+
+    static ALLOCATOR: CountingAllocator = CountingAllocator::new();
+
+    fn scratch(name: &str) -> PathBuf {
+        let directory = temp_dir().join(format!("aoc-report-{}-{}", name, std::process::id()));
+        let _ = remove_dir_all(&directory);
+        create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    fn benchmark(day: u8, part: u8, micros: u64) -> Benchmark {
+        Benchmark {
+            day,
+            part,
+            samples: vec![Duration::from_micros(micros)],
+        }
+    }
+
+    fn empty_day(day: u8) -> DayReport {
+        DayReport {
+            year: 2024,
+            day,
+            stars: 1,
+            results: Vec::new(),
+            profiles: Vec::new(),
+            benchmarks: Vec::new(),
+            tests: 4,
+            visualization: None,
+        }
+    }
+
+    #[test]
+    fn it_counts_tests() {
+        let root = workspace::find_root(Path::new(env!("CARGO_MANIFEST_DIR"))).unwrap();
+        assert!(0 < count_tests(&workspace::day_directory(&root, 2024, 1)));
+        assert_eq!(0, count_tests(&root.join("missing")));
+        let source = "use common::aoc_examples;\n\
+                      #[test]\nfn it_works() {}\n\
+                      aoc_examples! {\n    part1: \"example.txt\" => 143,\n    \
+                      part2: \"example.txt\" => \"6,1\",\n}\n\
+                      #[test]\nfn it_still_works() {}\n";
+        assert_eq!(4, count_source_tests(source));
+        assert_eq!(0, count_source_tests("// aoc_examples! needs entries\n"));
+    }
+
+    #[test]
+    fn it_draws_grids() {
+        let svg = grid_svg("..##X\n01\n");
+        assert!(
+            svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"20\" height=\"8\"")
+        );
+        assert!(svg.contains("<rect x=\"8\" y=\"0\" width=\"8\" height=\"4\" fill=\"#444\"/>"));
+        assert!(svg.contains("<rect x=\"16\" y=\"0\" width=\"4\" height=\"4\" fill=\"#c0392b\"/>"));
+        assert!(svg.contains("<rect x=\"4\" y=\"4\" width=\"4\" height=\"4\" fill=\"#08519c\"/>"));
+        assert_eq!(5, svg.matches("<rect").count());
+    }
+
+    #[test]
+    fn it_charts_baseline_timings() {
+        assert!(timing_chart(&[empty_day(1)]).contains("aoc bench --all"));
+        let mut day = empty_day(6);
+        day.benchmarks = vec![benchmark(6, 1, 10), benchmark(6, 2, 2_000_000)];
+        let chart = timing_chart(&[day]);
+        assert!(chart.contains("Day 06 part 1"));
+        assert!(chart.contains("2.00s"));
+        assert!(chart.contains(">10s<"));
+        assert!(!chart.contains(">100s<"));
+    }
+
+    #[test]
+    fn it_renders_pages() {
+        let mut day = empty_day(6);
+        day.benchmarks = vec![benchmark(6, 2, 1500)];
+        day.visualization = Some("#X\n".to_string());
+        let index = render_index(2024, &[day.clone()]);
+        assert!(index.contains("<h1>Advent of Code 2024</h1>"));
+        assert!(index.contains("<a href=\"day-06.html\">6</a>"));
+        assert!(index.contains("<td class=\"number\">1.50ms</td>"));
+        for external in ["<script", "<link", "src="] {
+            assert!(!index.contains(external));
+        }
+        let page = render_day(&day);
+        assert!(page.contains("No valid input.txt"));
+        assert!(page.contains("<h2>Visualization</h2>\n<svg"));
+        assert!(page.contains("<span class=\"star\">*</span>"));
+        assert!(page.contains("https://adventofcode.com/2024/day/6"));
+        assert_eq!("&lt;a &amp; &quot;b&quot;&gt;", escape("<a & \"b\">"));
+    }
+
+    #[test]
+    fn it_reports_on_days() {
+        let root = scratch("days");
+        let inputs = root.join("inputs");
+        create_dir_all(workspace::day_directory(&inputs, 2024, 10)).unwrap();
+        write(workspace::input_path(&inputs, 2024, 10), "0123456789\n").unwrap();
+        let database = AnswerDatabase::load(&workspace::answers_path(&root, 2024)).unwrap();
+        let mut baseline = Baseline::load(&workspace::baseline_path(&root, 2024)).unwrap();
+        baseline.insert(benchmark(10, 1, 5));
+        let solution = registry::find(2024, 10).unwrap();
+        let config = Config {
+            input_dir: Some(inputs),
+            ..Config::default()
+        };
+        let report = day_report(
+            &ALLOCATOR, &root, &config, &database, &baseline, &solution, None,
+        );
+        assert_eq!(2, report.results.len());
+        assert_eq!(2, report.profiles.len());
+        assert_eq!(1, report.benchmarks.len());
+        assert_eq!(Some("0123456789\n".to_string()), report.visualization);
+        let missing = day_report(
+            &ALLOCATOR,
+            &root,
+            &config,
+            &database,
+            &baseline,
+            &registry::find(2024, 6).unwrap(),
+            None,
+        );
+        assert!(missing.results.is_empty());
+        let out = root.join("site");
+        write_site(&out, 2024, &[report, missing]).unwrap();
+        assert!(read_to_string(out.join("index.html"))
+            .unwrap()
+            .contains("day-10.html"));
+        assert!(read_to_string(out.join("day-10.html"))
+            .unwrap()
+            .contains("<svg"));
+        assert!(out.join("day-06.html").is_file());
+        remove_dir_all(root).unwrap();
+    }
+}
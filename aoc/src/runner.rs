@@ -0,0 +1,610 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::answers::{AnswerDatabase, Check};
+use crate::registry::Solution;
+use clap::ValueEnum;
+use common::{Answer, Arithmetic, Explanation, Overflow};
+use serde_json::json;
+use std::any::Any;
+use std::fmt;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Solutions recurse, so workers get more stack than the 2 MiB default.
+const WORKER_STACK_SIZE: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Status {
+    Unverified,
+    Correct,
+    Incorrect,
+    TooHigh,
+    TooLow,
+    Overflow,
+    Timeout,
+    Panicked,
+}
+
+impl Status {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Unverified => "unverified",
+            Self::Correct => "correct",
+            Self::Incorrect => "incorrect",
+            Self::TooHigh => "too_high",
+            Self::TooLow => "too_low",
+            Self::Overflow => "overflow",
+            Self::Timeout => "timeout",
+            Self::Panicked => "panicked",
+        }
+    }
+}
+
+/// What running a part produced.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Outcome {
+    Solved(Answer),
+    Overflow,
+    Timeout,
+    Panicked(String),
+}
+
+impl Outcome {
+    pub fn answer(&self) -> Option<&Answer> {
+        match self {
+            Self::Solved(answer) => Some(answer),
+            _ => None,
+        }
+    }
+
+    pub fn error(&self) -> Option<String> {
+        match self {
+            Self::Solved(_) => None,
+            Self::Overflow => Some("arithmetic overflow".to_string()),
+            Self::Timeout => Some("time limit exceeded".to_string()),
+            Self::Panicked(message) => Some(message.clone()),
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Solved(answer) => write!(f, "{}", answer),
+            Self::Overflow => write!(f, "OVERFLOW"),
+            Self::Timeout => write!(f, "TIMEOUT"),
+            Self::Panicked(message) => write!(f, "PANIC: {}", message),
+        }
+    }
+}
+
+impl From<Result<Answer, Overflow>> for Outcome {
+    fn from(result: Result<Answer, Overflow>) -> Self {
+        match result {
+            Ok(answer) => Self::Solved(answer),
+            Err(Overflow) => Self::Overflow,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PartResult {
+    pub day: u8,
+    pub part: u8,
+    pub outcome: Outcome,
+    pub elapsed: Duration,
+    /// How the answer compares with the answer database; `Unknown` unless
+    /// the part was solved.
+    pub check: Check,
+}
+
+impl PartResult {
+    pub fn status(&self) -> Status {
+        match (&self.outcome, self.check) {
+            (Outcome::Overflow, _) => Status::Overflow,
+            (Outcome::Timeout, _) => Status::Timeout,
+            (Outcome::Panicked(_), _) => Status::Panicked,
+            (Outcome::Solved(_), Check::Unknown) => Status::Unverified,
+            (Outcome::Solved(_), Check::Correct) => Status::Correct,
+            (Outcome::Solved(_), Check::Incorrect) => Status::Incorrect,
+            (Outcome::Solved(_), Check::TooHigh) => Status::TooHigh,
+            (Outcome::Solved(_), Check::TooLow) => Status::TooLow,
+        }
+    }
+}
+
+/// The message a panic was raised with, for reporting it.
+pub fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Runs `job` on its own worker thread, reporting a panic or a `limit`
+/// that passes first as the matching `Outcome`.
+///
+/// A worker that runs out of time is abandoned, not stopped: it keeps running
+/// until it finishes or the process exits.
+pub(crate) fn run_on_worker<T: Send + 'static>(
+    name: String,
+    limit: Option<Duration>,
+    job: impl FnOnce() -> T + Send + 'static,
+) -> (Result<T, Outcome>, Duration) {
+    let (sender, receiver) = mpsc::channel();
+    let start = Instant::now();
+    thread::Builder::new()
+        .name(name)
+        .stack_size(WORKER_STACK_SIZE)
+        .spawn(move || {
+            let result = catch_unwind(AssertUnwindSafe(job))
+                .map_err(|payload| Outcome::Panicked(panic_message(payload)));
+            let _ = sender.send((result, start.elapsed()));
+        })
+        .expect("Unable to spawn worker thread");
+    let received = match limit {
+        Some(limit) => receiver.recv_timeout(limit).ok(),
+        None => receiver.recv().ok(),
+    };
+    received.unwrap_or_else(|| (Err(Outcome::Timeout), start.elapsed()))
+}
+
+/// Solves a part on the current thread, using its overflow-checked version on
+/// `arithmetic` when the day has one.
+pub(crate) fn solve(
+    solution: &Solution,
+    part: u8,
+    input: String,
+    arithmetic: Arithmetic,
+) -> Outcome {
+    match solution.checked_part(part) {
+        Some(checked) => Outcome::from(checked(input, arithmetic)),
+        None => {
+            let function = solution
+                .part(part)
+                .unwrap_or_else(|| panic!("Day {} has no part {}", solution.day, part));
+            Outcome::Solved(function(input))
+        }
+    }
+}
+
+/// Solves a part on a worker thread with an optional time limit.
+pub fn run_part(
+    solution: &Solution,
+    part: u8,
+    input: &str,
+    arithmetic: Arithmetic,
+    limit: Option<Duration>,
+) -> (Outcome, Duration) {
+    if solution.part(part).is_none() {
+        panic!("Day {} has no part {}", solution.day, part);
+    }
+    let solution = *solution;
+    let input = input.to_string();
+    let (result, elapsed) = run_on_worker(
+        format!("day-{:02}-part-{}", solution.day, part),
+        limit,
+        move || solve(&solution, part, input, arithmetic),
+    );
+    (result.unwrap_or_else(|failure| failure), elapsed)
+}
+
+/// Runs a part and checks its answer against the answer database.
+pub fn solve_part(
+    database: &AnswerDatabase,
+    solution: &Solution,
+    part: u8,
+    input: &str,
+    arithmetic: Arithmetic,
+    limit: Option<Duration>,
+) -> PartResult {
+    let (outcome, elapsed) = run_part(solution, part, input, arithmetic, limit);
+    let check = outcome.answer().map_or(Check::Unknown, |answer| {
+        database.check(solution.day, part, answer)
+    });
+    PartResult {
+        day: solution.day,
+        part,
+        outcome,
+        elapsed,
+        check,
+    }
+}
+
+/// Runs a day's `explain` for one part on a worker thread, keeping its first
+/// `lines` lines.
+pub fn explain_part(
+    solution: &Solution,
+    part: u8,
+    input: &str,
+    lines: usize,
+    limit: Option<Duration>,
+) -> Result<Explanation, Outcome> {
+    let explain = solution
+        .explain
+        .unwrap_or_else(|| panic!("Day {} has no explanation", solution.day));
+    let input = input.to_string();
+    run_on_worker(
+        format!("day-{:02}-part-{}-explain", solution.day, part),
+        limit,
+        move || {
+            let mut explanation = Explanation::new(lines);
+            explain(&input, part, &mut explanation);
+            explanation
+        },
+    )
+    .0
+}
+
+pub fn render(results: &[PartResult], format: Format) -> String {
+    render_explained(results, &[], format)
+}
+
+/// Renders results with the explanation at the same index in `explanations`
+/// under each; results past its end, or with `None`, have none.
+pub fn render_explained(
+    results: &[PartResult],
+    explanations: &[Option<Result<Explanation, Outcome>>],
+    format: Format,
+) -> String {
+    let explanation = |index: usize| explanations.get(index).and_then(Option::as_ref);
+    match format {
+        Format::Text => results
+            .iter()
+            .enumerate()
+            .map(|(index, result)| {
+                let status = match (&result.outcome, result.check) {
+                    (Outcome::Solved(_), check) if Check::Unknown != check => {
+                        format!(" ({})", check.label())
+                    }
+                    _ => String::new(),
+                };
+                let mut rendered = format!(
+                    "Day {:02} Part {}: {} [{:.3?}]{}\n",
+                    result.day, result.part, result.outcome, result.elapsed, status
+                );
+                match explanation(index) {
+                    Some(Ok(explanation)) => {
+                        for line in explanation.to_string().lines() {
+                            rendered.push_str(&format!("    {}\n", line));
+                        }
+                    }
+                    Some(Err(failure)) => rendered.push_str(&format!(
+                        "    explanation failed: {}\n",
+                        failure.error().unwrap_or_default()
+                    )),
+                    None => {}
+                }
+                rendered
+            })
+            .collect(),
+        Format::Json => {
+            let results: Vec<_> = results
+                .iter()
+                .enumerate()
+                .map(|(index, result)| {
+                    let mut rendered = json!({
+                        "day": result.day,
+                        "part": result.part,
+                        "answer": result.outcome.answer().map(|answer| answer.to_string()),
+                        "elapsed_ms": result.elapsed.as_secs_f64() * 1000.0,
+                        "status": result.status().label(),
+                        "error": result.outcome.error(),
+                    });
+                    match explanation(index) {
+                        Some(Ok(explanation)) => {
+                            rendered["explanation"] = json!({
+                                "lines": explanation.lines(),
+                                "omitted": explanation.omitted(),
+                            })
+                        }
+                        Some(Err(failure)) => {
+                            rendered["explanation"] = json!({ "error": failure.error() })
+                        }
+                        None => {}
+                    }
+                    rendered
+                })
+                .collect();
+            format!("{}\n", serde_json::Value::Array(results))
+        }
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::registry::{Checked, Shape};
+
+    fn fake_solution() -> Solution {
+        Solution {
+            year: 2024,
+            day: 1,
+            part1: |input| Answer::from(input.len()),
+            part2: |input| Answer::from(input),
+            checked: None,
+            parse: None,
+            variants: &[],
+            validate: |_| Vec::new(),
+            shape: Shape::Lines,
+            visualize: None,
+            explain: None,
+        }
+    }
+
+    fn result(check: Check) -> PartResult {
+        PartResult {
+            day: 1,
+            part: 2,
+            outcome: Outcome::Solved(Answer::from("6,1")),
+            elapsed: Duration::from_millis(2),
+            check,
+        }
+    }
+
+    #[test]
+    fn it_runs_parts() {
+        let solution = fake_solution();
+        assert_eq!(
+            Outcome::Solved(Answer::from(3)),
+            run_part(&solution, 1, "abc", Arithmetic::U64, None).0
+        );
+        assert_eq!(
+            Outcome::Solved(Answer::from("abc")),
+            run_part(&solution, 2, "abc", Arithmetic::U64, None).0
+        );
+    }
+
+    #[test]
+    fn it_prefers_checked_parts() {
+        let solution = Solution {
+            checked: Some(Checked {
+                part1: |_, arithmetic| match arithmetic {
+                    Arithmetic::U64 => Err(Overflow),
+                    _ => Ok(Answer::from(u64::MAX as u128 + 1)),
+                },
+                part2: |_, _| Ok(Answer::from(2)),
+            }),
+            ..fake_solution()
+        };
+        assert_eq!(
+            Outcome::Overflow,
+            run_part(&solution, 1, "abc", Arithmetic::U64, None).0
+        );
+        assert_eq!(
+            Outcome::Solved(Answer::from(u64::MAX as u128 + 1)),
+            run_part(&solution, 1, "abc", Arithmetic::Big, None).0
+        );
+    }
+
+    #[test]
+    fn it_times_out_slow_parts() {
+        let solution = Solution {
+            part1: |_| loop {
+                thread::sleep(Duration::from_secs(1));
+            },
+            ..fake_solution()
+        };
+        let (outcome, elapsed) = run_part(
+            &solution,
+            1,
+            "abc",
+            Arithmetic::U64,
+            Some(Duration::from_millis(50)),
+        );
+        assert_eq!(Outcome::Timeout, outcome);
+        assert!(elapsed < Duration::from_secs(1));
+        assert_eq!(
+            Outcome::Solved(Answer::from(3)),
+            run_part(
+                &fake_solution(),
+                1,
+                "abc",
+                Arithmetic::U64,
+                Some(Duration::from_secs(10))
+            )
+            .0
+        );
+    }
+
+    #[test]
+    fn it_reports_panics() {
+        let solution = Solution {
+            part1: |input| panic!("Bad input {}", input),
+            part2: |_| std::panic::panic_any(7),
+            ..fake_solution()
+        };
+        assert_eq!(
+            Outcome::Panicked("Bad input abc".to_string()),
+            run_part(&solution, 1, "abc", Arithmetic::U64, None).0
+        );
+        assert_eq!(
+            Outcome::Panicked("unknown panic".to_string()),
+            run_part(&solution, 2, "abc", Arithmetic::U64, None).0
+        );
+        let checked = Solution {
+            checked: Some(Checked {
+                part1: |_, _| panic!("static message"),
+                part2: |_, _| Ok(Answer::from(2)),
+            }),
+            ..fake_solution()
+        };
+        assert_eq!(
+            Outcome::Panicked("static message".to_string()),
+            run_part(&checked, 1, "abc", Arithmetic::U64, None).0
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Day 1 has no part 3")]
+    fn it_rejects_unknown_parts() {
+        run_part(&fake_solution(), 3, "abc", Arithmetic::U64, None);
+    }
+
+    #[test]
+    fn it_compares_against_known_answers() {
+        assert_eq!(Status::Unverified, result(Check::Unknown).status());
+        assert_eq!(Status::Correct, result(Check::Correct).status());
+        assert_eq!(Status::Incorrect, result(Check::Incorrect).status());
+        assert_eq!(Status::TooHigh, result(Check::TooHigh).status());
+        assert_eq!(Status::TooLow, result(Check::TooLow).status());
+        let overflow = PartResult {
+            outcome: Outcome::Overflow,
+            ..result(Check::Unknown)
+        };
+        assert_eq!(Status::Overflow, overflow.status());
+    }
+
+    #[test]
+    fn it_renders_text() {
+        assert_eq!(
+            "Day 01 Part 2: 6,1 [2.000ms] (incorrect)\n",
+            render(&[result(Check::Incorrect)], Format::Text)
+        );
+        assert_eq!(
+            "Day 01 Part 2: 6,1 [2.000ms] (too high)\n",
+            render(&[result(Check::TooHigh)], Format::Text)
+        );
+        assert_eq!(
+            "Day 01 Part 2: 6,1 [2.000ms] (correct)\n",
+            render(&[result(Check::Correct)], Format::Text)
+        );
+    }
+
+    #[test]
+    fn it_renders_overflow() {
+        let overflow = PartResult {
+            outcome: Outcome::Overflow,
+            ..result(Check::Unknown)
+        };
+        assert_eq!(
+            "Day 01 Part 2: OVERFLOW [2.000ms]\n",
+            render(std::slice::from_ref(&overflow), Format::Text)
+        );
+        let rendered: serde_json::Value =
+            serde_json::from_str(&render(&[overflow], Format::Json)).unwrap();
+        assert_eq!(json!(null), rendered[0]["answer"]);
+        assert_eq!(json!("overflow"), rendered[0]["status"]);
+        assert_eq!(json!("arithmetic overflow"), rendered[0]["error"]);
+    }
+
+    #[test]
+    fn it_renders_timeouts_and_panics() {
+        let timeout = PartResult {
+            outcome: Outcome::Timeout,
+            ..result(Check::Unknown)
+        };
+        let panicked = PartResult {
+            outcome: Outcome::Panicked("Bad input".to_string()),
+            ..result(Check::Unknown)
+        };
+        assert_eq!(Status::Timeout, timeout.status());
+        assert_eq!(Status::Panicked, panicked.status());
+        assert_eq!(
+            "Day 01 Part 2: TIMEOUT [2.000ms]\nDay 01 Part 2: PANIC: Bad input [2.000ms]\n",
+            render(&[timeout.clone(), panicked.clone()], Format::Text)
+        );
+        let rendered: serde_json::Value =
+            serde_json::from_str(&render(&[timeout, panicked], Format::Json)).unwrap();
+        assert_eq!(json!("timeout"), rendered[0]["status"]);
+        assert_eq!(json!("time limit exceeded"), rendered[0]["error"]);
+        assert_eq!(json!(null), rendered[0]["answer"]);
+        assert_eq!(json!("panicked"), rendered[1]["status"]);
+        assert_eq!(json!("Bad input"), rendered[1]["error"]);
+    }
+
+    #[test]
+    fn it_renders_json() {
+        let rendered: serde_json::Value =
+            serde_json::from_str(&render(&[result(Check::Unknown)], Format::Json)).unwrap();
+        assert_eq!(
+            json!([{
+                "day": 1,
+                "part": 2,
+                "answer": "6,1",
+                "elapsed_ms": 2.0,
+                "status": "unverified",
+                "error": null,
+            }]),
+            rendered
+        );
+    }
+
+    #[test]
+    fn it_explains_parts() {
+        let solution = Solution {
+            explain: Some(|input, part, explanation| {
+                for character in input.chars() {
+                    explanation.line(format_args!("part {} saw {}", part, character));
+                }
+            }),
+            ..fake_solution()
+        };
+        let explanation = explain_part(&solution, 2, "abc", 2, None).unwrap();
+        assert_eq!(["part 2 saw a", "part 2 saw b"], explanation.lines());
+        assert_eq!(1, explanation.omitted());
+        let panicking = Solution {
+            explain: Some(|_, _, _| panic!("No explanation")),
+            ..fake_solution()
+        };
+        assert_eq!(
+            Err(Outcome::Panicked("No explanation".to_string())),
+            explain_part(&panicking, 1, "abc", 2, None)
+        );
+    }
+
+    #[test]
+    fn it_renders_explanations() {
+        let mut explanation = Explanation::new(1);
+        explanation.line("first");
+        explanation.line("second");
+        let explanations = [Some(Ok(explanation)), Some(Err(Outcome::Timeout))];
+        let results = [
+            result(Check::Unknown),
+            result(Check::Unknown),
+            result(Check::Unknown),
+        ];
+        assert_eq!(
+            "Day 01 Part 2: 6,1 [2.000ms]\n    first\n    ... and 1 more\n\
+             Day 01 Part 2: 6,1 [2.000ms]\n    explanation failed: time limit exceeded\n\
+             Day 01 Part 2: 6,1 [2.000ms]\n",
+            render_explained(&results, &explanations, Format::Text)
+        );
+        let rendered: serde_json::Value =
+            serde_json::from_str(&render_explained(&results, &explanations, Format::Json)).unwrap();
+        assert_eq!(
+            json!({ "lines": ["first"], "omitted": 1 }),
+            rendered[0]["explanation"]
+        );
+        assert_eq!(
+            json!({ "error": "time limit exceeded" }),
+            rendered[1]["explanation"]
+        );
+        assert_eq!(json!(null), rendered[2]["explanation"]);
+    }
+}
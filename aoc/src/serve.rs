@@ -0,0 +1,236 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::registry::{self, DEFAULT_VARIANT};
+use crate::runner::{run_part, Outcome};
+use common::Arithmetic;
+use serde_json::{json, Value};
+use std::io;
+use std::net::SocketAddr;
+use std::thread;
+use std::time::Duration;
+use tiny_http::{Header, Method, Request, Response};
+
+/// Serves the registered solutions over HTTP:
+///
+/// - `GET /health` answers `{"status": "ok"}`
+/// - `GET /days` lists every day of every year with its variants
+/// - `POST /days/{day}/parts/{part}` solves the request body, optionally with
+///   `?year=2024`, `?variant=name` and `?arithmetic=u64|u128|big`, after
+///   checking its shape; the year defaults to the latest registered one
+pub struct Server {
+    server: tiny_http::Server,
+    limit: Option<Duration>,
+}
+
+impl Server {
+    /// Listens on `address`; port 0 picks a free port.
+    pub fn bind(address: &str, limit: Option<Duration>) -> io::Result<Self> {
+        let server = tiny_http::Server::http(address).map_err(io::Error::other)?;
+        Ok(Self { server, limit })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.server
+            .server_addr()
+            .to_ip()
+            .expect("Server is not listening on TCP")
+    }
+
+    /// Handles requests until the process exits, each on its own thread so a
+    /// slow solve does not hold up health checks.
+    pub fn run(self) {
+        for request in self.server.incoming_requests() {
+            let limit = self.limit;
+            thread::spawn(move || handle(request, limit));
+        }
+    }
+}
+
+fn handle(mut request: Request, limit: Option<Duration>) {
+    let mut body = String::new();
+    let (status, json) = match request.as_reader().read_to_string(&mut body) {
+        Ok(_) => respond(request.method(), request.url(), &body, limit),
+        Err(_) => error(400, "Body must be UTF-8"),
+    };
+    let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+    let response = Response::from_string(format!("{}\n", json))
+        .with_status_code(status)
+        .with_header(header);
+    let _ = request.respond(response);
+}
+
+fn error(status: u16, message: &str) -> (u16, Value) {
+    (status, json!({ "error": message }))
+}
+
+/// Routes one request, returning its status code and JSON body.
+pub fn respond(method: &Method, url: &str, body: &str, limit: Option<Duration>) -> (u16, Value) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let segments: Vec<&str> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    match (method, segments.as_slice()) {
+        (Method::Get, ["health"]) => (200, json!({ "status": "ok" })),
+        (Method::Get, ["days"]) => (200, list_days()),
+        (Method::Post, ["days", day, "parts", part]) => solve(day, part, query, body, limit),
+        (_, ["health"] | ["days"] | ["days", _, "parts", _]) => error(405, "Method not allowed"),
+        _ => error(404, "Not found"),
+    }
+}
+
+fn list_days() -> Value {
+    let days: Vec<_> = registry::solutions()
+        .iter()
+        .map(|solution| {
+            json!({
+                "year": solution.year,
+                "day": solution.day,
+                "checked": solution.checked.is_some(),
+                "variants": {
+                    "1": solution.variant_names(1),
+                    "2": solution.variant_names(2),
+                },
+            })
+        })
+        .collect();
+    Value::Array(days)
+}
+
+fn solve(day: &str, part: &str, query: &str, input: &str, limit: Option<Duration>) -> (u16, Value) {
+    let (Ok(day), Ok(part)) = (day.parse::<u8>(), part.parse::<u8>()) else {
+        return error(404, "Not found");
+    };
+    let mut year = registry::latest_year();
+    let mut variant = DEFAULT_VARIANT;
+    let mut arithmetic = Arithmetic::default();
+    for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        match key {
+            "year" => match value.parse() {
+                Ok(value) => year = value,
+                Err(_) => return error(400, "Unknown year"),
+            },
+            "variant" => variant = value,
+            "arithmetic" => match value.parse() {
+                Ok(value) => arithmetic = value,
+                Err(_) => return error(400, "Unknown arithmetic"),
+            },
+            _ => return error(400, "Unknown query parameter"),
+        }
+    }
+    let Some(solution) = registry::find(year, day) else {
+        return error(404, "Day is not registered");
+    };
+    let Some(solution) = solution.with_variant(part, variant) else {
+        return error(404, "Unknown part or variant");
+    };
+    let problems = (solution.validate)(input);
+    if !problems.is_empty() {
+        let problems: Vec<String> = problems.iter().map(ToString::to_string).collect();
+        return (
+            400,
+            json!({ "error": "Input is invalid", "problems": problems }),
+        );
+    }
+    let (outcome, elapsed) = run_part(&solution, part, input, arithmetic, limit);
+    let status = match outcome {
+        Outcome::Solved(_) => 200,
+        Outcome::Overflow => 422,
+        Outcome::Panicked(_) => 500,
+        Outcome::Timeout => 504,
+    };
+    (
+        status,
+        json!({
+            "year": year,
+            "day": day,
+            "part": part,
+            "variant": variant,
+            "answer": outcome.answer().map(|answer| answer.to_string()),
+            "elapsed_ms": elapsed.as_secs_f64() * 1000.0,
+            "error": outcome.error(),
+        }),
+    )
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_checks_health() {
+        assert_eq!(
+            (200, json!({ "status": "ok" })),
+            respond(&Method::Get, "/health", "", None)
+        );
+    }
+
+    #[test]
+    fn it_lists_days() {
+        let (status, days) = respond(&Method::Get, "/days", "", None);
+        assert_eq!(200, status);
+        assert_eq!(2024, days[0]["year"]);
+        assert_eq!(1, days[0]["day"]);
+        assert_eq!(json!(["default", "counts"]), days[0]["variants"]["2"]);
+        assert_eq!(true, days[6]["checked"]);
+    }
+
+    #[test]
+    fn it_solves_parts() {
+        let (status, result) = respond(&Method::Post, "/days/11/parts/1", "125 17", None);
+        assert_eq!(200, status);
+        assert_eq!("55312", result["answer"]);
+        assert_eq!(Value::Null, result["error"]);
+    }
+
+    #[test]
+    fn it_takes_query_options() {
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3";
+        let (status, result) =
+            respond(&Method::Post, "/days/1/parts/2?variant=counts", input, None);
+        assert_eq!(200, status);
+        assert_eq!("31", result["answer"]);
+        assert_eq!("counts", result["variant"]);
+        let (status, _) = respond(&Method::Post, "/days/1/parts/2?arithmetic=i8", input, None);
+        assert_eq!(400, status);
+        let (status, result) = respond(&Method::Post, "/days/1/parts/1?year=2024", input, None);
+        assert_eq!(200, status);
+        assert_eq!(2024, result["year"]);
+        let (status, _) = respond(&Method::Post, "/days/1/parts/1?year=2015", input, None);
+        assert_eq!(404, status);
+        let (status, _) = respond(&Method::Post, "/days/1/parts/1?year=next", input, None);
+        assert_eq!(400, status);
+    }
+
+    #[test]
+    fn it_rejects_unknown_routes() {
+        assert_eq!(404, respond(&Method::Get, "/nope", "", None).0);
+        assert_eq!(404, respond(&Method::Post, "/days/26/parts/1", "", None).0);
+        assert_eq!(404, respond(&Method::Post, "/days/1/parts/3", "", None).0);
+        assert_eq!(404, respond(&Method::Post, "/days/x/parts/1", "", None).0);
+    }
+
+    #[test]
+    fn it_rejects_invalid_input() {
+        let (status, result) = respond(&Method::Post, "/days/6/parts/1", "..#\n...", None);
+        assert_eq!(400, status);
+        assert_eq!(
+            json!(["expected exactly one guard '^', found 0"]),
+            result["problems"]
+        );
+        assert_eq!(405, respond(&Method::Delete, "/days", "", None).0);
+    }
+}
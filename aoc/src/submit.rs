@@ -0,0 +1,146 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::Answer;
+use std::env;
+use std::io;
+use std::process::Command;
+
+/// What the site said about a submitted answer.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Incorrect,
+    TooRecent,
+    AlreadySolved,
+    Unknown(String),
+}
+
+impl Verdict {
+    pub fn from_response(response: &str) -> Self {
+        if response.contains("That's the right answer") {
+            Self::Correct
+        } else if response.contains("your answer is too high") {
+            Self::TooHigh
+        } else if response.contains("your answer is too low") {
+            Self::TooLow
+        } else if response.contains("That's not the right answer") {
+            Self::Incorrect
+        } else if response.contains("You gave an answer too recently") {
+            Self::TooRecent
+        } else if response.contains("You don't seem to be solving the right level") {
+            Self::AlreadySolved
+        } else {
+            Self::Unknown(response.to_string())
+        }
+    }
+}
+
+/// Runs `curl` (or `$CURL`) against adventofcode.com with the session
+/// cookie, returning what it printed.
+fn curl(cookie: &str, arguments: &[String]) -> io::Result<String> {
+    let output = Command::new(env::var("CURL").unwrap_or_else(|_| "curl".to_string()))
+        .arg("--silent")
+        .arg("--cookie")
+        .arg(cookie)
+        .args(arguments)
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "curl exited with {}",
+            output.status
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Posts an answer with `cookie`, a `session=<value>` cookie.
+pub fn submit(year: u16, day: u8, part: u8, answer: &Answer, cookie: &str) -> io::Result<Verdict> {
+    let response = curl(
+        cookie,
+        &[
+            "--data".to_string(),
+            format!("level={}", part),
+            "--data-urlencode".to_string(),
+            format!("answer={}", answer),
+            format!("https://adventofcode.com/{}/day/{}/answer", year, day),
+        ],
+    )?;
+    Ok(Verdict::from_response(&response))
+}
+
+/// Downloads one of a day's pages. The site answers an error status for a
+/// day that has not unlocked or a cookie it does not accept.
+fn fetch(year: u16, day: u8, cookie: &str, url: String) -> io::Result<String> {
+    curl(cookie, &["--fail".to_string(), url]).map_err(|error| {
+        io::Error::other(format!(
+            "{}; check that day {} of {} has unlocked and the session cookie is current",
+            error, day, year
+        ))
+    })
+}
+
+pub fn fetch_input(year: u16, day: u8, cookie: &str) -> io::Result<String> {
+    let url = format!("https://adventofcode.com/{}/day/{}/input", year, day);
+    fetch(year, day, cookie, url)
+}
+
+/// Downloads a day's puzzle page, which includes part 2 once part 1 is
+/// solved.
+pub fn fetch_puzzle(year: u16, day: u8, cookie: &str) -> io::Result<String> {
+    let url = format!("https://adventofcode.com/{}/day/{}", year, day);
+    fetch(year, day, cookie, url)
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_verdicts() {
+        assert_eq!(
+            Verdict::Correct,
+            Verdict::from_response("<p>That's the right answer! You are one gold star closer")
+        );
+        assert_eq!(
+            Verdict::TooHigh,
+            Verdict::from_response(
+                "<p>That's not the right answer; your answer is too high. If you're stuck"
+            )
+        );
+        assert_eq!(
+            Verdict::TooLow,
+            Verdict::from_response("<p>That's not the right answer; your answer is too low.")
+        );
+        assert_eq!(
+            Verdict::Incorrect,
+            Verdict::from_response("<p>That's not the right answer. If you're stuck")
+        );
+        assert_eq!(
+            Verdict::TooRecent,
+            Verdict::from_response("<p>You gave an answer too recently; you have to wait")
+        );
+        assert_eq!(
+            Verdict::AlreadySolved,
+            Verdict::from_response("<p>You don't seem to be solving the right level.")
+        );
+        assert_eq!(
+            Verdict::Unknown("<p>???".to_string()),
+            Verdict::from_response("<p>???")
+        );
+    }
+}
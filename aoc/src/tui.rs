@@ -0,0 +1,689 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The `aoc tui` dashboard: every day in the workspace with its stars, the
+//! latest answers and run times, and its test status.
+
+use crate::answers::AnswerDatabase;
+use crate::bench::Baseline;
+use crate::config::Config;
+use crate::progress::{self, Progress};
+use crate::registry::{self, Solution};
+use crate::runner::{self, PartResult};
+use crate::vault;
+use crate::workspace;
+use common::Arithmetic;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::slice;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+/// How often the dashboard checks for finished runs while waiting for keys.
+const TICK: Duration = Duration::from_millis(100);
+
+const HELP: &str = "\
+r       run both parts of the selected day
+t       run the day's tests with cargo
+v       show the day's visualization
+p       show problems with the day's input
+↑ ↓     select a day
+PgUp PgDn  scroll the panel below
+Esc     back to this help
+q       quit";
+
+/// What `cargo test` reported for a day.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Tests {
+    NotRun,
+    Running,
+    Finished {
+        passed: usize,
+        failed: usize,
+    },
+    /// Cargo could not run or build the tests.
+    Broken,
+}
+
+impl Tests {
+    fn label(&self) -> String {
+        match self {
+            Self::NotRun => String::new(),
+            Self::Running => "running".to_string(),
+            Self::Finished { passed, failed: 0 } => format!("{} passed", passed),
+            Self::Finished { passed, failed } => format!("{} failed, {} passed", failed, passed),
+            Self::Broken => "broken".to_string(),
+        }
+    }
+}
+
+/// Sums the counts from every `test result:` line cargo printed.
+fn parse_test_counts(output: &str) -> Option<(usize, usize)> {
+    let mut totals = None;
+    for line in output.lines() {
+        let Some(summary) = line.strip_prefix("test result: ") else {
+            continue;
+        };
+        let count = |label: &str| {
+            summary
+                .split(['.', ';'])
+                .find_map(|field| field.trim().strip_suffix(label)?.trim().parse().ok())
+                .unwrap_or(0)
+        };
+        let (passed, failed) = totals.unwrap_or((0, 0));
+        totals = Some((passed + count(" passed"), failed + count(" failed")));
+    }
+    totals
+}
+
+/// A finished background job.
+enum Update {
+    Solved(PartResult),
+    Tested {
+        day: u8,
+        tests: Tests,
+        output: String,
+    },
+}
+
+/// One day's row on the dashboard.
+struct Day {
+    day: u8,
+    /// `None` for a day directory that is not in the registry yet.
+    solution: Option<Solution>,
+    results: [Option<PartResult>; 2],
+    running: [bool; 2],
+    tests: Tests,
+}
+
+pub struct Dashboard {
+    root: PathBuf,
+    year: u16,
+    database: AnswerDatabase,
+    /// When each part was solved and verified, for days not run yet.
+    progress: Option<Progress>,
+    /// The benchmarked time of each part, for days not run yet.
+    baseline: Option<Baseline>,
+    config: Config,
+    /// Seconds from `--timeout`, overriding the limits in `config`.
+    timeout: Option<f64>,
+    days: Vec<Day>,
+    table: TableState,
+    title: String,
+    detail: String,
+    scroll: u16,
+    sender: Sender<Update>,
+    receiver: Receiver<Update>,
+    quit: bool,
+}
+
+impl Dashboard {
+    /// Lists every day registered for `year` along with any day directory
+    /// of that year in `root` that has not been registered yet, showing
+    /// what `progress.json` and `baseline.json` know until a day is run.
+    pub fn new(
+        root: &Path,
+        year: u16,
+        config: Config,
+        database: AnswerDatabase,
+        timeout: Option<f64>,
+    ) -> Self {
+        let solutions = registry::solutions_in(year);
+        let mut numbers: Vec<u8> = solutions.iter().map(|solution| solution.day).collect();
+        numbers.extend(vault::days(root, year).unwrap_or_default());
+        numbers.sort_unstable();
+        numbers.dedup();
+        let days = numbers
+            .into_iter()
+            .map(|day| Day {
+                day,
+                solution: solutions
+                    .iter()
+                    .find(|solution| solution.day == day)
+                    .copied(),
+                results: [None, None],
+                running: [false, false],
+                tests: Tests::NotRun,
+            })
+            .collect();
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            root: root.to_path_buf(),
+            year,
+            database,
+            progress: Progress::load(&workspace::progress_path(root, year)).ok(),
+            baseline: Baseline::load(&workspace::baseline_path(root, year)).ok(),
+            config,
+            timeout,
+            days,
+            table: TableState::default().with_selected(Some(0)),
+            title: "Keys".to_string(),
+            detail: HELP.to_string(),
+            scroll: 0,
+            sender,
+            receiver,
+            quit: false,
+        }
+    }
+
+    fn selected(&self) -> Option<&Day> {
+        self.days.get(self.table.selected()?)
+    }
+
+    fn show(&mut self, title: String, detail: String) {
+        self.title = title;
+        self.detail = detail;
+        self.scroll = 0;
+    }
+
+    /// Reads the selected day's input and checks its shape, showing why
+    /// when it cannot be used.
+    fn checked_input(&mut self) -> Option<(Solution, String)> {
+        let day = self.selected()?;
+        let number = day.day;
+        let Some(solution) = day.solution else {
+            self.show(
+                format!("Day {:02}", number),
+                format!(
+                    "This day has no #[aoc(year = {}, day = {}, part = 1)] function yet",
+                    self.year, number
+                ),
+            );
+            return None;
+        };
+        let input = match vault::read_input(
+            &self.root,
+            &self.config.inputs(&self.root),
+            self.year,
+            number,
+        ) {
+            Ok(Some(input)) => input,
+            Ok(None) => {
+                self.show(format!("Day {:02}", number), "No input.txt".to_string());
+                return None;
+            }
+            Err(error) => {
+                self.show(format!("Day {:02}", number), error.to_string());
+                return None;
+            }
+        };
+        let problems = (solution.validate)(&input);
+        if !problems.is_empty() {
+            self.show_problems(number, &problems);
+            return None;
+        }
+        Some((solution, input))
+    }
+
+    fn show_problems(&mut self, day: u8, problems: &[common::Problem]) {
+        let detail = if problems.is_empty() {
+            "The input has no problems".to_string()
+        } else {
+            problems
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        self.show(format!("Day {:02} problems", day), detail);
+    }
+
+    fn run_selected(&mut self) {
+        let Some((solution, input)) = self.checked_input() else {
+            return;
+        };
+        if let Some(index) = self.table.selected() {
+            self.days[index].running = [true, true];
+        }
+        let limit = self
+            .config
+            .time_limit(self.year, solution.day, self.timeout);
+        let (database, sender) = (self.database.clone(), self.sender.clone());
+        thread::spawn(move || {
+            for part in 1..=2 {
+                let result = runner::solve_part(
+                    &database,
+                    &solution,
+                    part,
+                    &input,
+                    Arithmetic::default(),
+                    limit,
+                );
+                if sender.send(Update::Solved(result)).is_err() {
+                    return;
+                }
+            }
+        });
+    }
+
+    fn test_selected(&mut self) {
+        let Some(index) = self.table.selected() else {
+            return;
+        };
+        let (year, day) = (self.year, self.days[index].day);
+        self.days[index].tests = Tests::Running;
+        let (root, sender) = (self.root.clone(), self.sender.clone());
+        thread::spawn(move || {
+            let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+            let output = Command::new(cargo)
+                .args(["test", "--color", "never", "--package"])
+                .arg(format!("year-{}-day-{:02}", year, day))
+                .current_dir(root)
+                .output();
+            let (tests, output) = match output {
+                Ok(output) => {
+                    let text = String::from_utf8_lossy(&output.stdout).to_string()
+                        + &String::from_utf8_lossy(&output.stderr);
+                    let tests = match parse_test_counts(&text) {
+                        Some((passed, failed)) => Tests::Finished { passed, failed },
+                        None => Tests::Broken,
+                    };
+                    (tests, text)
+                }
+                Err(error) => (Tests::Broken, error.to_string()),
+            };
+            let _ = sender.send(Update::Tested { day, tests, output });
+        });
+    }
+
+    fn visualize_selected(&mut self) {
+        let Some(day) = self.selected() else {
+            return;
+        };
+        let number = day.day;
+        let Some(visualize) = day.solution.and_then(|solution| solution.visualize) else {
+            self.show(
+                format!("Day {:02} visualization", number),
+                "This day has no visualization".to_string(),
+            );
+            return;
+        };
+        if let Some((_, input)) = self.checked_input() {
+            self.show(
+                format!("Day {:02} visualization", number),
+                visualize(&input),
+            );
+        }
+    }
+
+    fn problems_selected(&mut self) {
+        // Inputs with problems are shown by `checked_input` itself.
+        if let Some((solution, _)) = self.checked_input() {
+            self.show_problems(solution.day, &[]);
+        }
+    }
+
+    fn apply(&mut self, update: Update) {
+        match update {
+            Update::Solved(result) => {
+                let path = workspace::progress_path(&self.root, self.year);
+                if let Err(error) = progress::record_results(&path, slice::from_ref(&result)) {
+                    self.show(
+                        format!("Day {:02}", result.day),
+                        format!("Unable to save progress: {}", error),
+                    );
+                }
+                if let Some(day) = self.days.iter_mut().find(|day| day.day == result.day) {
+                    let index = usize::from(result.part - 1);
+                    day.running[index] = false;
+                    day.results[index] = Some(result);
+                }
+            }
+            Update::Tested { day, tests, output } => {
+                if let Some(row) = self.days.iter_mut().find(|row| row.day == day) {
+                    row.tests = tests;
+                }
+                self.show(format!("Day {:02} tests", day), output);
+            }
+        }
+    }
+
+    /// Applies every background result that has arrived.
+    fn receive(&mut self) {
+        while let Ok(update) = self.receiver.try_recv() {
+            self.apply(update);
+        }
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Down | KeyCode::Char('j') => {
+                let last = self.days.len().saturating_sub(1);
+                let next = self
+                    .table
+                    .selected()
+                    .map_or(0, |index| (index + 1).min(last));
+                self.table.select(Some(next));
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                let previous = self
+                    .table
+                    .selected()
+                    .map_or(0, |index| index.saturating_sub(1));
+                self.table.select(Some(previous));
+            }
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::Esc => self.show("Keys".to_string(), HELP.to_string()),
+            KeyCode::Char('r') => self.run_selected(),
+            KeyCode::Char('t') => self.test_selected(),
+            KeyCode::Char('v') => self.visualize_selected(),
+            KeyCode::Char('p') => self.problems_selected(),
+            _ => {}
+        }
+    }
+
+    /// How a part stood in `progress.json` and how long it took in
+    /// `baseline.json`, for a part not run since the dashboard opened.
+    fn saved(&self, day: u8, part: u8) -> (String, String) {
+        let state = self
+            .progress
+            .as_ref()
+            .map(|progress| progress.get(day, part))
+            .unwrap_or_default();
+        let answer = if state.verified.is_some() {
+            "verified"
+        } else if state.solved.is_some() {
+            "solved"
+        } else {
+            ""
+        };
+        let elapsed = self
+            .baseline
+            .as_ref()
+            .and_then(|baseline| baseline.get(day, part))
+            .map(|benchmark| format!("{:.2?}", benchmark.median()))
+            .unwrap_or_default();
+        (answer.to_string(), elapsed)
+    }
+
+    fn row(&self, day: &Day) -> Row<'static> {
+        let stars: String = (1..=2)
+            .map(|part| {
+                if self.database.is_solved(day.day, part) {
+                    '*'
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        let mut cells = vec![format!("{:02}", day.day), stars];
+        for ((result, running), part) in day.results.iter().zip(day.running).zip(1..=2) {
+            let (answer, elapsed) = match (result, running) {
+                (_, true) => ("running".to_string(), String::new()),
+                (Some(result), false) => (
+                    result.outcome.to_string(),
+                    format!("{:.2?}", result.elapsed),
+                ),
+                (None, false) => self.saved(day.day, part),
+            };
+            cells.extend([answer, elapsed]);
+        }
+        cells.push(match day.solution {
+            Some(_) => day.tests.label(),
+            None => "not registered".to_string(),
+        });
+        Row::new(cells)
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let [top, bottom] = Layout::vertical([
+            Constraint::Length(self.days.len() as u16 + 3),
+            Constraint::Fill(1),
+        ])
+        .areas(frame.area());
+        let rows: Vec<Row> = self.days.iter().map(|day| self.row(day)).collect();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(3),
+                Constraint::Length(5),
+                Constraint::Fill(1),
+                Constraint::Length(10),
+                Constraint::Fill(1),
+                Constraint::Length(10),
+                Constraint::Length(18),
+            ],
+        )
+        .header(
+            Row::new(["Day", "Stars", "Part 1", "Time", "Part 2", "Time", "Tests"])
+                .style(Style::new().add_modifier(Modifier::BOLD)),
+        )
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+        .block(
+            Block::bordered()
+                .title(Line::from(format!(" Advent of Code {} ", self.year)).centered()),
+        );
+        frame.render_stateful_widget(table, top, &mut self.table);
+        let detail = Paragraph::new(self.detail.as_str())
+            .scroll((self.scroll, 0))
+            .block(Block::bordered().title(format!(" {} ", self.title)));
+        frame.render_widget(detail, bottom);
+    }
+
+    /// Draws the dashboard until `q` is pressed.
+    #[cfg(not(tarpaulin_include))]
+    pub fn run(mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        while !self.quit {
+            self.receive();
+            terminal.draw(|frame| self.draw(frame))?;
+            if event::poll(TICK)? {
+                if let Event::Key(key) = event::read()? {
+                    if KeyEventKind::Press == key.kind {
+                        self.handle_key(key.code);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answers::Check;
+    use crate::bench::Benchmark;
+    use crate::progress::Event;
+    use crate::runner::Outcome;
+    use common::Answer;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+    use std::fs::{create_dir_all, remove_dir_all, write};
+
+    fn scratch(name: &str) -> PathBuf {
+        let directory = env::temp_dir().join(format!("aoc-tui-{}-{}", name, std::process::id()));
+        let _ = remove_dir_all(&directory);
+        create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    fn empty_database(root: &Path) -> AnswerDatabase {
+        AnswerDatabase::load(&workspace::answers_path(root, 2024)).unwrap()
+    }
+
+    fn screen(dashboard: &mut Dashboard) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 40)).unwrap();
+        terminal.draw(|frame| dashboard.draw(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>() + "\n")
+            .collect()
+    }
+
+    #[test]
+    fn it_sums_cargo_test_counts() {
+        let output = "running 5 tests\n\
+            test result: ok. 5 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out\n\
+            test result: FAILED. 1 passed; 2 failed; 0 ignored; 0 measured; 0 filtered out\n";
+        assert_eq!(Some((6, 2)), parse_test_counts(output));
+        assert_eq!(None, parse_test_counts("error: could not compile"));
+        assert_eq!(
+            "6 passed",
+            Tests::Finished {
+                passed: 6,
+                failed: 0
+            }
+            .label()
+        );
+        assert_eq!(
+            "2 failed, 6 passed",
+            Tests::Finished {
+                passed: 6,
+                failed: 2
+            }
+            .label()
+        );
+    }
+
+    #[test]
+    fn it_lists_registered_and_unregistered_days() {
+        let root = scratch("days");
+        create_dir_all(workspace::day_directory(&root, 2024, 25)).unwrap();
+        let mut dashboard =
+            Dashboard::new(&root, 2024, Config::default(), empty_database(&root), None);
+        let days: Vec<u8> = dashboard.days.iter().map(|day| day.day).collect();
+        assert_eq!(registry::latest(2024).unwrap().day as usize + 1, days.len());
+        assert_eq!(Some(&25), days.last());
+        let screen = screen(&mut dashboard);
+        assert!(screen.contains("not registered"));
+        assert!(screen.contains("Advent of Code 2024"));
+        remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn it_moves_the_selection_and_quits() {
+        let root = scratch("keys");
+        let mut dashboard =
+            Dashboard::new(&root, 2024, Config::default(), empty_database(&root), None);
+        dashboard.handle_key(KeyCode::Up);
+        assert_eq!(Some(0), dashboard.table.selected());
+        dashboard.handle_key(KeyCode::Down);
+        dashboard.handle_key(KeyCode::Char('j'));
+        assert_eq!(3, dashboard.selected().unwrap().day);
+        dashboard.handle_key(KeyCode::Char('q'));
+        assert!(dashboard.quit);
+        remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn it_shows_finished_runs() {
+        let root = scratch("runs");
+        create_dir_all(workspace::day_directory(&root, 2024, 1)).unwrap();
+        let mut database = empty_database(&root);
+        database.insert(1, 1, &Answer::from(11));
+        let mut dashboard = Dashboard::new(&root, 2024, Config::default(), database, None);
+        dashboard.apply(Update::Solved(PartResult {
+            day: 1,
+            part: 2,
+            outcome: Outcome::Solved(Answer::from(31)),
+            elapsed: Duration::from_millis(3),
+            check: Check::Unknown,
+        }));
+        let screen = screen(&mut dashboard);
+        assert!(screen.contains("*."));
+        assert!(screen.contains("31"));
+        assert!(screen.contains("3.00ms"));
+        let progress = Progress::load(&workspace::progress_path(&root, 2024)).unwrap();
+        assert!(progress.get(1, 2).solved.is_some());
+        remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn it_shows_saved_progress_and_timings() {
+        let root = scratch("saved");
+        create_dir_all(workspace::day_directory(&root, 2024, 1)).unwrap();
+        let mut progress = Progress::load(&workspace::progress_path(&root, 2024)).unwrap();
+        progress.record(1, 1, Event::Solved, 10);
+        progress.record(1, 1, Event::Verified, 10);
+        progress.record(1, 2, Event::Solved, 20);
+        progress.save().unwrap();
+        let mut baseline = Baseline::load(&workspace::baseline_path(&root, 2024)).unwrap();
+        baseline.insert(Benchmark {
+            day: 1,
+            part: 2,
+            samples: vec![Duration::from_millis(4), Duration::from_millis(6)],
+        });
+        baseline.save().unwrap();
+        let mut dashboard =
+            Dashboard::new(&root, 2024, Config::default(), empty_database(&root), None);
+        let screen = screen(&mut dashboard);
+        assert!(screen.contains("verified"));
+        assert!(screen.contains("solved"));
+        assert!(screen.contains("5.00ms"));
+        remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn it_shows_visualizations_and_problems() {
+        let root = scratch("visualize");
+        create_dir_all(workspace::day_directory(&root, 2024, 10)).unwrap();
+        let mut dashboard =
+            Dashboard::new(&root, 2024, Config::default(), empty_database(&root), None);
+        dashboard.table.select(Some(9));
+        dashboard.handle_key(KeyCode::Char('v'));
+        assert_eq!("No input.txt", dashboard.detail);
+        write(workspace::input_path(&root, 2024, 10), "0123456789\n").unwrap();
+        dashboard.handle_key(KeyCode::Char('v'));
+        assert_eq!("Day 10 visualization", dashboard.title);
+        assert_eq!("0123456789\n", dashboard.detail);
+        dashboard.handle_key(KeyCode::Char('p'));
+        assert_eq!("The input has no problems", dashboard.detail);
+        write(workspace::input_path(&root, 2024, 10), "01x\n").unwrap();
+        dashboard.handle_key(KeyCode::Char('r'));
+        assert_eq!("Day 10 problems", dashboard.title);
+        assert_eq!("line 1: unexpected 'x' in column 3", dashboard.detail);
+        dashboard.handle_key(KeyCode::Esc);
+        assert_eq!(HELP, dashboard.detail);
+        dashboard.table.select(Some(0));
+        dashboard.handle_key(KeyCode::Char('v'));
+        assert_eq!("This day has no visualization", dashboard.detail);
+        remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn it_runs_days_in_the_background() {
+        let root = scratch("background");
+        create_dir_all(workspace::day_directory(&root, 2024, 1)).unwrap();
+        write(workspace::input_path(&root, 2024, 1), "3   4\n4   3\n").unwrap();
+        let mut dashboard =
+            Dashboard::new(&root, 2024, Config::default(), empty_database(&root), None);
+        dashboard.handle_key(KeyCode::Char('r'));
+        assert_eq!([true, true], dashboard.days[0].running);
+        for _ in 0..2 {
+            let update = dashboard
+                .receiver
+                .recv_timeout(Duration::from_secs(10))
+                .unwrap();
+            dashboard.apply(update);
+        }
+        assert_eq!([false, false], dashboard.days[0].running);
+        let part2 = dashboard.days[0].results[1].as_ref().unwrap();
+        assert_eq!(Outcome::Solved(Answer::from(7)), part2.outcome);
+        remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,258 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::workspace;
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use std::env;
+use std::fs::{read, read_dir, read_to_string, write};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Identifies an encrypted input and the version of its layout.
+const MAGIC: &[u8; 4] = b"AOC1";
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const HEADER_LENGTH: usize = MAGIC.len() + SALT_LENGTH + NONCE_LENGTH;
+
+/// Passphrase used when set; takes precedence over any key file.
+pub const PASSPHRASE_VARIABLE: &str = "AOC_PASSPHRASE";
+/// Path of a key file to use instead of `.aoc-key` in the workspace root.
+pub const KEY_FILE_VARIABLE: &str = "AOC_KEY_FILE";
+
+pub fn key_path(root: &Path) -> PathBuf {
+    root.join(".aoc-key")
+}
+
+pub fn encrypted_path(input: &Path) -> PathBuf {
+    let mut name = input.as_os_str().to_owned();
+    name.push(".enc");
+    PathBuf::from(name)
+}
+
+/// Finds the secret to encrypt inputs with: `AOC_PASSPHRASE`, then the file
+/// named by `AOC_KEY_FILE`, then `.aoc-key` in the workspace root.
+pub fn load_secret(root: &Path) -> io::Result<Option<Vec<u8>>> {
+    if let Ok(passphrase) = env::var(PASSPHRASE_VARIABLE) {
+        return Ok(Some(passphrase.into_bytes()));
+    }
+    let path = env::var_os(KEY_FILE_VARIABLE).map_or_else(|| key_path(root), PathBuf::from);
+    match read(&path) {
+        Ok(mut contents) => {
+            // Editors like to end key files with a newline nobody meant as key material.
+            while contents.last().is_some_and(u8::is_ascii_whitespace) {
+                contents.pop();
+            }
+            Ok(Some(contents))
+        }
+        Err(error) if io::ErrorKind::NotFound == error.kind() => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+/// Like `load_secret`, but explains where a missing key should go.
+pub fn require_secret(root: &Path) -> io::Result<Vec<u8>> {
+    load_secret(root)?.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "Set {} or {}, or write a key to {}",
+                PASSPHRASE_VARIABLE,
+                KEY_FILE_VARIABLE,
+                key_path(root).display()
+            ),
+        )
+    })
+}
+
+fn derive_key(secret: &[u8], salt: &[u8]) -> Key {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(secret, salt, &mut key)
+        .expect("Salt and key lengths are fixed");
+    key
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0; N];
+    getrandom::getrandom(&mut bytes).expect("Unable to read random bytes");
+    bytes
+}
+
+/// Encrypts `plaintext` with a key derived from `secret` and a fresh salt,
+/// producing `AOC1 | salt | nonce | ciphertext`.
+pub fn encrypt(secret: &[u8], plaintext: &[u8]) -> Vec<u8> {
+    let salt = random_bytes::<SALT_LENGTH>();
+    let nonce = random_bytes::<NONCE_LENGTH>();
+    let cipher = ChaCha20Poly1305::new(&derive_key(secret, &salt));
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .expect("Inputs are far below the cipher's length limit");
+    [MAGIC.as_slice(), &salt, &nonce, &ciphertext].concat()
+}
+
+pub fn decrypt(secret: &[u8], data: &[u8]) -> io::Result<Vec<u8>> {
+    if data.len() < HEADER_LENGTH || !data.starts_with(MAGIC) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "not an encrypted input",
+        ));
+    }
+    let (salt, rest) = data[MAGIC.len()..].split_at(SALT_LENGTH);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LENGTH);
+    ChaCha20Poly1305::new(&derive_key(secret, salt))
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "wrong key or corrupted encrypted input",
+            )
+        })
+}
+
+/// Writes `input.txt.enc` next to `input`.
+pub fn encrypt_file(secret: &[u8], input: &Path) -> io::Result<PathBuf> {
+    let output = encrypted_path(input);
+    write(&output, encrypt(secret, &read(input)?))?;
+    Ok(output)
+}
+
+/// Restores `input` from the `input.txt.enc` next to it.
+pub fn decrypt_file(secret: &[u8], input: &Path) -> io::Result<String> {
+    let plaintext = decrypt(secret, &read(encrypted_path(input))?)?;
+    let plaintext = String::from_utf8(plaintext)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    write(input, &plaintext)?;
+    Ok(plaintext)
+}
+
+/// Reads a day's input from `inputs`, decrypting its `input.txt.enc` into
+/// `input.txt` first when only the encrypted copy exists. Days with neither
+/// have no input. The key is looked up in `root`.
+pub fn read_input(root: &Path, inputs: &Path, year: u16, day: u8) -> io::Result<Option<String>> {
+    let path = workspace::input_path(inputs, year, day);
+    match read_to_string(&path) {
+        Ok(input) => return Ok(Some(input)),
+        Err(error) if io::ErrorKind::NotFound != error.kind() => return Err(error),
+        Err(_) => {}
+    }
+    if !encrypted_path(&path).is_file() {
+        return Ok(None);
+    }
+    decrypt_file(&require_secret(root)?, &path).map(Some)
+}
+
+/// Lists the days of `year` with a directory, whether or not they are
+/// registered with the runner yet.
+pub fn days(root: &Path, year: u16) -> io::Result<Vec<u8>> {
+    let mut days = Vec::new();
+    for entry in read_dir(workspace::year_directory(root, year))? {
+        let name = entry?.file_name();
+        let day = name
+            .to_str()
+            .and_then(|name| name.strip_prefix("day-"))
+            .and_then(|day| day.parse::<u8>().ok());
+        days.extend(day);
+    }
+    days.sort_unstable();
+    Ok(days)
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, remove_dir_all};
+
+    fn scratch(name: &str) -> PathBuf {
+        let directory = env::temp_dir().join(format!("aoc-vault-{}-{}", name, std::process::id()));
+        let _ = remove_dir_all(&directory);
+        create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    #[test]
+    fn it_round_trips() {
+        let data = encrypt(b"hunter2", b"3   4\n4   3\n");
+        assert!(data.starts_with(MAGIC));
+        assert_eq!(
+            b"3   4\n4   3\n".to_vec(),
+            decrypt(b"hunter2", &data).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_salts_every_encryption() {
+        assert_ne!(encrypt(b"hunter2", b"input"), encrypt(b"hunter2", b"input"));
+    }
+
+    #[test]
+    fn it_rejects_wrong_keys_and_tampering() {
+        let mut data = encrypt(b"hunter2", b"input");
+        assert!(decrypt(b"hunter3", &data).is_err());
+        *data.last_mut().unwrap() ^= 1;
+        assert!(decrypt(b"hunter2", &data).is_err());
+        assert!(decrypt(b"hunter2", b"input").is_err());
+        assert!(decrypt(b"hunter2", b"AOC1").is_err());
+    }
+
+    #[test]
+    fn it_encrypts_and_restores_files() {
+        let directory = scratch("files");
+        let input = directory.join("input.txt");
+        write(&input, "190: 10 19\n").unwrap();
+        assert_eq!(
+            directory.join("input.txt.enc"),
+            encrypt_file(b"key", &input).unwrap()
+        );
+        write(&input, "stale").unwrap();
+        assert_eq!("190: 10 19\n", decrypt_file(b"key", &input).unwrap());
+        assert_eq!("190: 10 19\n", read_to_string(&input).unwrap());
+        remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn it_reads_plain_inputs_first() {
+        let directory = scratch("inputs");
+        create_dir_all(workspace::day_directory(&directory, 2024, 3)).unwrap();
+        assert!(read_input(&directory, &directory, 2024, 3)
+            .unwrap()
+            .is_none());
+        write(workspace::input_path(&directory, 2024, 3), "mul(2,4)").unwrap();
+        assert_eq!(
+            "mul(2,4)",
+            read_input(&directory, &directory, 2024, 3)
+                .unwrap()
+                .unwrap()
+        );
+        assert!(read_input(&directory, &directory, 2015, 3)
+            .unwrap()
+            .is_none());
+        remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn it_lists_day_directories() {
+        let directory = scratch("days");
+        for name in ["day-11", "day-02", "day-xx", "common"] {
+            create_dir_all(workspace::year_directory(&directory, 2024).join(name)).unwrap();
+        }
+        create_dir_all(workspace::day_directory(&directory, 2015, 5)).unwrap();
+        assert_eq!(vec![2, 11], days(&directory, 2024).unwrap());
+        assert_eq!(vec![5], days(&directory, 2015).unwrap());
+        assert!(days(&directory, 2016).is_err());
+        remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,193 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::registry::Solution;
+use crate::runner::{run_part, Format, Outcome};
+use common::Arithmetic;
+use serde_json::json;
+use std::time::Duration;
+
+/// What every variant of one part produced.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Verification {
+    pub day: u8,
+    pub part: u8,
+    pub outcomes: Vec<(&'static str, Outcome, Duration)>,
+}
+
+impl Verification {
+    /// Whether every variant solved the part with the same answer.
+    pub fn agrees(&self) -> bool {
+        let mut outcomes = self.outcomes.iter().map(|(_, outcome, _)| outcome);
+        match outcomes.next() {
+            Some(first @ Outcome::Solved(_)) => outcomes.all(|outcome| outcome == first),
+            _ => false,
+        }
+    }
+}
+
+/// Runs every variant of `part` against `input` on the usual workers.
+pub fn verify_part(
+    solution: &Solution,
+    part: u8,
+    input: &str,
+    arithmetic: Arithmetic,
+    limit: Option<Duration>,
+) -> Verification {
+    let outcomes = solution
+        .variant_names(part)
+        .into_iter()
+        .map(|name| {
+            let variant = solution
+                .with_variant(part, name)
+                .expect("Registered variant is missing");
+            let (outcome, elapsed) = run_part(&variant, part, input, arithmetic, limit);
+            (name, outcome, elapsed)
+        })
+        .collect();
+    Verification {
+        day: solution.day,
+        part,
+        outcomes,
+    }
+}
+
+pub fn render(verifications: &[Verification], format: Format) -> String {
+    match format {
+        Format::Text => verifications
+            .iter()
+            .map(|verification| {
+                let verdict = if verification.agrees() {
+                    "agree"
+                } else {
+                    "DISAGREE"
+                };
+                let outcomes: String = verification
+                    .outcomes
+                    .iter()
+                    .map(|(name, outcome, elapsed)| {
+                        format!("  {}: {} [{:.3?}]\n", name, outcome, elapsed)
+                    })
+                    .collect();
+                format!(
+                    "Day {:02} Part {}: {}\n{}",
+                    verification.day, verification.part, verdict, outcomes
+                )
+            })
+            .collect(),
+        Format::Json => {
+            let verifications: Vec<_> = verifications
+                .iter()
+                .map(|verification| {
+                    let variants: Vec<_> = verification
+                        .outcomes
+                        .iter()
+                        .map(|(name, outcome, elapsed)| {
+                            json!({
+                                "variant": name,
+                                "answer": outcome.answer().map(|answer| answer.to_string()),
+                                "elapsed_ms": elapsed.as_secs_f64() * 1000.0,
+                                "error": outcome.error(),
+                            })
+                        })
+                        .collect();
+                    json!({
+                        "day": verification.day,
+                        "part": verification.part,
+                        "agrees": verification.agrees(),
+                        "variants": variants,
+                    })
+                })
+                .collect();
+            format!("{}\n", serde_json::Value::Array(verifications))
+        }
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::registry::{Shape, Variant};
+    use common::Answer;
+
+    fn fake_solution() -> Solution {
+        Solution {
+            year: 2024,
+            day: 1,
+            part1: |input| Answer::from(input.len()),
+            part2: |input| Answer::from(input.len()),
+            checked: None,
+            parse: None,
+            variants: &[
+                Variant {
+                    part: 1,
+                    name: "chars",
+                    function: |input| Answer::from(input.chars().count()),
+                },
+                Variant {
+                    part: 2,
+                    name: "off-by-one",
+                    function: |input| Answer::from(input.len() + 1),
+                },
+            ],
+            validate: |_| Vec::new(),
+            shape: Shape::Lines,
+            visualize: None,
+            explain: None,
+        }
+    }
+
+    #[test]
+    fn it_runs_every_variant() {
+        let verification = verify_part(&fake_solution(), 1, "abc", Arithmetic::U64, None);
+        assert_eq!(
+            vec!["default", "chars"],
+            verification
+                .outcomes
+                .iter()
+                .map(|(name, _, _)| *name)
+                .collect::<Vec<_>>()
+        );
+        assert!(verification.agrees());
+    }
+
+    #[test]
+    fn it_reports_disagreements() {
+        let verification = verify_part(&fake_solution(), 2, "abc", Arithmetic::U64, None);
+        assert!(!verification.agrees());
+        let verifications = vec![verification];
+        let text = render(&verifications, Format::Text);
+        assert!(text.starts_with("Day 01 Part 2: DISAGREE\n  default: 3 ["));
+        assert!(text.contains("  off-by-one: 4 ["));
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&verifications, Format::Json)).unwrap();
+        assert_eq!(false, json[0]["agrees"]);
+        assert_eq!("4", json[0]["variants"][1]["answer"]);
+    }
+
+    #[test]
+    fn it_does_not_agree_on_failures() {
+        let verification = Verification {
+            day: 1,
+            part: 1,
+            outcomes: vec![
+                ("default", Outcome::Timeout, Duration::ZERO),
+                ("fast", Outcome::Timeout, Duration::ZERO),
+            ],
+        };
+        assert!(!verification.agrees());
+    }
+}
@@ -0,0 +1,106 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::read_to_string;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Walks up from `start` to the directory holding the workspace `Cargo.toml`.
+pub fn find_root(start: &Path) -> io::Result<PathBuf> {
+    for directory in start.ancestors() {
+        let manifest = directory.join("Cargo.toml");
+        if let Ok(contents) = read_to_string(&manifest) {
+            if contents.lines().any(|line| line.trim() == "[workspace]") {
+                return Ok(directory.to_path_buf());
+            }
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("No workspace found above {}", start.display()),
+    ))
+}
+
+/// Holds a year's day crates along with its answers and benchmarks.
+pub fn year_directory(root: &Path, year: u16) -> PathBuf {
+    root.join(format!("year-{}", year))
+}
+
+pub fn day_directory(root: &Path, year: u16, day: u8) -> PathBuf {
+    year_directory(root, year).join(format!("day-{:02}", day))
+}
+
+pub fn input_path(root: &Path, year: u16, day: u8) -> PathBuf {
+    day_directory(root, year, day).join("input.txt")
+}
+
+/// The day's puzzle page as last downloaded, kept beside its input.
+pub fn puzzle_path(root: &Path, year: u16, day: u8) -> PathBuf {
+    day_directory(root, year, day).join("puzzle.html")
+}
+
+pub fn answers_path(root: &Path, year: u16) -> PathBuf {
+    year_directory(root, year).join("answers.txt")
+}
+
+pub fn baseline_path(root: &Path, year: u16) -> PathBuf {
+    year_directory(root, year).join("baseline.json")
+}
+
+pub fn progress_path(root: &Path, year: u16) -> PathBuf {
+    year_directory(root, year).join("progress.json")
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_the_workspace_root() {
+        let root = find_root(Path::new(env!("CARGO_MANIFEST_DIR"))).unwrap();
+        assert!(root.join("boilerplate").is_dir());
+        assert!(find_root(Path::new("/")).is_err());
+    }
+
+    #[test]
+    fn it_builds_day_paths() {
+        let root = Path::new("/repo");
+        assert_eq!(PathBuf::from("/repo/year-2015"), year_directory(root, 2015));
+        assert_eq!(
+            PathBuf::from("/repo/year-2024/day-06"),
+            day_directory(root, 2024, 6)
+        );
+        assert_eq!(
+            PathBuf::from("/repo/year-2024/day-11/input.txt"),
+            input_path(root, 2024, 11)
+        );
+        assert_eq!(
+            PathBuf::from("/repo/year-2024/day-11/puzzle.html"),
+            puzzle_path(root, 2024, 11)
+        );
+        assert_eq!(
+            PathBuf::from("/repo/year-2024/answers.txt"),
+            answers_path(root, 2024)
+        );
+        assert_eq!(
+            PathBuf::from("/repo/year-2024/baseline.json"),
+            baseline_path(root, 2024)
+        );
+        assert_eq!(
+            PathBuf::from("/repo/year-2024/progress.json"),
+            progress_path(root, 2024)
+        );
+    }
+}
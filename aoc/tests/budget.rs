@@ -0,0 +1,72 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fails when a part takes longer than its `budget` from `aoc.toml` on the
+//! day's real input. Timings only mean something in a release build, so the
+//! test is ignored by default; `make budget` runs it.
+
+use aoc::config::Config;
+use aoc::registry;
+use aoc::runner::{self, Outcome};
+use aoc::vault;
+use aoc::workspace;
+use common::Arithmetic;
+use std::path::Path;
+
+/// How many budgets a part may run before it is cut off, so a part that is
+/// far over budget fails quickly instead of running to the day's time limit.
+const CUTOFF: u32 = 3;
+
+#[test]
+#[ignore = "needs real inputs and a release build; run `make budget`"]
+fn every_part_runs_within_its_budget() {
+    if cfg!(debug_assertions) {
+        panic!("Budgets only hold in release builds; run the test with --release");
+    }
+    let root = workspace::find_root(Path::new(env!("CARGO_MANIFEST_DIR"))).unwrap();
+    let config = Config::load(&root).unwrap();
+    let inputs = config.inputs(&root);
+    let mut failures = Vec::new();
+    for solution in registry::solutions() {
+        let (year, day) = (solution.year, solution.day);
+        let Some(budget) = config.budget(year, day) else {
+            continue;
+        };
+        let Some(input) = vault::read_input(&root, &inputs, year, day).unwrap() else {
+            eprintln!("Skipping {} day {:02}: no input.txt", year, day);
+            continue;
+        };
+        for part in 1..=2 {
+            let limit = Some(budget * CUTOFF);
+            let (outcome, elapsed) =
+                runner::run_part(&solution, part, &input, Arithmetic::default(), limit);
+            match outcome {
+                Outcome::Solved(_) if elapsed <= budget => {}
+                Outcome::Solved(_) => failures.push(format!(
+                    "{} day {:02} part {} took {:.3?}, over its {:?} budget",
+                    year, day, part, elapsed, budget
+                )),
+                Outcome::Timeout => failures.push(format!(
+                    "{} day {:02} part {} ran past {} times its {:?} budget",
+                    year, day, part, CUTOFF, budget
+                )),
+                failed => failures.push(format!(
+                    "{} day {:02} part {} failed: {}",
+                    year, day, part, failed
+                )),
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
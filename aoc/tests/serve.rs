@@ -0,0 +1,94 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use aoc::serve::Server;
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+fn start() -> SocketAddr {
+    let server = Server::bind("127.0.0.1:0", Some(Duration::from_secs(5))).unwrap();
+    let address = server.address();
+    thread::spawn(move || server.run());
+    address
+}
+
+fn request(address: SocketAddr, method: &str, path: &str, body: &str) -> (u16, Value) {
+    let mut stream = TcpStream::connect(address).unwrap();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        method,
+        path,
+        body.len(),
+        body
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+    (status, serde_json::from_str(body).unwrap())
+}
+
+#[test]
+fn it_serves_health_checks() {
+    let address = start();
+    let (status, body) = request(address, "GET", "/health", "");
+    assert_eq!(200, status);
+    assert_eq!("ok", body["status"]);
+}
+
+#[test]
+fn it_lists_days() {
+    let address = start();
+    let (status, body) = request(address, "GET", "/days", "");
+    assert_eq!(200, status);
+    let days: Vec<u64> = body
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|day| day["day"].as_u64().unwrap())
+        .collect();
+    assert_eq!((1..=11).collect::<Vec<u64>>(), days);
+}
+
+#[test]
+fn it_solves_posted_input() {
+    let address = start();
+    let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+    let (status, body) = request(address, "POST", "/days/1/parts/1", input);
+    assert_eq!(200, status);
+    assert_eq!("11", body["answer"]);
+    assert!(body["elapsed_ms"].as_f64().unwrap() >= 0.0);
+    let (status, body) = request(address, "POST", "/days/1/parts/2?variant=counts", input);
+    assert_eq!(200, status);
+    assert_eq!("31", body["answer"]);
+}
+
+#[test]
+fn it_reports_failures_as_errors() {
+    let address = start();
+    let (status, body) = request(address, "POST", "/days/26/parts/1", "");
+    assert_eq!(404, status);
+    assert!(body["error"].is_string());
+    let (status, body) = request(address, "POST", "/days/1/parts/1", "not numbers");
+    assert_eq!(400, status);
+    assert_eq!(2, body["problems"].as_array().unwrap().len());
+    let (status, body) = request(address, "POST", "/days/11/parts/1", "99999999999999999999");
+    assert_eq!(422, status);
+    assert_eq!("arithmetic overflow", body["error"]);
+}
@@ -0,0 +1,41 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{aoc, check_text, Answer, Problem};
+
+#[aoc(year = yyyy, day = xx, validate)]
+pub fn validate(input: &str) -> Vec<Problem> {
+    check_text(input)
+}
+
+#[aoc(year = yyyy, day = xx, part = 1)]
+pub fn part1(input: String) -> Answer {
+    todo!()
+}
+
+#[aoc(year = yyyy, day = xx, part = 2)]
+pub fn part2(input: String) -> Answer {
+    todo!()
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::aoc_examples;
+
+    aoc_examples! {
+        // part1: "example.txt" => 0,
+    }
+}
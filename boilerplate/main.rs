@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::fs::read_to_string;
+use year_yyyy_day_xx::{part1, part2};
 
 #[cfg(not(tarpaulin_include))]
 fn main() {
@@ -20,17 +21,3 @@ fn main() {
     println!("Part 1: {}", part1(input.clone()));
     println!("Part 2: {}", part2(input));
 }
-
-fn part1(input: String) -> usize {
-    todo!()
-}
-
-fn part2(input: String) -> usize {
-    todo!()
-}
-
-#[cfg(not(tarpaulin_include))]
-#[cfg(test)]
-mod tests {
-    use super::*;
-}
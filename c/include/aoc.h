@@ -0,0 +1,92 @@
+/*
+ * Copyright 2024 CJ Harries
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#ifndef AOC_H
+#define AOC_H
+
+/* Generated by cbindgen from c/src/lib.rs; run `make header` instead of editing. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * The answer is in `out_buf`.
+ */
+#define AOC_OK 0
+
+/**
+ * No days are registered, the latest year has no such day, or the day has
+ * no such part.
+ */
+#define AOC_UNKNOWN_DAY 1
+
+/**
+ * The input isn't UTF-8 or fails the day's validation.
+ */
+#define AOC_INVALID_INPUT 2
+
+/**
+ * The answer doesn't fit the day's number type.
+ */
+#define AOC_OVERFLOW 3
+
+/**
+ * The solution, or anything else while solving, panicked.
+ */
+#define AOC_PANICKED 4
+
+/**
+ * `out_buf` can't hold the answer and its terminating NUL.
+ */
+#define AOC_BUFFER_TOO_SMALL 5
+
+/**
+ * `out_buf` or `out_len` is null, or `input_ptr` is null with a nonzero
+ * `len`.
+ */
+#define AOC_NULL_POINTER 6
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Solves `part` of `day` of the latest year on the `len` bytes of UTF-8 at
+ * `input_ptr`.
+ *
+ * `*out_len` is the size of `out_buf` on the way in. On the way out it is the
+ * length of the answer, without its NUL, and `out_buf` holds as much of the
+ * answer as fits. On `AOC_UNKNOWN_DAY`, `AOC_INVALID_INPUT`, `AOC_OVERFLOW`
+ * and `AOC_PANICKED`, they hold the error message instead. A panic never crosses into the
+ * caller; it is reported as `AOC_PANICKED`.
+ *
+ * # Safety
+ *
+ * `input_ptr` must point to `len` readable bytes, `out_len` to a `size_t`,
+ * and `out_buf` to `*out_len` writable bytes.
+ */
+int32_t aoc_solve(uint8_t day,
+                  uint8_t part,
+                  const uint8_t *input_ptr,
+                  size_t len,
+                  char *out_buf,
+                  size_t *out_len);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* AOC_H */
@@ -0,0 +1,144 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A C API for the solutions, built as the `aoc_c` shared library.
+//!
+//! `include/aoc.h` declares it for C and C++. It is generated with cbindgen,
+//! so run `make header` after changing anything here.
+
+use aoc::registry;
+use aoc::runner::{self, Outcome};
+use common::Arithmetic;
+use std::ffi::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+/// The answer is in `out_buf`.
+pub const AOC_OK: i32 = 0;
+/// No days are registered, the latest year has no such day, or the day has
+/// no such part.
+pub const AOC_UNKNOWN_DAY: i32 = 1;
+/// The input isn't UTF-8 or fails the day's validation.
+pub const AOC_INVALID_INPUT: i32 = 2;
+/// The answer doesn't fit the day's number type.
+pub const AOC_OVERFLOW: i32 = 3;
+/// The solution, or anything else while solving, panicked.
+pub const AOC_PANICKED: i32 = 4;
+/// `out_buf` can't hold the answer and its terminating NUL.
+pub const AOC_BUFFER_TOO_SMALL: i32 = 5;
+/// `out_buf` or `out_len` is null, or `input_ptr` is null with a nonzero
+/// `len`.
+pub const AOC_NULL_POINTER: i32 = 6;
+
+/// Copies as much of `text` as fits in `capacity` bytes to `out_buf`,
+/// followed by a NUL, and returns whether all of it fit.
+unsafe fn write_text(text: &str, out_buf: *mut c_char, capacity: usize) -> bool {
+    let Some(room) = capacity.checked_sub(1) else {
+        return false;
+    };
+    let length = text.len().min(room);
+    ptr::copy_nonoverlapping(text.as_ptr(), out_buf.cast::<u8>(), length);
+    *out_buf.add(length) = 0;
+    length == text.len()
+}
+
+/// Solves `part` of `day` of the latest year on the `len` bytes of UTF-8 at
+/// `input_ptr`.
+///
+/// `*out_len` is the size of `out_buf` on the way in. On the way out it is the
+/// length of the answer, without its NUL, and `out_buf` holds as much of the
+/// answer as fits. On `AOC_UNKNOWN_DAY`, `AOC_INVALID_INPUT`, `AOC_OVERFLOW`
+/// and `AOC_PANICKED`, they hold the error message instead. A panic never crosses into the
+/// caller; it is reported as `AOC_PANICKED`.
+///
+/// # Safety
+///
+/// `input_ptr` must point to `len` readable bytes, `out_len` to a `size_t`,
+/// and `out_buf` to `*out_len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(
+    day: u8,
+    part: u8,
+    input_ptr: *const u8,
+    len: usize,
+    out_buf: *mut c_char,
+    out_len: *mut usize,
+) -> i32 {
+    if out_buf.is_null() || out_len.is_null() || (input_ptr.is_null() && 0 < len) {
+        return AOC_NULL_POINTER;
+    }
+    let input = match len {
+        0 => &[],
+        _ => slice::from_raw_parts(input_ptr, len),
+    };
+    let capacity = *out_len;
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        solve(day, part, input, out_buf, out_len, capacity)
+    }))
+    .unwrap_or_else(|payload| {
+        let message = runner::panic_message(payload);
+        *out_len = message.len();
+        write_text(&message, out_buf, capacity);
+        AOC_PANICKED
+    })
+}
+
+/// The body of `aoc_solve`, once its pointers are checked.
+unsafe fn solve(
+    day: u8,
+    part: u8,
+    input: &[u8],
+    out_buf: *mut c_char,
+    out_len: *mut usize,
+    capacity: usize,
+) -> i32 {
+    let fail = |code: i32, message: &str| {
+        *out_len = message.len();
+        write_text(message, out_buf, capacity);
+        code
+    };
+    let Some(year) = registry::years().last().copied() else {
+        return fail(AOC_UNKNOWN_DAY, "no days are registered");
+    };
+    let Some(solution) = registry::find(year, day) else {
+        return fail(AOC_UNKNOWN_DAY, &format!("{} has no day {}", year, day));
+    };
+    if solution.part(part).is_none() {
+        return fail(
+            AOC_UNKNOWN_DAY,
+            &format!("day {} has no part {}", day, part),
+        );
+    }
+    let Ok(input) = std::str::from_utf8(input) else {
+        return fail(AOC_INVALID_INPUT, "input is not UTF-8");
+    };
+    if let Some(problem) = (solution.validate)(input).first() {
+        return fail(AOC_INVALID_INPUT, &problem.to_string());
+    }
+    match runner::run_part(&solution, part, input, Arithmetic::default(), None).0 {
+        Outcome::Solved(answer) => {
+            let answer = answer.to_string();
+            *out_len = answer.len();
+            if write_text(&answer, out_buf, capacity) {
+                AOC_OK
+            } else {
+                AOC_BUFFER_TOO_SMALL
+            }
+        }
+        Outcome::Overflow => fail(AOC_OVERFLOW, "arithmetic overflow"),
+        // Parts run without a time limit, so anything else is a panic.
+        failure => fail(AOC_PANICKED, &failure.error().unwrap_or_default()),
+    }
+}
@@ -0,0 +1,46 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn it_passes_the_c_tests() {
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    assert!(
+        Command::new(&compiler).arg("--version").output().is_ok(),
+        "The C tests need a C compiler, but {} is not installed; install one or set CC",
+        compiler
+    );
+    // The shared library is built next to the directory holding this test.
+    let deps = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    let library = deps.parent().unwrap();
+    let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let program = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("solve");
+    let status = Command::new(&compiler)
+        .args(["-std=c99", "-Wall", "-Wextra", "-Werror", "-I"])
+        .arg(manifest.join("include"))
+        .arg(manifest.join("tests").join("solve.c"))
+        .arg("-L")
+        .arg(library)
+        .arg(format!("-Wl,-rpath,{}", library.display()))
+        .args(["-laoc_c", "-o"])
+        .arg(&program)
+        .status()
+        .unwrap();
+    assert!(status.success(), "Unable to compile tests/solve.c");
+    let status = Command::new(&program).status().unwrap();
+    assert!(status.success(), "C tests failed");
+}
@@ -0,0 +1,92 @@
+/*
+ * Copyright 2024 CJ Harries
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+/* Calls aoc_solve the way an embedding program would, and exits with 1 after
+ * reporting every check that fails. */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "aoc.h"
+
+static int failures = 0;
+
+#define CHECK(condition)                                                    \
+    do {                                                                    \
+        if (!(condition)) {                                                 \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, \
+                    #condition);                                            \
+            failures++;                                                     \
+        }                                                                   \
+    } while (0)
+
+static int32_t solve(uint8_t day, uint8_t part, const char *input, char *out_buf,
+                     size_t *out_len) {
+    return aoc_solve(day, part, (const uint8_t *)input, strlen(input), out_buf,
+                     out_len);
+}
+
+int main(void) {
+    const char *lists = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+    char answer[64];
+    size_t length;
+
+    length = sizeof answer;
+    CHECK(AOC_OK == solve(1, 1, lists, answer, &length));
+    CHECK(0 == strcmp("11", answer));
+    CHECK(2 == length);
+
+    length = sizeof answer;
+    CHECK(AOC_OK == solve(1, 2, lists, answer, &length));
+    CHECK(0 == strcmp("31", answer));
+
+    length = sizeof answer;
+    CHECK(AOC_UNKNOWN_DAY == solve(26, 1, lists, answer, &length));
+    CHECK(0 == strcmp("2024 has no day 26", answer));
+    CHECK(strlen(answer) == length);
+    length = sizeof answer;
+    CHECK(AOC_UNKNOWN_DAY == solve(1, 3, lists, answer, &length));
+    CHECK(0 == strcmp("day 1 has no part 3", answer));
+
+    length = sizeof answer;
+    CHECK(AOC_INVALID_INPUT == solve(1, 1, "3 x\n", answer, &length));
+    CHECK(0 == strcmp("line 1: \"x\" is not a number", answer));
+    CHECK(strlen(answer) == length);
+
+    length = sizeof answer;
+    const uint8_t invalid[] = {0xff, '\n'};
+    CHECK(AOC_INVALID_INPUT == aoc_solve(1, 1, invalid, sizeof invalid, answer, &length));
+    CHECK(0 == strcmp("input is not UTF-8", answer));
+
+    length = sizeof answer;
+    CHECK(AOC_OVERFLOW == solve(7, 1, "18446744073709551615: 18446744073709551615 1\n"
+                                      "18446744073709551615: 1 18446744073709551615\n",
+                                answer, &length));
+    CHECK(0 == strcmp("arithmetic overflow", answer));
+
+    char small[2];
+    length = sizeof small;
+    CHECK(AOC_BUFFER_TOO_SMALL == solve(1, 1, lists, small, &length));
+    CHECK(2 == length);
+    CHECK(0 == strcmp("1", small));
+
+    CHECK(AOC_NULL_POINTER == solve(1, 1, lists, answer, NULL));
+    length = sizeof answer;
+    CHECK(AOC_NULL_POINTER == aoc_solve(1, 1, NULL, 1, answer, &length));
+    CHECK(AOC_NULL_POINTER == solve(1, 1, lists, NULL, &length));
+
+    return failures ? 1 : 0;
+}
@@ -0,0 +1,321 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt;
+use std::sync::atomic::{AtomicIsize, AtomicUsize, Ordering};
+
+/// Hands out span tokens; zero means no span.
+static NEXT_SPAN: AtomicUsize = AtomicUsize::new(1);
+
+thread_local! {
+    /// The span this thread's allocations are charged to, if any.
+    static CHARGED_SPAN: Cell<usize> = const { Cell::new(0) };
+}
+
+fn charged_span() -> usize {
+    // Allocations while the thread is being torn down are never charged.
+    CHARGED_SPAN.try_with(Cell::get).unwrap_or(0)
+}
+
+/// One measured span, from `start` to `stop`. Only the thread running
+/// `charge` with the current span is counted, and only the current span can
+/// stop counting, so a worker abandoned mid-span can neither end a later
+/// span nor add to it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Span(usize);
+
+/// Allocation counts for one measured span.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Allocations {
+    /// Calls to `alloc` and `realloc`.
+    pub allocations: usize,
+    /// Bytes requested across those calls.
+    pub bytes: usize,
+    /// Highest live heap size above what was live when the span started.
+    pub peak: usize,
+}
+
+impl fmt::Display for Allocations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocations, {} allocated, {} peak",
+            self.allocations,
+            format_bytes(self.bytes),
+            format_bytes(self.peak)
+        )
+    }
+}
+
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if 0 == unit {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Wraps the system allocator and, during a span, counts what the thread
+/// charged to it allocates. Install it with `#[global_allocator]`; it costs
+/// one thread-local read per call on threads that aren't charged to a span.
+pub struct CountingAllocator {
+    /// The current span's token, or zero.
+    current: AtomicUsize,
+    /// Charged calls between checking `current` and finishing their counts.
+    in_flight: AtomicUsize,
+    allocations: AtomicUsize,
+    bytes: AtomicUsize,
+    live: AtomicIsize,
+    baseline: AtomicIsize,
+    peak: AtomicIsize,
+}
+
+impl Default for CountingAllocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CountingAllocator {
+    pub const fn new() -> Self {
+        Self {
+            current: AtomicUsize::new(0),
+            in_flight: AtomicUsize::new(0),
+            allocations: AtomicUsize::new(0),
+            bytes: AtomicUsize::new(0),
+            live: AtomicIsize::new(0),
+            baseline: AtomicIsize::new(0),
+            peak: AtomicIsize::new(0),
+        }
+    }
+
+    fn record_allocation(&self, size: usize) {
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(size, Ordering::Relaxed);
+        let live = self.live.fetch_add(size as isize, Ordering::Relaxed) + size as isize;
+        self.peak.fetch_max(live, Ordering::Relaxed);
+    }
+
+    fn record_deallocation(&self, size: usize) {
+        self.live.fetch_sub(size as isize, Ordering::Relaxed);
+    }
+
+    /// Runs `record` when this thread is charged to the current span, staying
+    /// in `in_flight` meanwhile so a new span can't start halfway through.
+    fn when_charged(&self, record: impl FnOnce()) {
+        let span = charged_span();
+        if 0 == span {
+            return;
+        }
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        if self.current.load(Ordering::SeqCst) == span {
+            record();
+        }
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// Waits for calls that saw the span that just ended to finish counting.
+    fn settle(&self) {
+        while 0 != self.in_flight.load(Ordering::SeqCst) {
+            std::hint::spin_loop();
+        }
+    }
+
+    /// Zeroes the counters and starts a span, ending any earlier one.
+    pub fn start(&self) -> Span {
+        let span = NEXT_SPAN.fetch_add(1, Ordering::Relaxed);
+        self.current.store(0, Ordering::SeqCst);
+        self.settle();
+        self.allocations.store(0, Ordering::Relaxed);
+        self.bytes.store(0, Ordering::Relaxed);
+        let live = self.live.load(Ordering::Relaxed);
+        self.baseline.store(live, Ordering::Relaxed);
+        self.peak.store(live, Ordering::Relaxed);
+        self.current.store(span, Ordering::SeqCst);
+        Span(span)
+    }
+
+    /// Runs `work`, counting what it allocates on this thread towards `span`
+    /// while that span is current.
+    pub fn charge<T>(&self, span: Span, work: impl FnOnce() -> T) -> T {
+        /// Puts back what the thread was charged to, even if `work` panics.
+        struct Restore(usize);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let _ = CHARGED_SPAN.try_with(|charged| charged.set(self.0));
+            }
+        }
+        let _restore = Restore(CHARGED_SPAN.with(|charged| charged.replace(span.0)));
+        work()
+    }
+
+    /// Ends `span` and returns what was counted since it started. A span that
+    /// has already been replaced by a later one counts nothing and leaves
+    /// the later one running.
+    pub fn stop(&self, span: Span) -> Allocations {
+        if self
+            .current
+            .compare_exchange(span.0, 0, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return Allocations::default();
+        }
+        self.settle();
+        let peak = self.peak.load(Ordering::Relaxed) - self.baseline.load(Ordering::Relaxed);
+        Allocations {
+            allocations: self.allocations.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+            peak: peak.max(0) as usize,
+        }
+    }
+
+    /// Counts what `work` allocates on this thread.
+    pub fn measure<T>(&self, work: impl FnOnce() -> T) -> (T, Allocations) {
+        let span = self.start();
+        let result = self.charge(span, work);
+        (result, self.stop(span))
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc(layout);
+        if !pointer.is_null() {
+            self.when_charged(|| self.record_allocation(layout.size()));
+        }
+        pointer
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc_zeroed(layout);
+        if !pointer.is_null() {
+            self.when_charged(|| self.record_allocation(layout.size()));
+        }
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout);
+        self.when_charged(|| self.record_deallocation(layout.size()));
+    }
+
+    unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_pointer = System.realloc(pointer, layout, new_size);
+        if !new_pointer.is_null() {
+            self.when_charged(|| {
+                self.record_deallocation(layout.size());
+                self.record_allocation(new_size);
+            });
+        }
+        new_pointer
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_formats_bytes() {
+        assert_eq!("0 B", format_bytes(0));
+        assert_eq!("1023 B", format_bytes(1023));
+        assert_eq!("1.5 KiB", format_bytes(1536));
+        assert_eq!("2.0 MiB", format_bytes(2 * 1024 * 1024));
+        assert_eq!("4096.0 GiB", format_bytes(4096 * 1024 * 1024 * 1024));
+        assert_eq!(
+            "2 allocations, 1.0 KiB allocated, 512 B peak",
+            Allocations {
+                allocations: 2,
+                bytes: 1024,
+                peak: 512
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn it_counts_allocations_while_enabled() {
+        let allocator = CountingAllocator::new();
+        let layout = Layout::from_size_align(64, 8).unwrap();
+        unsafe {
+            let ignored = allocator.alloc(layout);
+            let (_, counted) = allocator.measure(|| {
+                let first = allocator.alloc(layout);
+                let second = allocator.alloc_zeroed(layout);
+                allocator.dealloc(first, layout);
+                let second = allocator.realloc(second, layout, 256);
+                allocator.dealloc(second, Layout::from_size_align(256, 8).unwrap());
+            });
+            assert_eq!(
+                Allocations {
+                    allocations: 3,
+                    bytes: 384,
+                    peak: 256,
+                },
+                counted
+            );
+            allocator.dealloc(ignored, layout);
+        }
+        let allocator = CountingAllocator::default();
+        let span = allocator.start();
+        assert_eq!(Allocations::default(), allocator.stop(span));
+    }
+
+    #[test]
+    fn it_only_lets_the_current_span_stop_or_be_charged() {
+        let allocator = CountingAllocator::new();
+        let layout = Layout::from_size_align(32, 8).unwrap();
+        unsafe {
+            let abandoned = allocator.start();
+            let current = allocator.start();
+            assert_eq!(Allocations::default(), allocator.stop(abandoned));
+            let stale = allocator.charge(abandoned, || allocator.alloc(layout));
+            let outside = allocator.alloc(layout);
+            let counted = allocator.charge(current, || allocator.alloc(layout));
+            assert_eq!(
+                Allocations {
+                    allocations: 1,
+                    bytes: 32,
+                    peak: 32,
+                },
+                allocator.stop(current)
+            );
+            for pointer in [stale, outside, counted] {
+                allocator.dealloc(pointer, layout);
+            }
+        }
+    }
+
+    #[test]
+    fn it_measures_peaks_from_the_starting_point() {
+        let allocator = CountingAllocator::new();
+        let layout = Layout::from_size_align(128, 8).unwrap();
+        unsafe {
+            let (kept, _) = allocator.measure(|| allocator.alloc(layout));
+            let (_, counted) = allocator.measure(|| allocator.dealloc(kept, layout));
+            assert_eq!(0, counted.peak);
+            assert_eq!(0, counted.allocations);
+        }
+    }
+}
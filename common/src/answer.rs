@@ -0,0 +1,178 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use num_bigint::{BigInt, BigUint};
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// The answer to a single part of a puzzle.
+///
+/// Numbers are always stored in the narrowest variant that fits, so that an
+/// answer computed as an `i64` or a `BigUint` compares equal to the same
+/// answer read back from disk.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Answer {
+    Unsigned(u128),
+    Signed(i128),
+    Big(BigInt),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsigned(number) => write!(f, "{}", number),
+            Self::Signed(number) => write!(f, "{}", number),
+            Self::Big(number) => write!(f, "{}", number),
+            Self::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim();
+        if let Ok(number) = input.parse::<u128>() {
+            return Ok(Self::Unsigned(number));
+        }
+        if let Ok(number) = input.parse::<i128>() {
+            return Ok(Self::from(number));
+        }
+        if let Ok(number) = input.parse::<BigInt>() {
+            return Ok(Self::from(number));
+        }
+        Ok(Self::Text(input.to_string()))
+    }
+}
+
+macro_rules! impl_from_unsigned {
+    ($($number:ty),*) => {
+        $(
+            impl From<$number> for Answer {
+                fn from(number: $number) -> Self {
+                    Self::Unsigned(number as u128)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_from_signed {
+    ($($number:ty),*) => {
+        $(
+            impl From<$number> for Answer {
+                fn from(number: $number) -> Self {
+                    if number < 0 {
+                        Self::Signed(number as i128)
+                    } else {
+                        Self::Unsigned(number as u128)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_from_signed!(i8, i16, i32, i64, i128, isize);
+
+impl From<BigInt> for Answer {
+    fn from(number: BigInt) -> Self {
+        if let Ok(number) = u128::try_from(&number) {
+            Self::Unsigned(number)
+        } else if let Ok(number) = i128::try_from(&number) {
+            Self::Signed(number)
+        } else {
+            Self::Big(number)
+        }
+    }
+}
+
+impl From<BigUint> for Answer {
+    fn from(number: BigUint) -> Self {
+        Self::from(BigInt::from(number))
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+/// Coordinate pairs are submitted as `x,y`.
+impl<X: fmt::Display, Y: fmt::Display> From<(X, Y)> for Answer {
+    fn from((x, y): (X, Y)) -> Self {
+        Self::Text(format!("{},{}", x, y))
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_displays_answers() {
+        assert_eq!("11", Answer::Unsigned(11).to_string());
+        assert_eq!("-11", Answer::Signed(-11).to_string());
+        assert_eq!("6,1", Answer::Text("6,1".to_string()).to_string());
+    }
+
+    #[test]
+    fn it_converts_numbers() {
+        assert_eq!(Answer::Unsigned(31), Answer::from(31usize));
+        assert_eq!(Answer::Unsigned(31), Answer::from(31i64));
+        assert_eq!(Answer::Signed(-31), Answer::from(-31i32));
+        assert_eq!(Answer::Unsigned(u128::MAX), Answer::from(u128::MAX));
+    }
+
+    #[test]
+    fn it_narrows_big_numbers() {
+        assert_eq!(Answer::Unsigned(31), Answer::from(BigUint::from(31u32)));
+        assert_eq!(Answer::Signed(-31), Answer::from(BigInt::from(-31)));
+        let big = BigUint::from(u128::MAX) * 2u32;
+        assert_eq!(
+            Answer::Big(BigInt::from(big.clone())),
+            Answer::from(big.clone())
+        );
+        assert_eq!(big.to_string(), Answer::from(big).to_string());
+    }
+
+    #[test]
+    fn it_converts_text_and_pairs() {
+        assert_eq!(Answer::Text("abc".to_string()), Answer::from("abc"));
+        assert_eq!(Answer::Text("6,1".to_string()), Answer::from((6, 1)));
+    }
+
+    #[test]
+    fn it_parses_answers() {
+        assert_eq!(Answer::Unsigned(143), "143\n".parse().unwrap());
+        assert_eq!(Answer::Signed(-143), "-143".parse().unwrap());
+        assert_eq!(
+            Answer::from(BigUint::from(u128::MAX) * 2u32),
+            "680564733841876926926749214863536422910".parse().unwrap()
+        );
+        assert_eq!(Answer::Text("6,1".to_string()), "6,1".parse().unwrap());
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+/// How a part's answer was reached, one line per step, for `aoc run
+/// --explain`. Only the first `limit` lines are kept; the rest are counted
+/// without being formatted, so a day can describe every step of a big input.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Explanation {
+    limit: usize,
+    lines: Vec<String>,
+    omitted: usize,
+}
+
+impl Explanation {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            lines: Vec::new(),
+            omitted: 0,
+        }
+    }
+
+    /// Adds a line, or counts it once `limit` lines are kept. Pass
+    /// `format_args!` to skip formatting lines that won't be kept.
+    pub fn line(&mut self, line: impl fmt::Display) {
+        if self.is_full() {
+            self.omitted += 1;
+        } else {
+            self.lines.push(line.to_string());
+        }
+    }
+
+    /// Whether further lines will only be counted.
+    pub fn is_full(&self) -> bool {
+        self.lines.len() >= self.limit
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// How many lines were offered after the limit was reached.
+    pub fn omitted(&self) -> usize {
+        self.omitted
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{}", line)?;
+        }
+        if 0 < self.omitted {
+            writeln!(f, "... and {} more", self.omitted)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_keeps_the_first_lines() {
+        let mut explanation = Explanation::new(2);
+        explanation.line("first");
+        assert!(!explanation.is_full());
+        explanation.line(format_args!("second {}", 2));
+        explanation.line("third");
+        explanation.line("fourth");
+        assert!(explanation.is_full());
+        assert_eq!(["first", "second 2"], explanation.lines());
+        assert_eq!(2, explanation.omitted());
+        assert_eq!("first\nsecond 2\n... and 2 more\n", explanation.to_string());
+        assert_eq!("", Explanation::new(0).to_string());
+    }
+}
@@ -0,0 +1,31 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub mod alloc;
+mod answer;
+mod explain;
+mod memo;
+mod number;
+pub mod registry;
+mod validate;
+
+pub use answer::Answer;
+pub use aoc_macros::{aoc, aoc_examples, require_days};
+pub use explain::Explanation;
+#[doc(hidden)]
+pub use linkme;
+pub use memo::{Memo, Stats};
+pub use num_bigint::{BigInt, BigUint};
+pub use number::{parse_number, Arithmetic, Number, Overflow};
+pub use validate::{check_grid, check_numbers, check_text, Problem};
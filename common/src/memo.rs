@@ -0,0 +1,262 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+
+/// Hit, miss and eviction counts for a `Memo`.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Stats {
+    pub hits: usize,
+    pub misses: usize,
+    pub evictions: usize,
+}
+
+impl Stats {
+    pub fn hit_rate(&self) -> f64 {
+        if 0 == self.hits + self.misses {
+            return 0.0;
+        }
+        self.hits as f64 / (self.hits + self.misses) as f64
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate), {} evictions",
+            self.hits,
+            self.misses,
+            100.0 * self.hit_rate(),
+            self.evictions
+        )
+    }
+}
+
+/// A memoization cache owned by whoever creates it, so each solve gets a fresh
+/// one instead of sharing a process-wide static.
+///
+/// Recursive functions take the cache as an argument and call back into it:
+///
+/// ```
+/// use common::Memo;
+///
+/// fn fibonacci(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+///     memo.call(n, |memo| match n {
+///         0 | 1 => n,
+///         _ => fibonacci(n - 1, memo) + fibonacci(n - 2, memo),
+///     })
+/// }
+///
+/// let mut memo = Memo::new();
+/// assert_eq!(12586269025, fibonacci(50, &mut memo));
+/// assert_eq!(51, memo.stats().misses);
+/// ```
+///
+/// With a capacity limit the oldest entry is evicted to make room.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    values: HashMap<K, V>,
+    order: VecDeque<K>,
+    capacity: Option<usize>,
+    stats: Stats,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self {
+            values: HashMap::new(),
+            order: VecDeque::new(),
+            capacity: None,
+            stats: Stats::default(),
+        }
+    }
+
+    pub fn with_capacity_limit(capacity: usize) -> Self {
+        Self {
+            capacity: Some(capacity),
+            ..Self::new()
+        }
+    }
+
+    /// Looks up `key`, counting a hit or a miss.
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        match self.values.get(key) {
+            Some(value) => {
+                self.stats.hits += 1;
+                Some(value.clone())
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        if let Some(existing) = self.values.get_mut(&key) {
+            *existing = value;
+            return;
+        }
+        if let Some(capacity) = self.capacity {
+            if 0 == capacity {
+                return;
+            }
+            while self.values.len() >= capacity {
+                let oldest = self.order.pop_front().expect("Memo order is out of sync");
+                self.values.remove(&oldest);
+                self.stats.evictions += 1;
+            }
+        }
+        self.order.push_back(key.clone());
+        self.values.insert(key, value);
+    }
+
+    /// Returns the cached value for `key` or computes and caches it.
+    /// `compute` gets the cache back so it can recurse.
+    pub fn call(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.get(&key) {
+            return value;
+        }
+        let value = compute(self);
+        self.insert(key, value.clone());
+        value
+    }
+
+    /// Like `call`, but errors are passed through without being cached.
+    pub fn try_call<E>(
+        &mut self,
+        key: K,
+        compute: impl FnOnce(&mut Self) -> Result<V, E>,
+    ) -> Result<V, E> {
+        if let Some(value) = self.get(&key) {
+            return Ok(value);
+        }
+        let value = compute(self)?;
+        self.insert(key, value.clone());
+        Ok(value)
+    }
+
+    /// Drops every cached value but keeps the statistics.
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.order.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+        memo.call(n, |memo| if 0 == n { 0 } else { n + triangle(n - 1, memo) })
+    }
+
+    #[test]
+    fn it_counts_hits_and_misses() {
+        let mut memo = Memo::new();
+        assert_eq!(None, memo.get(&1));
+        memo.insert(1, 2);
+        assert_eq!(Some(2), memo.get(&1));
+        assert_eq!(
+            Stats {
+                hits: 1,
+                misses: 1,
+                evictions: 0
+            },
+            memo.stats()
+        );
+        assert_eq!(0.5, memo.stats().hit_rate());
+        assert_eq!(
+            "1 hits, 1 misses (50.0% hit rate), 0 evictions",
+            memo.stats().to_string()
+        );
+        assert_eq!(0.0, Stats::default().hit_rate());
+    }
+
+    #[test]
+    fn it_memoizes_recursive_calls() {
+        let mut memo = Memo::new();
+        assert_eq!(55, triangle(10, &mut memo));
+        assert_eq!(11, memo.len());
+        assert_eq!(0, memo.stats().hits);
+        assert_eq!(66, triangle(11, &mut memo));
+        assert_eq!(1, memo.stats().hits);
+    }
+
+    #[test]
+    fn it_evicts_the_oldest_entries() {
+        let mut memo = Memo::with_capacity_limit(2);
+        memo.insert(1, 1);
+        memo.insert(2, 2);
+        memo.insert(2, 4);
+        assert_eq!(0, memo.stats().evictions);
+        memo.insert(3, 3);
+        assert_eq!(2, memo.len());
+        assert_eq!(1, memo.stats().evictions);
+        assert_eq!(None, memo.get(&1));
+        assert_eq!(Some(4), memo.get(&2));
+        let mut memo = Memo::with_capacity_limit(2);
+        assert_eq!(55, triangle(10, &mut memo));
+        assert_eq!(2, memo.len());
+        assert_eq!(9, memo.stats().evictions);
+    }
+
+    #[test]
+    fn it_can_disable_caching() {
+        let mut memo = Memo::with_capacity_limit(0);
+        memo.insert(1, 1);
+        assert!(memo.is_empty());
+    }
+
+    #[test]
+    fn it_does_not_cache_errors() {
+        let mut memo: Memo<u8, u8> = Memo::new();
+        assert_eq!(Err("nope"), memo.try_call(1, |_| Err("nope")));
+        assert!(memo.is_empty());
+        assert_eq!(Ok::<u8, ()>(2), memo.try_call(1, |_| Ok(2)));
+        assert_eq!(Ok::<u8, ()>(2), memo.try_call(1, |_| Err(())));
+    }
+
+    #[test]
+    fn it_clears_values_but_keeps_stats() {
+        let mut memo = Memo::new();
+        triangle(3, &mut memo);
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(4, memo.stats().misses);
+        assert_eq!(Memo::<u8, u8>::default().stats(), Stats::default());
+    }
+}
@@ -0,0 +1,200 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::Answer;
+use num_bigint::BigUint;
+use num_traits::{CheckedAdd, CheckedMul, One, Zero};
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+use std::str::FromStr;
+
+/// Returned instead of a wrapped value when a calculation doesn't fit.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "arithmetic overflow")
+    }
+}
+
+impl Error for Overflow {}
+
+/// An unsigned integer backend for solvers whose numbers can outgrow `u64`.
+pub trait Number:
+    Clone
+    + Eq
+    + Ord
+    + Hash
+    + fmt::Display
+    + FromStr
+    + From<u32>
+    + CheckedAdd
+    + CheckedMul
+    + Zero
+    + One
+    + Into<Answer>
+{
+    fn try_add(&self, other: &Self) -> Result<Self, Overflow> {
+        self.checked_add(other).ok_or(Overflow)
+    }
+
+    fn try_mul(&self, other: &Self) -> Result<Self, Overflow> {
+        self.checked_mul(other).ok_or(Overflow)
+    }
+}
+
+impl Number for u64 {}
+impl Number for u128 {}
+impl Number for BigUint {}
+
+/// Parses a run of digits, treating a number too large for `N` as overflow.
+pub fn parse_number<N: Number>(text: &str) -> Result<N, Overflow> {
+    match text.parse::<N>() {
+        Ok(number) => Ok(number),
+        Err(_) if !text.is_empty() && text.chars().all(|c| c.is_ascii_digit()) => Err(Overflow),
+        Err(_) => panic!("Unable to parse number {:?}", text),
+    }
+}
+
+/// Which `Number` backend a solver should use.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Arithmetic {
+    #[default]
+    U64,
+    U128,
+    Big,
+}
+
+impl fmt::Display for Arithmetic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::U64 => write!(f, "u64"),
+            Self::U128 => write!(f, "u128"),
+            Self::Big => write!(f, "big"),
+        }
+    }
+}
+
+impl FromStr for Arithmetic {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "u64" => Ok(Self::U64),
+            "u128" => Ok(Self::U128),
+            "big" => Ok(Self::Big),
+            _ => Err(format!(
+                "unknown arithmetic {:?}; expected u64, u128 or big",
+                input
+            )),
+        }
+    }
+}
+
+/// Runs `$body` with `$number` bound to the type chosen by `$arithmetic`,
+/// converting the result into an `Answer`.
+///
+/// ```
+/// use common::{with_arithmetic, Arithmetic, Number, Overflow};
+///
+/// fn double<N: Number>() -> Result<N, Overflow> {
+///     N::from(21).try_add(&N::from(21))
+/// }
+///
+/// let answer = with_arithmetic!(Arithmetic::Big, N => double::<N>());
+/// assert_eq!(common::Answer::from(42), answer.unwrap());
+/// ```
+#[macro_export]
+macro_rules! with_arithmetic {
+    ($arithmetic:expr, $number:ident => $body:expr) => {
+        match $arithmetic {
+            $crate::Arithmetic::U64 => {
+                type $number = u64;
+                $body.map($crate::Answer::from)
+            }
+            $crate::Arithmetic::U128 => {
+                type $number = u128;
+                $body.map($crate::Answer::from)
+            }
+            $crate::Arithmetic::Big => {
+                type $number = $crate::BigUint;
+                $body.map($crate::Answer::from)
+            }
+        }
+    };
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_detects_overflow() {
+        assert_eq!(Ok(4), 2u64.try_add(&2));
+        assert_eq!(Err(Overflow), u64::MAX.try_add(&1));
+        assert_eq!(Err(Overflow), u128::MAX.try_mul(&2));
+        assert_eq!(
+            Ok(BigUint::from(u128::MAX) * 2u32),
+            BigUint::from(u128::MAX).try_mul(&BigUint::from(2u32))
+        );
+    }
+
+    #[test]
+    fn it_parses_numbers() {
+        assert_eq!(Ok(2024u64), parse_number("2024"));
+        assert_eq!(Err(Overflow), parse_number::<u64>("18446744073709551616"));
+        assert_eq!(
+            Ok(18446744073709551616u128),
+            parse_number("18446744073709551616")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Unable to parse number \"abc\"")]
+    fn it_rejects_non_numbers() {
+        let _ = parse_number::<u64>("abc");
+    }
+
+    #[test]
+    fn it_parses_arithmetic() {
+        for arithmetic in [Arithmetic::U64, Arithmetic::U128, Arithmetic::Big] {
+            assert_eq!(Ok(arithmetic), arithmetic.to_string().parse());
+        }
+        assert!("i32".parse::<Arithmetic>().is_err());
+    }
+
+    #[test]
+    fn it_dispatches_on_arithmetic() {
+        fn cube<N: Number>(number: u32) -> Result<N, Overflow> {
+            N::from(number)
+                .try_mul(&N::from(number))?
+                .try_mul(&N::from(number))
+        }
+        assert_eq!(
+            Err(Overflow),
+            with_arithmetic!(Arithmetic::U64, N => cube::<N>(u32::MAX))
+        );
+        assert_eq!(
+            Ok(Answer::from(u32::MAX as u128 * u32::MAX as u128)),
+            with_arithmetic!(Arithmetic::U128, N => N::from(u32::MAX).try_mul(&N::from(u32::MAX)))
+        );
+        assert_eq!(
+            Ok(Answer::from(BigUint::from(u32::MAX).pow(3))),
+            with_arithmetic!(Arithmetic::Big, N => cube::<N>(u32::MAX))
+        );
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! What `#[aoc]` registers from each day crate. The `aoc` runner assembles
+//! these into one solution per day of each year.
+
+use crate::{Answer, Arithmetic, Explanation, Overflow, Problem};
+use linkme::distributed_slice;
+
+pub type Part = fn(String) -> Answer;
+pub type CheckedPart = fn(String, Arithmetic) -> Result<Answer, Overflow>;
+/// Runs a day's input parser on its own and throws the result away, so the
+/// parse can be profiled apart from the parts.
+pub type Parse = fn(String);
+/// Checks an input's shape, returning every problem found.
+pub type Validate = fn(&str) -> Vec<Problem>;
+/// Draws a day's input as text, marked up with how it was solved.
+pub type Visualize = fn(&str) -> String;
+/// Describes how a part of the input was solved, step by step.
+pub type Explain = fn(&str, u8, &mut Explanation);
+
+/// How `aoc minimize` may cut a day's input down without breaking its format.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum Shape {
+    /// Whole lines.
+    #[default]
+    Lines,
+    /// Whole lines, then whitespace-separated tokens within each line.
+    Tokens,
+    /// Whole lines, then single characters within each line.
+    Characters,
+    /// Whole rows, then whole columns, so the grid stays rectangular.
+    Grid,
+    /// Lines within each blank-line-separated section, keeping every section.
+    Sections,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Registered {
+    /// A part, or a named alternative implementation of one.
+    Part {
+        part: u8,
+        variant: Option<&'static str>,
+        function: Part,
+    },
+    /// A part that can run on any `Arithmetic` backend and reports overflow.
+    Checked {
+        part: u8,
+        function: CheckedPart,
+    },
+    Parse(Parse),
+    Validate {
+        function: Validate,
+        shape: Shape,
+    },
+    Visualize(Visualize),
+    Explain(Explain),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Registration {
+    pub year: u16,
+    pub day: u8,
+    pub registered: Registered,
+}
+
+/// Every registration from every day crate linked into the binary.
+#[distributed_slice]
+pub static REGISTRATIONS: [Registration];
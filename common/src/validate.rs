@@ -0,0 +1,148 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+/// Something wrong with a puzzle input, found before trying to solve it.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Problem {
+    /// The 1-based line the problem is on, if it is on one line.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Problem {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            line: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn on_line(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line: Some(line),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Catches inputs no day can use: nothing at all, or the login page that
+/// comes back when the session cookie has expired.
+pub fn check_text(input: &str) -> Vec<Problem> {
+    let start = input.trim_start().to_ascii_lowercase();
+    if input.trim().is_empty() {
+        vec![Problem::new("input is empty")]
+    } else if start.starts_with("<!doctype html") || start.starts_with("<html") {
+        vec![Problem::new(
+            "input is an HTML page; is the session cookie still valid?",
+        )]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Checks that `input` is a rectangular grid of cells that `allowed`
+/// accepts, reporting the first bad cell of each line.
+pub fn check_grid(input: &str, allowed: impl Fn(char) -> bool) -> Vec<Problem> {
+    let mut problems = check_text(input);
+    if !problems.is_empty() {
+        return problems;
+    }
+    let width = input.lines().next().map_or(0, |line| line.chars().count());
+    for (index, line) in input.trim_end().lines().enumerate() {
+        let length = line.chars().count();
+        if length != width {
+            problems.push(Problem::on_line(
+                index + 1,
+                format!("row has {} cells, expected {}", length, width),
+            ));
+        }
+        if let Some((column, character)) = line
+            .chars()
+            .enumerate()
+            .find(|(_, character)| !allowed(*character))
+        {
+            problems.push(Problem::on_line(
+                index + 1,
+                format!("unexpected {:?} in column {}", character, column + 1),
+            ));
+        }
+    }
+    problems
+}
+
+/// Checks that every word of `line` is an unsigned number.
+pub fn check_numbers<'a>(line: usize, words: impl IntoIterator<Item = &'a str>) -> Vec<Problem> {
+    words
+        .into_iter()
+        .filter(|word| word.is_empty() || !word.chars().all(|character| character.is_ascii_digit()))
+        .map(|word| Problem::on_line(line, format!("{:?} is not a number", word)))
+        .collect()
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_displays_problems() {
+        assert_eq!("input is empty", Problem::new("input is empty").to_string());
+        assert_eq!(
+            "line 3: row is short",
+            Problem::on_line(3, "row is short").to_string()
+        );
+    }
+
+    #[test]
+    fn it_rejects_empty_and_html_input() {
+        assert_eq!(vec![Problem::new("input is empty")], check_text(" \n"));
+        assert_eq!(1, check_text("\n<!DOCTYPE html>\n<html>").len());
+        assert!(check_text("1 2\n").is_empty());
+    }
+
+    #[test]
+    fn it_checks_grids() {
+        assert!(check_grid("..#\n.^.\n", |character| ".#^".contains(character)).is_empty());
+        assert_eq!(
+            vec![
+                Problem::on_line(2, "row has 2 cells, expected 3"),
+                Problem::on_line(3, "unexpected 'x' in column 2"),
+            ],
+            check_grid("...\n..\n.x.", |character| '.' == character)
+        );
+    }
+
+    #[test]
+    fn it_checks_numbers() {
+        assert!(check_numbers(1, "3 4".split(' ')).is_empty());
+        assert_eq!(
+            vec![
+                Problem::on_line(2, "\"-1\" is not a number"),
+                Problem::on_line(2, "\"\" is not a number"),
+            ],
+            check_numbers(2, "-1,,5".split(','))
+        );
+    }
+}
@@ -0,0 +1,624 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Attribute and function-like macros that register each day's functions
+//! with the `aoc` runner.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream, Parser};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Error, Expr, ExprLit, Ident, ItemFn, Lit, LitInt, LitStr, Meta, Token};
+
+/// What a function is registered as.
+#[derive(Debug, PartialEq, Eq)]
+enum Kind {
+    Part {
+        part: u8,
+        variant: Option<String>,
+        checked: bool,
+    },
+    Parse,
+    Validate {
+        shape: Option<Ident>,
+    },
+    Visualize,
+    Explain,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Registration {
+    year: u16,
+    day: u8,
+    kind: Kind,
+}
+
+/// The first Advent of Code.
+const FIRST_YEAR: u16 = 2015;
+
+fn integer<N>(expr: &Expr) -> syn::Result<N>
+where
+    N: std::str::FromStr,
+    N::Err: std::fmt::Display,
+{
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(int), ..
+        }) => int.base10_parse(),
+        _ => Err(Error::new_spanned(expr, "expected an integer")),
+    }
+}
+
+fn parse_registration(attribute: TokenStream2) -> syn::Result<Registration> {
+    let metas = Punctuated::<Meta, Token![,]>::parse_terminated.parse2(attribute)?;
+    let mut year = None;
+    let mut day = None;
+    let mut part = None;
+    let mut variant = None;
+    let mut shape = None;
+    let mut flags = Vec::new();
+    for meta in &metas {
+        match meta {
+            Meta::NameValue(pair) if pair.path.is_ident("year") => {
+                let value = integer(&pair.value)?;
+                if value < FIRST_YEAR {
+                    return Err(Error::new_spanned(
+                        &pair.value,
+                        "year must be 2015 or later",
+                    ));
+                }
+                year = Some(value);
+            }
+            Meta::NameValue(pair) if pair.path.is_ident("day") => {
+                let value = integer(&pair.value)?;
+                if !(1..=25).contains(&value) {
+                    return Err(Error::new_spanned(&pair.value, "day must be from 1 to 25"));
+                }
+                day = Some(value);
+            }
+            Meta::NameValue(pair) if pair.path.is_ident("part") => {
+                let value = integer(&pair.value)?;
+                if !(1..=2).contains(&value) {
+                    return Err(Error::new_spanned(&pair.value, "part must be 1 or 2"));
+                }
+                part = Some(value);
+            }
+            Meta::NameValue(pair) if pair.path.is_ident("variant") => {
+                let Expr::Lit(ExprLit {
+                    lit: Lit::Str(name),
+                    ..
+                }) = &pair.value
+                else {
+                    return Err(Error::new_spanned(&pair.value, "expected a string"));
+                };
+                let value = name.value();
+                let valid = value
+                    .chars()
+                    .next()
+                    .is_some_and(|first| first.is_ascii_lowercase())
+                    && value.chars().all(|character| {
+                        character.is_ascii_lowercase()
+                            || character.is_ascii_digit()
+                            || '_' == character
+                    });
+                if !valid || "default" == value {
+                    return Err(Error::new_spanned(
+                        name,
+                        "variant names are lowercase identifiers other than \"default\"",
+                    ));
+                }
+                variant = Some(value);
+            }
+            Meta::NameValue(pair) if pair.path.is_ident("shape") => match &pair.value {
+                Expr::Path(path) if path.path.get_ident().is_some() => {
+                    shape = path.path.get_ident().cloned();
+                }
+                value => return Err(Error::new_spanned(value, "expected a `Shape` variant")),
+            },
+            Meta::Path(path)
+                if ["checked", "parse", "validate", "visualize", "explain"]
+                    .iter()
+                    .any(|flag| path.is_ident(flag)) =>
+            {
+                flags.push(path.clone());
+            }
+            _ => return Err(Error::new_spanned(meta, "unknown `aoc` option")),
+        }
+    }
+    let year = year.ok_or_else(|| Error::new(Span::call_site(), "missing `year = N`"))?;
+    let day = day.ok_or_else(|| Error::new(Span::call_site(), "missing `day = N`"))?;
+    let is = |flag: &str| flags.iter().any(|path| path.is_ident(flag));
+    let kinds = usize::from(part.is_some())
+        + ["parse", "validate", "visualize", "explain"]
+            .iter()
+            .filter(|flag| is(flag))
+            .count();
+    if 1 != kinds {
+        return Err(Error::new_spanned(
+            &metas,
+            "expected exactly one of `part = N`, `parse`, `validate`, `visualize` or `explain`",
+        ));
+    }
+    if part.is_none() && (is("checked") || variant.is_some()) {
+        return Err(Error::new_spanned(
+            &metas,
+            "`checked` and `variant` need `part = N`",
+        ));
+    }
+    if is("checked") && variant.is_some() {
+        return Err(Error::new_spanned(&metas, "variants cannot be checked"));
+    }
+    if !is("validate") && shape.is_some() {
+        return Err(Error::new_spanned(
+            &metas,
+            "`shape` belongs on the `validate` registration",
+        ));
+    }
+    let kind = match part {
+        Some(part) => Kind::Part {
+            part,
+            variant,
+            checked: is("checked"),
+        },
+        None if is("parse") => Kind::Parse,
+        None if is("validate") => Kind::Validate { shape },
+        None if is("visualize") => Kind::Visualize,
+        None => Kind::Explain,
+    };
+    Ok(Registration { year, day, kind })
+}
+
+/// The name of the constant that marks a registration. `require_days!`
+/// refers to these, and registering the same thing twice defines one twice.
+fn marker(year: u16, day: u8, kind: &Kind) -> Ident {
+    let suffix = match kind {
+        Kind::Part {
+            part,
+            variant: Some(variant),
+            ..
+        } => format!("PART_{}_{}", part, variant.to_uppercase()),
+        Kind::Part {
+            part,
+            checked: true,
+            ..
+        } => format!("PART_{}_CHECKED", part),
+        Kind::Part { part, .. } => format!("PART_{}", part),
+        Kind::Parse => "PARSE".to_string(),
+        Kind::Validate { .. } => "VALIDATE".to_string(),
+        Kind::Visualize => "VISUALIZE".to_string(),
+        Kind::Explain => "EXPLAIN".to_string(),
+    };
+    format_ident!("__AOC_{}_DAY_{:02}_{}", year, day, suffix)
+}
+
+fn expand_aoc(attribute: TokenStream2, item: TokenStream2) -> syn::Result<TokenStream2> {
+    let Registration { year, day, kind } = parse_registration(attribute)?;
+    let function: ItemFn = syn::parse2(item)?;
+    let name = &function.sig.ident;
+    let registered = match &kind {
+        Kind::Part {
+            part,
+            checked: true,
+            ..
+        } => quote! {
+            ::common::registry::Registered::Checked { part: #part, function: #name }
+        },
+        Kind::Part { part, variant, .. } => {
+            let variant = match variant {
+                Some(variant) => quote!(::core::option::Option::Some(#variant)),
+                None => quote!(::core::option::Option::None),
+            };
+            quote! {
+                ::common::registry::Registered::Part {
+                    part: #part,
+                    variant: #variant,
+                    function: |input| #name(input).into(),
+                }
+            }
+        }
+        Kind::Parse => quote! {
+            ::common::registry::Registered::Parse(|input| {
+                let _ = #name(input);
+            })
+        },
+        Kind::Validate { shape } => {
+            let shape = shape.clone().unwrap_or_else(|| format_ident!("Lines"));
+            quote! {
+                ::common::registry::Registered::Validate {
+                    function: #name,
+                    shape: ::common::registry::Shape::#shape,
+                }
+            }
+        }
+        Kind::Visualize => quote!(::common::registry::Registered::Visualize(#name)),
+        Kind::Explain => quote!(::common::registry::Registered::Explain(#name)),
+    };
+    let marker = marker(year, day, &kind);
+    let registration = format_ident!("{}_REGISTRATION", marker, span = name.span());
+    Ok(quote! {
+        #function
+
+        #[doc(hidden)]
+        pub const #marker: () = ();
+
+        #[::common::linkme::distributed_slice(::common::registry::REGISTRATIONS)]
+        #[linkme(crate = ::common::linkme)]
+        static #registration: ::common::registry::Registration =
+            ::common::registry::Registration {
+                year: #year,
+                day: #day,
+                registered: #registered,
+            };
+    })
+}
+
+/// Registers a day's function with the `aoc` runner. Every registration
+/// names its `year` and `day`:
+///
+/// - `#[aoc(year = 2024, day = 6, part = 1)]`: a part, returning anything
+///   that converts into `Answer`
+/// - `#[aoc(year = 2024, day = 6, part = 2, variant = "states")]`: an
+///   alternative implementation of a part
+/// - `#[aoc(year = 2024, day = 7, part = 1, checked)]`: the overflow-checked
+///   version of a part, taking an `Arithmetic`
+/// - `#[aoc(year = 2024, day = 6, parse)]`: the input parser to profile on
+///   its own
+/// - `#[aoc(year = 2024, day = 6, validate, shape = Grid)]`: the input check,
+///   and how `aoc minimize` may cut the input, `Lines` by default
+/// - `#[aoc(year = 2024, day = 6, visualize)]`: a function that draws the
+///   input
+/// - `#[aoc(year = 2024, day = 2, explain)]`: a function that describes how
+///   each part reaches its answer, for `aoc run --explain`
+///
+/// Registering the same thing twice in a module fails to compile.
+#[cfg(not(tarpaulin_include))]
+#[proc_macro_attribute]
+pub fn aoc(attribute: TokenStream, item: TokenStream) -> TokenStream {
+    expand_aoc(attribute.into(), item.into())
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// A year and an inclusive range of its days, such as `2024, 1..=11`.
+struct Days {
+    year: u16,
+    first: u8,
+    last: u8,
+}
+
+impl Parse for Days {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let year: LitInt = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let first: LitInt = input.parse()?;
+        input.parse::<Token![..=]>()?;
+        let last: LitInt = input.parse()?;
+        let days = Days {
+            year: year.base10_parse()?,
+            first: first.base10_parse()?,
+            last: last.base10_parse()?,
+        };
+        if days.year < FIRST_YEAR {
+            return Err(Error::new(year.span(), "year must be 2015 or later"));
+        }
+        if days.first < 1 || days.last < days.first || 25 < days.last {
+            return Err(Error::new(
+                first.span().join(last.span()).unwrap_or(first.span()),
+                "expected days from 1 to 25, such as `2024, 1..=11`",
+            ));
+        }
+        Ok(days)
+    }
+}
+
+fn expand_require_days(input: TokenStream2) -> syn::Result<TokenStream2> {
+    let Days { year, first, last } = syn::parse2(input.clone())?;
+    let checks = (first..=last).map(|day| {
+        let krate = format_ident!("year_{}_day_{:02}", year, day, span = input.span());
+        let kinds = [
+            Kind::Part {
+                part: 1,
+                variant: None,
+                checked: false,
+            },
+            Kind::Part {
+                part: 2,
+                variant: None,
+                checked: false,
+            },
+            Kind::Validate { shape: None },
+        ];
+        let markers = kinds.iter().map(|kind| marker(year, day, kind));
+        quote!(#(let _ = #krate::#markers;)*)
+    });
+    Ok(quote! {
+        const _: () = {
+            #(#checks)*
+        };
+    })
+}
+
+/// Fails to compile unless every `year_YYYY_day_NN` crate in the range
+/// registers both parts and `validate` with `#[aoc]` for that year. Naming the crates here also links
+/// them, so their registrations are collected.
+#[cfg(not(tarpaulin_include))]
+#[proc_macro]
+pub fn require_days(input: TokenStream) -> TokenStream {
+    expand_require_days(input.into())
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// One `function: "file" => expected` entry of `aoc_examples!`.
+struct Example {
+    function: Ident,
+    file: LitStr,
+    expected: Expr,
+}
+
+impl Parse for Example {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let function = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let file = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let expected = input.parse()?;
+        Ok(Example {
+            function,
+            file,
+            expected,
+        })
+    }
+}
+
+fn expand_examples(input: TokenStream2) -> syn::Result<TokenStream2> {
+    let examples = Punctuated::<Example, Token![,]>::parse_terminated.parse2(input)?;
+    let tests = examples.iter().map(|example| {
+        let Example {
+            function,
+            file,
+            expected,
+        } = example;
+        let stem: String = file
+            .value()
+            .split('.')
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .map(|character| {
+                if character.is_ascii_alphanumeric() {
+                    character.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let name = format_ident!("{}_{}", function, stem, span = file.span());
+        quote! {
+            #[test]
+            fn #name() {
+                let input = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/examples/", #file));
+                assert_eq!(#expected, #function(input.to_string()));
+            }
+        }
+    });
+    Ok(quote!(#(#tests)*))
+}
+
+/// Generates a test per `function: "file" => expected` entry that runs the
+/// function on `examples/<file>` in the day's crate and checks its answer:
+///
+/// ```ignore
+/// aoc_examples! {
+///     part1: "example.txt" => 143,
+///     part2: "example.txt" => 123,
+/// }
+/// ```
+///
+/// Each test is named after the function and file, such as
+/// `part1_example`. A missing file fails to compile.
+#[cfg(not(tarpaulin_include))]
+#[proc_macro]
+pub fn aoc_examples(input: TokenStream) -> TokenStream {
+    expand_examples(input.into())
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registration(attribute: TokenStream2) -> Registration {
+        parse_registration(attribute).unwrap()
+    }
+
+    fn error(attribute: TokenStream2) -> String {
+        parse_registration(attribute).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn it_parses_registrations() {
+        assert_eq!(
+            Registration {
+                year: 2024,
+                day: 6,
+                kind: Kind::Part {
+                    part: 2,
+                    variant: Some("states".to_string()),
+                    checked: false
+                }
+            },
+            registration(quote!(year = 2024, day = 6, part = 2, variant = "states"))
+        );
+        assert_eq!(
+            Kind::Part {
+                part: 1,
+                variant: None,
+                checked: true
+            },
+            registration(quote!(year = 2024, day = 7, part = 1, checked)).kind
+        );
+        assert_eq!(
+            Kind::Parse,
+            registration(quote!(year = 2024, day = 4, parse)).kind
+        );
+        assert_eq!(
+            Kind::Validate {
+                shape: Some(format_ident!("Grid"))
+            },
+            registration(quote!(year = 2024, day = 4, validate, shape = Grid)).kind
+        );
+        assert_eq!(
+            Kind::Visualize,
+            registration(quote!(visualize, day = 10, year = 2015)).kind
+        );
+        assert_eq!(
+            Kind::Explain,
+            registration(quote!(year = 2024, day = 2, explain)).kind
+        );
+    }
+
+    #[test]
+    fn it_rejects_bad_registrations() {
+        assert_eq!("missing `year = N`", error(quote!(day = 1, part = 1)));
+        assert_eq!("missing `day = N`", error(quote!(year = 2024, part = 1)));
+        assert_eq!(
+            "year must be 2015 or later",
+            error(quote!(year = 2014, day = 1, part = 1))
+        );
+        assert_eq!(
+            "day must be from 1 to 25",
+            error(quote!(year = 2024, day = 26, part = 1))
+        );
+        assert_eq!(
+            "part must be 1 or 2",
+            error(quote!(year = 2024, day = 1, part = 3))
+        );
+        assert_eq!(
+            "expected an integer",
+            error(quote!(year = 2024, day = "1", part = 1))
+        );
+        assert_eq!(
+            "unknown `aoc` option",
+            error(quote!(year = 2024, day = 1, part = 1, fast))
+        );
+        let kinds =
+            "expected exactly one of `part = N`, `parse`, `validate`, `visualize` or `explain`";
+        assert_eq!(kinds, error(quote!(year = 2024, day = 1)));
+        assert_eq!(
+            kinds,
+            error(quote!(year = 2024, day = 1, part = 1, validate))
+        );
+        assert_eq!(kinds, error(quote!(year = 2024, day = 1, visualize, explain)));
+        assert_eq!(
+            "`checked` and `variant` need `part = N`",
+            error(quote!(year = 2024, day = 1, validate, checked))
+        );
+        assert_eq!(
+            "variants cannot be checked",
+            error(quote!(
+                year = 2024,
+                day = 1,
+                part = 1,
+                checked,
+                variant = "fast"
+            ))
+        );
+        assert_eq!(
+            "`shape` belongs on the `validate` registration",
+            error(quote!(year = 2024, day = 1, part = 1, shape = Grid))
+        );
+        for name in ["default", "Fast", "", "two words"] {
+            assert!(
+                error(quote!(year = 2024, day = 1, part = 1, variant = #name))
+                    .starts_with("variant names")
+            );
+        }
+    }
+
+    #[test]
+    fn it_expands_registrations() {
+        let expanded = expand_aoc(
+            quote!(year = 2024, day = 6, part = 2, variant = "states"),
+            quote!(
+                pub fn part2_states(input: String) -> usize {
+                    input.len()
+                }
+            ),
+        )
+        .unwrap()
+        .to_string();
+        assert!(expanded.starts_with("pub fn part2_states"));
+        assert!(expanded.contains("pub const __AOC_2024_DAY_06_PART_2_STATES : () = ()"));
+        assert!(expanded.contains("static __AOC_2024_DAY_06_PART_2_STATES_REGISTRATION"));
+        assert!(expanded.contains("year : 2024u16 , day : 6u8"));
+        assert!(expanded.contains("variant : :: core :: option :: Option :: Some (\"states\")"));
+        let validate = expand_aoc(
+            quote!(year = 2024, day = 1, validate),
+            quote!(
+                fn validate() {}
+            ),
+        )
+        .unwrap()
+        .to_string();
+        assert!(validate.contains(":: common :: registry :: Shape :: Lines"));
+        assert!(expand_aoc(
+            quote!(year = 2024, day = 1, part = 1),
+            quote!(
+                struct Part;
+            )
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn it_expands_examples() {
+        let expanded = expand_examples(quote! {
+            part1: "example.txt" => 143,
+            part2_counts: "Example-2.txt" => 31,
+        })
+        .unwrap()
+        .to_string();
+        assert!(expanded.contains("fn part1_example ()"));
+        assert!(expanded.contains("fn part2_counts_example_2 ()"));
+        assert!(expanded.contains("\"/examples/\" , \"Example-2.txt\""));
+        assert!(expanded.contains("assert_eq ! (143 , part1 (input . to_string ()))"));
+        assert!(expand_examples(quote!(part1 => 143)).is_err());
+        assert!(expand_examples(quote!()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_requires_days() {
+        let expanded = expand_require_days(quote!(2024, 1..=2))
+            .unwrap()
+            .to_string();
+        for marker in [
+            "year_2024_day_01 :: __AOC_2024_DAY_01_PART_1",
+            "year_2024_day_01 :: __AOC_2024_DAY_01_VALIDATE",
+            "year_2024_day_02 :: __AOC_2024_DAY_02_PART_2",
+        ] {
+            assert!(expanded.contains(marker));
+        }
+        assert!(!expanded.contains("day_03"));
+        assert!(expand_require_days(quote!(2024, 0..=2)).is_err());
+        assert!(expand_require_days(quote!(2024, 3..=2)).is_err());
+        assert!(expand_require_days(quote!(2024, 1..2)).is_err());
+        assert!(expand_require_days(quote!(1..=2)).is_err());
+        assert!(expand_require_days(quote!(2014, 1..=2)).is_err());
+    }
+}
@@ -0,0 +1,178 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Python bindings for the solutions, built as the `advent` extension module.
+//!
+//! Every day is a submodule with `part1` and `part2`, so Python can call
+//! `advent.day06.part2(text)`.
+
+use common::{Answer, Arithmetic};
+use pyo3::exceptions::{PyOverflowError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::IntoPyObjectExt;
+
+fn answer_into_py(py: Python<'_>, answer: Answer) -> PyResult<Py<PyAny>> {
+    match answer {
+        Answer::Unsigned(number) => number.into_py_any(py),
+        Answer::Signed(number) => number.into_py_any(py),
+        Answer::Big(number) => number.into_py_any(py),
+        Answer::Text(text) => text.into_py_any(py),
+    }
+}
+
+fn parse_arithmetic(arithmetic: &str) -> PyResult<Arithmetic> {
+    arithmetic
+        .parse()
+        .map_err(|_| PyValueError::new_err(format!("Unknown arithmetic {:?}", arithmetic)))
+}
+
+/// Adds a `dayNN` submodule exposing the crate's `part1` and `part2`.
+macro_rules! day_module {
+    ($parent:expr, $name:literal, $krate:ident) => {{
+        #[pyfunction]
+        fn part1(py: Python<'_>, input: String) -> PyResult<Py<PyAny>> {
+            answer_into_py(py, $krate::part1(input).into())
+        }
+
+        #[pyfunction]
+        fn part2(py: Python<'_>, input: String) -> PyResult<Py<PyAny>> {
+            answer_into_py(py, $krate::part2(input).into())
+        }
+
+        let module = PyModule::new($parent.py(), $name)?;
+        module.add_function(wrap_pyfunction!(part1, &module)?)?;
+        module.add_function(wrap_pyfunction!(part2, &module)?)?;
+        $parent.add_submodule(&module)?;
+        module
+    }};
+}
+
+/// Adds `part1_checked` and `part2_checked`, which take an `arithmetic` of
+/// `"u64"`, `"u128"` or `"big"` and raise `OverflowError` instead of
+/// wrapping.
+macro_rules! checked_functions {
+    ($module:expr, $krate:ident) => {{
+        #[pyfunction]
+        #[pyo3(signature = (input, arithmetic = "u64"))]
+        fn part1_checked(py: Python<'_>, input: String, arithmetic: &str) -> PyResult<Py<PyAny>> {
+            let answer = $krate::part1_checked(input, parse_arithmetic(arithmetic)?)
+                .map_err(|overflow| PyOverflowError::new_err(overflow.to_string()))?;
+            answer_into_py(py, answer)
+        }
+
+        #[pyfunction]
+        #[pyo3(signature = (input, arithmetic = "u64"))]
+        fn part2_checked(py: Python<'_>, input: String, arithmetic: &str) -> PyResult<Py<PyAny>> {
+            let answer = $krate::part2_checked(input, parse_arithmetic(arithmetic)?)
+                .map_err(|overflow| PyOverflowError::new_err(overflow.to_string()))?;
+            answer_into_py(py, answer)
+        }
+
+        $module.add_function(wrap_pyfunction!(part1_checked, &$module)?)?;
+        $module.add_function(wrap_pyfunction!(part2_checked, &$module)?)?;
+    }};
+}
+
+/// A page ordering rule from day 5: `number` must come before every page in
+/// `before`.
+#[pyclass(name = "Rule", module = "advent.day05")]
+struct Rule(year_2024_day_05::Rule);
+
+#[pymethods]
+impl Rule {
+    /// Parses a `47|53` line.
+    #[new]
+    fn new(line: &str) -> Self {
+        Self(year_2024_day_05::Rule::new_from_line(line))
+    }
+
+    #[getter]
+    fn number(&self) -> usize {
+        self.0.number()
+    }
+
+    #[getter]
+    fn before(&self) -> Vec<usize> {
+        self.0.before().to_vec()
+    }
+
+    /// Adds the page from another `47|13` line with the same number.
+    fn add_before(&mut self, line: &str) {
+        self.0.add_before_from_entry(line);
+    }
+
+    /// Adds the pages of another rule with the same number.
+    fn merge(&mut self, other: PyRef<'_, Rule>) {
+        self.0.add_before_from_rule(other.0.clone());
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Rule({}, before={:?})", self.0.number(), self.0.before())
+    }
+}
+
+/// The lab map from day 6, with the guard's starting position.
+#[pyclass(name = "Grid", module = "advent.day06")]
+struct Grid(year_2024_day_06::Grid);
+
+#[pymethods]
+impl Grid {
+    /// Raises `ValueError` listing the problems with a map the guard can't
+    /// walk, such as one they never leave.
+    #[new]
+    fn new(text: String) -> PyResult<Self> {
+        let problems = year_2024_day_06::validate(&text);
+        if !problems.is_empty() {
+            let problems: Vec<String> = problems.iter().map(ToString::to_string).collect();
+            return Err(PyValueError::new_err(problems.join("; ")));
+        }
+        Ok(Self(year_2024_day_06::Grid::new(text)))
+    }
+
+    /// The guard's starting `(x, y)`.
+    #[getter]
+    fn guard(&self) -> (i32, i32) {
+        self.0.guard()
+    }
+
+    /// How many cells the guard visits before leaving the map.
+    fn visited(&self) -> usize {
+        self.0.clone().run()
+    }
+
+    /// How many single obstacles would trap the guard in a loop.
+    fn loop_obstacles(&self) -> usize {
+        self.0.count_loop_obstacles()
+    }
+}
+
+#[pymodule]
+fn advent(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    day_module!(module, "day01", year_2024_day_01);
+    day_module!(module, "day02", year_2024_day_02);
+    day_module!(module, "day03", year_2024_day_03);
+    day_module!(module, "day04", year_2024_day_04);
+    let day05 = day_module!(module, "day05", year_2024_day_05);
+    day05.add_class::<Rule>()?;
+    let day06 = day_module!(module, "day06", year_2024_day_06);
+    day06.add_class::<Grid>()?;
+    let day07 = day_module!(module, "day07", year_2024_day_07);
+    checked_functions!(day07, year_2024_day_07);
+    day_module!(module, "day08", year_2024_day_08);
+    day_module!(module, "day09", year_2024_day_09);
+    day_module!(module, "day10", year_2024_day_10);
+    let day11 = day_module!(module, "day11", year_2024_day_11);
+    checked_functions!(day11, year_2024_day_11);
+    Ok(())
+}
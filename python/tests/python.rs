@@ -0,0 +1,55 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::env::{self, consts};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Copies the freshly built extension module to where Python can import it
+/// as `advent`.
+fn install_module() -> PathBuf {
+    let deps = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    let library = deps.parent().unwrap().join(format!(
+        "{}advent{}",
+        consts::DLL_PREFIX,
+        consts::DLL_SUFFIX
+    ));
+    let directory = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("python");
+    fs::create_dir_all(&directory).unwrap();
+    let module = if cfg!(windows) {
+        "advent.pyd"
+    } else {
+        "advent.so"
+    };
+    fs::copy(&library, directory.join(module)).unwrap();
+    directory
+}
+
+#[test]
+fn it_passes_the_python_tests() {
+    let python = env::var("PYTHON").unwrap_or_else(|_| "python3".to_string());
+    assert!(
+        Command::new(&python).arg("--version").output().is_ok(),
+        "The Python tests need Python, but {} is not installed; install it or set PYTHON",
+        python
+    );
+    let status = Command::new(&python)
+        .args(["-m", "unittest", "discover", "-v", "-s"])
+        .arg(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests"))
+        .env("PYTHONPATH", install_module())
+        .status()
+        .unwrap();
+    assert!(status.success(), "Python tests failed");
+}
@@ -0,0 +1,109 @@
+# Copyright 2024 CJ Harries
+#
+# Licensed under the Apache License, Version 2.0 (the "License");
+# you may not use this file except in compliance with the License.
+# You may obtain a copy of the License at
+#
+#     http://www.apache.org/licenses/LICENSE-2.0
+#
+# Unless required by applicable law or agreed to in writing, software
+# distributed under the License is distributed on an "AS IS" BASIS,
+# WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+# See the License for the specific language governing permissions and
+# limitations under the License.
+
+import unittest
+
+import advent
+from advent import day01, day05, day06, day07, day11
+
+DAY_01 = """3   4
+4   3
+2   5
+1   3
+3   9
+3   3
+"""
+
+DAY_06 = """....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+"""
+
+
+class TestParts(unittest.TestCase):
+    def test_every_day_has_parts(self):
+        for day in range(1, 12):
+            module = getattr(advent, f"day{day:02}")
+            self.assertTrue(callable(module.part1))
+            self.assertTrue(callable(module.part2))
+
+    def test_it_solves_day01(self):
+        self.assertEqual(11, day01.part1(DAY_01))
+        self.assertEqual(31, day01.part2(DAY_01))
+
+    def test_it_solves_day06(self):
+        self.assertEqual(41, day06.part1(DAY_06))
+        self.assertEqual(6, day06.part2(DAY_06))
+
+    def test_it_raises_panics(self):
+        with self.assertRaises(BaseException):
+            day01.part1("not numbers")
+
+
+class TestChecked(unittest.TestCase):
+    def test_it_solves_with_any_arithmetic(self):
+        self.assertEqual(55312, day11.part1_checked("125 17"))
+        self.assertEqual(55312, day11.part1_checked("125 17", "big"))
+
+    def test_it_raises_overflow(self):
+        stones = str(2**64 - 1) + "0"
+        with self.assertRaises(OverflowError):
+            day11.part1_checked(stones)
+        self.assertEqual(
+            day11.part1_checked(stones, arithmetic="u128"),
+            day11.part1_checked(stones, arithmetic="big"),
+        )
+
+    def test_it_rejects_unknown_arithmetic(self):
+        with self.assertRaises(ValueError):
+            day07.part1_checked("190: 10 19", "i8")
+
+
+class TestRule(unittest.TestCase):
+    def test_it_parses_rules(self):
+        rule = day05.Rule("47|53")
+        self.assertEqual(47, rule.number)
+        self.assertEqual([53], rule.before)
+
+    def test_it_adds_pages(self):
+        rule = day05.Rule("47|53")
+        rule.add_before("47|13")
+        rule.add_before("97|29")
+        rule.merge(day05.Rule("47|61"))
+        self.assertEqual([53, 13, 61], rule.before)
+        self.assertEqual("Rule(47, before=[53, 13, 61])", repr(rule))
+
+
+class TestGrid(unittest.TestCase):
+    def test_it_walks_the_guard(self):
+        grid = day06.Grid(DAY_06)
+        self.assertEqual((4, 6), grid.guard)
+        self.assertEqual(41, grid.visited())
+        self.assertEqual(41, grid.visited())
+        self.assertEqual(6, grid.loop_obstacles())
+
+    def test_it_rejects_a_guard_who_never_leaves(self):
+        with self.assertRaises(ValueError):
+            day06.Grid(".#.\n#^#\n.#.\n")
+
+
+if __name__ == "__main__":
+    unittest.main()
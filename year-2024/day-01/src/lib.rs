@@ -0,0 +1,122 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{aoc, check_numbers, check_text, Problem};
+use std::collections::HashMap;
+
+/// Each line needs exactly two location IDs.
+#[aoc(year = 2024, day = 1, validate)]
+pub fn validate(input: &str) -> Vec<Problem> {
+    let mut problems = check_text(input);
+    if !problems.is_empty() {
+        return problems;
+    }
+    for (index, line) in input.trim_end().lines().enumerate() {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        if 2 != columns.len() {
+            problems.push(Problem::on_line(
+                index + 1,
+                format!("expected two columns, found {}", columns.len()),
+            ));
+        }
+        problems.extend(check_numbers(index + 1, columns));
+    }
+    problems
+}
+
+#[aoc(year = 2024, day = 1, part = 1)]
+pub fn part1(input: String) -> usize {
+    let mut first = Vec::<usize>::new();
+    let mut second = Vec::<usize>::new();
+    for line in input.lines() {
+        let numbers = line
+            .split_whitespace()
+            .map(|number| number.parse::<usize>().unwrap())
+            .collect::<Vec<usize>>();
+        first.push(numbers[0]);
+        second.push(numbers[1]);
+    }
+    first.sort();
+    second.sort();
+    first
+        .iter()
+        .zip(second.iter())
+        .map(|(first, second)| first.abs_diff(*second))
+        .sum()
+}
+
+#[aoc(year = 2024, day = 1, part = 2)]
+pub fn part2(input: String) -> usize {
+    let mut first = Vec::<usize>::new();
+    let mut second = Vec::<usize>::new();
+    for line in input.lines() {
+        let numbers = line
+            .split_whitespace()
+            .map(|number| number.parse::<usize>().unwrap())
+            .collect::<Vec<usize>>();
+        first.push(numbers[0]);
+        second.push(numbers[1]);
+    }
+    first.sort();
+    second.sort();
+    first
+        .iter()
+        .map(|first| second.iter().filter(|&second| second == first).count() * first)
+        .sum()
+}
+
+/// Same as `part2`, but counts the right list once instead of scanning it for
+/// every number on the left.
+#[aoc(year = 2024, day = 1, part = 2, variant = "counts")]
+pub fn part2_counts(input: String) -> usize {
+    let mut first = Vec::<usize>::new();
+    let mut counts = HashMap::<usize, usize>::new();
+    for line in input.lines() {
+        let numbers = line
+            .split_whitespace()
+            .map(|number| number.parse::<usize>().unwrap())
+            .collect::<Vec<usize>>();
+        first.push(numbers[0]);
+        *counts.entry(numbers[1]).or_default() += 1;
+    }
+    first
+        .iter()
+        .map(|first| counts.get(first).unwrap_or(&0) * first)
+        .sum()
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::aoc_examples;
+
+    aoc_examples! {
+        part1: "example.txt" => 11,
+        part2: "example.txt" => 31,
+        part2_counts: "example.txt" => 31,
+    }
+
+    #[test]
+    fn validate_should_report_every_problem() {
+        assert!(validate("3   4\n4   3\n").is_empty());
+        assert_eq!(
+            vec![
+                Problem::on_line(2, "expected two columns, found 3"),
+                Problem::on_line(3, "\"x\" is not a number"),
+            ],
+            validate("3   4\n4   3   5\n2   x\n")
+        );
+    }
+}
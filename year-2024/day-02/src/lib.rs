@@ -0,0 +1,226 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{aoc, check_numbers, check_text, Explanation, Problem};
+
+fn parse_level_str(level: &str) -> Vec<usize> {
+    level
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .into_iter()
+        .map(|part| part.parse::<usize>().unwrap())
+        .collect::<Vec<usize>>()
+}
+
+fn is_level_safe(levels: Vec<usize>) -> bool {
+    for index in 0..levels.len() - 1 {
+        if 3 < levels[index].abs_diff(levels[index + 1]) || levels[index] == levels[index + 1] {
+            return false;
+        }
+    }
+    let mut sorted = levels.clone();
+    sorted.sort();
+    if levels[0] > levels[1] && levels[1] > levels[2] {
+        sorted.reverse();
+    }
+    levels == sorted
+}
+
+/// Why a report is unsafe, from the first pair of levels that breaks a rule.
+fn why_unsafe(levels: &[usize]) -> Option<String> {
+    let increasing = levels[0] < levels[1];
+    levels.windows(2).find_map(|pair| {
+        let (from, to) = (pair[0], pair[1]);
+        if from == to {
+            Some(format!("{} to {} doesn't change", from, to))
+        } else if 3 < from.abs_diff(to) {
+            Some(format!(
+                "{} to {} changes by {}",
+                from,
+                to,
+                from.abs_diff(to)
+            ))
+        } else if increasing != (from < to) {
+            Some(format!(
+                "{} to {} {} after {}",
+                from,
+                to,
+                if increasing { "decreases" } else { "increases" },
+                if increasing {
+                    "increasing"
+                } else {
+                    "decreasing"
+                }
+            ))
+        } else {
+            None
+        }
+    })
+}
+
+/// Each report needs at least three levels to tell which way it is heading.
+#[aoc(year = 2024, day = 2, validate, shape = Tokens)]
+pub fn validate(input: &str) -> Vec<Problem> {
+    let mut problems = check_text(input);
+    if !problems.is_empty() {
+        return problems;
+    }
+    for (index, line) in input.trim_end().lines().enumerate() {
+        let levels: Vec<&str> = line.split_whitespace().collect();
+        if levels.len() < 3 {
+            problems.push(Problem::on_line(
+                index + 1,
+                format!("expected at least three levels, found {}", levels.len()),
+            ));
+        }
+        problems.extend(check_numbers(index + 1, levels));
+    }
+    problems
+}
+
+#[aoc(year = 2024, day = 2, part = 1)]
+pub fn part1(input: String) -> usize {
+    input
+        .lines()
+        .filter(|line| is_level_safe(parse_level_str(line)))
+        .count()
+}
+
+#[aoc(year = 2024, day = 2, part = 2)]
+pub fn part2(input: String) -> usize {
+    input
+        .lines()
+        .filter(|line| {
+            let levels = parse_level_str(line);
+            if is_level_safe(levels.clone()) {
+                true
+            } else {
+                let mut safe = false;
+                for index in 0..levels.len() {
+                    let mut removed = levels[0..index].to_vec();
+                    removed.extend(levels[index + 1..].to_vec());
+                    if is_level_safe(removed) {
+                        safe = true;
+                        break;
+                    }
+                }
+                safe
+            }
+        })
+        .count()
+}
+
+/// Part 1 lists the unsafe reports and the first step that breaks a rule;
+/// part 2 adds which level, if any, the dampener removes to make them safe.
+#[aoc(year = 2024, day = 2, explain)]
+pub fn explain(input: &str, part: u8, explanation: &mut Explanation) {
+    for (index, line) in input.lines().enumerate() {
+        let levels = parse_level_str(line);
+        let Some(reason) = why_unsafe(&levels) else {
+            continue;
+        };
+        let report = format!("Report {} ({})", index + 1, line.trim());
+        if 1 == part {
+            explanation.line(format_args!("{} is unsafe: {}", report, reason));
+            continue;
+        }
+        let removal = (0..levels.len()).find(|&removed| {
+            let mut remaining = levels.clone();
+            remaining.remove(removed);
+            is_level_safe(remaining)
+        });
+        match removal {
+            Some(removed) => explanation.line(format_args!(
+                "{} is unsafe ({}), but safe without level {} ({})",
+                report,
+                reason,
+                removed + 1,
+                levels[removed]
+            )),
+            None => explanation.line(format_args!(
+                "{} is unsafe ({}) whichever level is removed",
+                report, reason
+            )),
+        }
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::aoc_examples;
+
+    #[test]
+    fn test_is_level_safe() {
+        assert!(is_level_safe(parse_level_str("7 6 4 2 1")));
+        assert!(!is_level_safe(parse_level_str("1 2 7 8 9")));
+        assert!(!is_level_safe(parse_level_str("9 7 6 2 1")));
+        assert!(!is_level_safe(parse_level_str("1 3 2 4 5")));
+        assert!(!is_level_safe(parse_level_str("8 6 4 4 1")));
+        assert!(is_level_safe(parse_level_str("1 3 6 7 9")));
+    }
+
+    aoc_examples! {
+        part1: "example.txt" => 2,
+        part2: "example.txt" => 4,
+    }
+
+    #[test]
+    fn test_part2() {
+        assert!(part2("7 6 4 2 1".to_string()) > 0);
+        assert!(part2("1 2 7 8 9".to_string()) == 0);
+        assert!(part2("9 7 6 2 1".to_string()) == 0);
+        assert!(part2("1 3 2 4 5".to_string()) > 0);
+        assert!(part2("8 6 4 4 1".to_string()) > 0);
+        assert!(part2("1 3 6 7 9".to_string()) > 0);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate("7 6 4 2 1\n1 2 7 8 9\n").is_empty());
+        assert_eq!(
+            vec![
+                Problem::on_line(1, "expected at least three levels, found 2"),
+                Problem::on_line(2, "\"-9\" is not a number"),
+            ],
+            validate("7 6\n1 2 -9\n")
+        );
+    }
+
+    #[test]
+    fn test_explain() {
+        let input = "7 6 4 2 1\n1 2 7 8 9\n1 3 2 4 5\n8 6 4 4 1\n";
+        let mut explanation = Explanation::new(10);
+        explain(input, 1, &mut explanation);
+        assert_eq!(
+            [
+                "Report 2 (1 2 7 8 9) is unsafe: 2 to 7 changes by 5",
+                "Report 3 (1 3 2 4 5) is unsafe: 3 to 2 decreases after increasing",
+                "Report 4 (8 6 4 4 1) is unsafe: 4 to 4 doesn't change",
+            ],
+            explanation.lines()
+        );
+        let mut explanation = Explanation::new(10);
+        explain(input, 2, &mut explanation);
+        assert_eq!(
+            [
+                "Report 2 (1 2 7 8 9) is unsafe (2 to 7 changes by 5) whichever level is removed",
+                "Report 3 (1 3 2 4 5) is unsafe (3 to 2 decreases after increasing), but safe without level 2 (3)",
+                "Report 4 (8 6 4 4 1) is unsafe (4 to 4 doesn't change), but safe without level 3 (4)",
+            ],
+            explanation.lines()
+        );
+    }
+}
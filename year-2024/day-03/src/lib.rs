@@ -0,0 +1,141 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{aoc, check_text, Explanation, Problem};
+use regex::Regex;
+
+/// Corrupted memory can hold anything, so only unusable input is rejected.
+#[aoc(year = 2024, day = 3, validate, shape = Characters)]
+pub fn validate(input: &str) -> Vec<Problem> {
+    check_text(input)
+}
+
+#[aoc(year = 2024, day = 3, part = 1)]
+pub fn part1(input: String) -> usize {
+    let mul_pattern = Regex::new(r"mul\((?<first>\d{1,3}),(?<second>\d{1,3})\)").unwrap();
+    input
+        .lines()
+        .map(|line| {
+            mul_pattern
+                .captures_iter(line)
+                .map(|capture| {
+                    let first = capture
+                        .name("first")
+                        .unwrap()
+                        .as_str()
+                        .parse::<usize>()
+                        .unwrap();
+                    let second = capture
+                        .name("second")
+                        .unwrap()
+                        .as_str()
+                        .parse::<usize>()
+                        .unwrap();
+                    first * second
+                })
+                .sum::<usize>()
+        })
+        .sum()
+}
+
+#[aoc(year = 2024, day = 3, part = 2)]
+pub fn part2(input: String) -> usize {
+    let remove_pattern = Regex::new(r"don't\(\).*?do\(\)|don't\(\).*$").unwrap();
+    let single_line = input.replace("\n", "");
+    let cleaned = remove_pattern.replace_all(&single_line, "");
+    part1(cleaned.to_string())
+}
+
+/// Lists each `mul` instruction and its product, and for part 2 the `do()`
+/// and `don't()` instructions and which `mul` instructions they disable.
+#[aoc(year = 2024, day = 3, explain)]
+pub fn explain(input: &str, part: u8, explanation: &mut Explanation) {
+    let instruction_pattern =
+        Regex::new(r"mul\((?<first>\d{1,3}),(?<second>\d{1,3})\)|do\(\)|don't\(\)").unwrap();
+    // Part 1 reads each line on its own, part 2 reads the lines joined.
+    let memory = match part {
+        1 => input.lines().map(String::from).collect::<Vec<String>>(),
+        _ => vec![input.replace("\n", "")],
+    };
+    let mut enabled = true;
+    for line in memory {
+        for capture in instruction_pattern.captures_iter(&line) {
+            let instruction = capture.get(0).unwrap().as_str();
+            match instruction {
+                "do()" if 2 == part => {
+                    enabled = true;
+                    explanation.line("do() enables what follows");
+                }
+                "don't()" if 2 == part => {
+                    enabled = false;
+                    explanation.line("don't() disables what follows");
+                }
+                "do()" | "don't()" => {}
+                _ if enabled => {
+                    let first = capture["first"].parse::<usize>().unwrap();
+                    let second = capture["second"].parse::<usize>().unwrap();
+                    explanation.line(format_args!("{} = {}", instruction, first * second));
+                }
+                _ => explanation.line(format_args!("{} is disabled", instruction)),
+            }
+        }
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::aoc_examples;
+
+    aoc_examples! {
+        part1: "example.txt" => 161,
+        part2: "example2.txt" => 48,
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate("xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)").is_empty());
+        assert_eq!(1, validate("<!DOCTYPE html>\n<html lang=\"en-us\">").len());
+    }
+
+    #[test]
+    fn test_explain() {
+        let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        let mut explanation = Explanation::new(10);
+        explain(input, 1, &mut explanation);
+        assert_eq!(
+            [
+                "mul(2,4) = 8",
+                "mul(5,5) = 25",
+                "mul(11,8) = 88",
+                "mul(8,5) = 40"
+            ],
+            explanation.lines()
+        );
+        let mut explanation = Explanation::new(10);
+        explain(input, 2, &mut explanation);
+        assert_eq!(
+            [
+                "mul(2,4) = 8",
+                "don't() disables what follows",
+                "mul(5,5) is disabled",
+                "mul(11,8) is disabled",
+                "do() enables what follows",
+                "mul(8,5) = 40",
+            ],
+            explanation.lines()
+        );
+    }
+}
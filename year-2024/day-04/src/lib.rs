@@ -12,16 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::fs::read_to_string;
+use common::{aoc, check_grid, Problem};
 
-#[cfg(not(tarpaulin_include))]
-fn main() {
-    let input = read_to_string("input.txt").expect("Unable to read input file");
-    println!("Part 1: {}", part1(input.clone()));
-    println!("Part 2: {}", part2(input));
-}
-
-fn convert_input_to_2d_array(input: String) -> Vec<Vec<char>> {
+#[aoc(year = 2024, day = 4, parse)]
+pub fn convert_input_to_2d_array(input: String) -> Vec<Vec<char>> {
     input
         .trim()
         .split("\n")
@@ -29,7 +23,14 @@ fn convert_input_to_2d_array(input: String) -> Vec<Vec<char>> {
         .collect()
 }
 
-fn part1(input: String) -> usize {
+/// The word search is a rectangular grid of the letters in `XMAS`.
+#[aoc(year = 2024, day = 4, validate, shape = Grid)]
+pub fn validate(input: &str) -> Vec<Problem> {
+    check_grid(input, |character| "XMAS".contains(character))
+}
+
+#[aoc(year = 2024, day = 4, part = 1)]
+pub fn part1(input: String) -> usize {
     let grid = convert_input_to_2d_array(input);
     let mut count = 0;
     for row in 0..grid.len() {
@@ -85,7 +86,46 @@ fn part1(input: String) -> usize {
     count
 }
 
-fn part2(input: String) -> usize {
+/// Redraws the word search with every letter outside an `XMAS` replaced by
+/// `.`, like the puzzle's own example.
+#[aoc(year = 2024, day = 4, visualize)]
+pub fn visualize(input: &str) -> String {
+    let grid = convert_input_to_2d_array(input.to_string());
+    let mut kept = vec![vec!['.'; grid[0].len()]; grid.len()];
+    let at = |row: i32, column: i32| {
+        let row = usize::try_from(row).ok()?;
+        let column = usize::try_from(column).ok()?;
+        grid.get(row)?.get(column).copied()
+    };
+    for row in 0..grid.len() as i32 {
+        for column in 0..grid[0].len() as i32 {
+            for (row_offset, column_offset) in (-1..=1)
+                .flat_map(|row_offset| {
+                    (-1..=1).map(move |column_offset| (row_offset, column_offset))
+                })
+                .filter(|offsets| (0, 0) != *offsets)
+            {
+                let cells =
+                    (0..4).map(|step| (row + step * row_offset, column + step * column_offset));
+                if cells
+                    .clone()
+                    .map(|(row, column)| at(row, column))
+                    .eq("XMAS".chars().map(Some))
+                {
+                    for (row, column) in cells {
+                        kept[row as usize][column as usize] = grid[row as usize][column as usize];
+                    }
+                }
+            }
+        }
+    }
+    kept.iter()
+        .map(|row| row.iter().collect::<String>() + "\n")
+        .collect()
+}
+
+#[aoc(year = 2024, day = 4, part = 2)]
+pub fn part2(input: String) -> usize {
     let grid = convert_input_to_2d_array(input);
     let mut count = 0;
     for row in 1..grid.len() - 1 {
@@ -129,6 +169,7 @@ fn part2(input: String) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::aoc_examples;
 
     #[test]
     fn ensure_array_conversion_works() {
@@ -150,43 +191,38 @@ XXAMMXXAMA"
         assert_eq!(expected, convert_input_to_2d_array(input));
     }
 
+    aoc_examples! {
+        part1: "example.txt" => 18,
+        part2: "example.txt" => 9,
+    }
+
     #[test]
-    fn test_part1() {
+    fn test_visualize() {
         assert_eq!(
-            18,
-            part1(
-                "MMMSXXMASM
-MSAMXMSMSA
-AMXSXMAAMM
-MSAMASMSMX
-XMASAMXAMM
-XXAMMXXAMA
-SMSMSASXSS
-SAXAMASAAA
-MAMMMXMMMM
-MXMXAXMASX"
-                    .to_string()
-            )
+            "....XXMAS.
+.SAMXMS...
+...S..A...
+..A.A.MS.X
+XMASAMX.MM
+X.....XA.A
+S.S.S.S.SS
+.A.A.A.A.A
+..M.M.M.MM
+.X.X.XMASX
+",
+            visualize(include_str!("../examples/example.txt"))
         )
     }
 
     #[test]
-    fn test_part2() {
+    fn test_validate() {
+        assert!(validate("XMAS\nSAMX\n").is_empty());
         assert_eq!(
-            9,
-            part2(
-                "MMMSXXMASM
-MSAMXMSMSA
-AMXSXMAAMM
-MSAMASMSMX
-XMASAMXAMM
-XXAMMXXAMA
-SMSMSASXSS
-SAXAMASAAA
-MAMMMXMMMM
-MXMXAXMASX"
-                    .to_string()
-            )
-        )
+            vec![
+                Problem::on_line(2, "row has 3 cells, expected 4"),
+                Problem::on_line(2, "unexpected 'Q' in column 2"),
+            ],
+            validate("XMAS\nSQM\n")
+        );
     }
 }
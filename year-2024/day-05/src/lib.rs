@@ -0,0 +1,336 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{aoc, check_numbers, check_text, Explanation, Problem};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Rule {
+    number: usize,
+    before: Vec<usize>,
+}
+
+impl Rule {
+    pub fn new_from_line(line: &str) -> Self {
+        let mut parts = line.split("|");
+        let number: usize = parts.next().unwrap().parse().unwrap();
+        let before_entry: usize = parts.next().unwrap().parse().unwrap();
+        Rule {
+            number,
+            before: vec![before_entry],
+        }
+    }
+
+    pub fn add_before_from_entry(&mut self, entry: &str) {
+        let mut parts = entry.split("|");
+        let number: usize = parts.next().unwrap().parse().unwrap();
+        if number != self.number {
+            return;
+        }
+        let before_entry: usize = parts.next().unwrap().parse().unwrap();
+        self.before.push(before_entry);
+    }
+
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    pub fn before(&self) -> &[usize] {
+        &self.before
+    }
+
+    pub fn add_before_from_rule(&mut self, rule: Rule) {
+        if rule.number != self.number {
+            return;
+        }
+        self.before.extend(rule.before.iter());
+    }
+}
+
+/// Rules (`47|53`) and updates (`75,47,61`) are two sections split by a
+/// blank line.
+#[aoc(year = 2024, day = 5, validate, shape = Sections)]
+pub fn validate(input: &str) -> Vec<Problem> {
+    let mut problems = check_text(input);
+    if !problems.is_empty() {
+        return problems;
+    }
+    let input = input.trim();
+    let Some((rules, updates)) = input.split_once("\n\n") else {
+        return vec![Problem::new(
+            "expected rules and updates separated by a blank line",
+        )];
+    };
+    let rule_count = rules.lines().count();
+    for (index, line) in rules.lines().enumerate() {
+        let pages: Vec<&str> = line.split('|').collect();
+        if 2 != pages.len() {
+            problems.push(Problem::on_line(index + 1, "expected a rule like 47|53"));
+        }
+        problems.extend(check_numbers(index + 1, pages));
+    }
+    for (index, line) in updates.lines().enumerate() {
+        problems.extend(check_numbers(rule_count + index + 2, line.split(',')));
+    }
+    problems
+}
+
+/// Merges the `47|53` lines of the first section into one `Rule` per page.
+fn parse_rules(section: &str) -> HashMap<usize, Rule> {
+    let mut rules: HashMap<usize, Rule> = HashMap::new();
+    for rule_line in section.lines() {
+        let mut rule = Rule::new_from_line(rule_line);
+        if let Some(existing_rule) = rules.get(&rule.number) {
+            rule.add_before_from_rule(existing_rule.clone());
+        }
+        rules.insert(rule.number, rule);
+    }
+    rules
+}
+
+fn parse_update(line: &str) -> Vec<usize> {
+    line.split(",").map(|x| x.parse().unwrap()).collect()
+}
+
+/// Every pair of pages in `sequence`, earlier first, without a rule putting
+/// them in that order. An update is in order only when there are none.
+fn unordered_pairs(rules: &HashMap<usize, Rule>, sequence: &[usize]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    for (i, current) in sequence.iter().enumerate() {
+        for next in &sequence[i + 1..] {
+            if !rules
+                .get(current)
+                .is_some_and(|rule| rule.before.contains(next))
+            {
+                pairs.push((*current, *next));
+            }
+        }
+    }
+    pairs
+}
+
+/// Sorts `sequence` so each page comes before the pages its rule lists.
+fn reorder(rules: &HashMap<usize, Rule>, sequence: &[usize]) -> Vec<usize> {
+    let mut ordered = sequence.to_vec();
+    ordered.sort_by(|a, b| {
+        if rules.get(a).is_some_and(|rule| rule.before.contains(b)) {
+            return Ordering::Less;
+        }
+        Ordering::Greater
+    });
+    ordered
+}
+
+#[aoc(year = 2024, day = 5, part = 1)]
+pub fn part1(input: String) -> usize {
+    let (rules_section, messages) = input.trim().split_once("\n\n").unwrap();
+    let rules = parse_rules(rules_section);
+    let mut count = 0;
+    for message in messages.lines() {
+        let sequence = parse_update(message);
+        if unordered_pairs(&rules, &sequence).is_empty() {
+            count += sequence[sequence.len() / 2];
+        }
+    }
+    count
+}
+
+#[aoc(year = 2024, day = 5, part = 2)]
+pub fn part2(input: String) -> usize {
+    let (rules_section, messages) = input.trim().split_once("\n\n").unwrap();
+    let rules = parse_rules(rules_section);
+    let mut count = 0;
+    for message in messages.lines() {
+        let sequence = parse_update(message);
+        if !unordered_pairs(&rules, &sequence).is_empty() {
+            let ordered = reorder(&rules, &sequence);
+            count += ordered[ordered.len() / 2];
+        }
+    }
+    count
+}
+
+/// Lists the updates, the rules each one breaks or lacks and its middle
+/// page; part 1 takes the middle of updates already in order, part 2 the
+/// middle once the rest are put in order.
+#[aoc(year = 2024, day = 5, explain)]
+pub fn explain(input: &str, part: u8, explanation: &mut Explanation) {
+    let (rules_section, updates) = input.trim().split_once("\n\n").unwrap();
+    let rules = parse_rules(rules_section);
+    for (index, line) in updates.lines().enumerate() {
+        let pages = parse_update(line);
+        let pairs = unordered_pairs(&rules, &pages);
+        let (broken, missing): (Vec<_>, Vec<_>) = pairs.iter().partition(|(earlier, later)| {
+            rules
+                .get(later)
+                .is_some_and(|rule| rule.before.contains(earlier))
+        });
+        let mut reasons = Vec::new();
+        if !broken.is_empty() {
+            let broken: Vec<String> = broken
+                .iter()
+                .map(|(earlier, later)| format!("{}|{}", later, earlier))
+                .collect();
+            reasons.push(format!("breaks {}", broken.join(", ")));
+        }
+        if !missing.is_empty() {
+            let missing: Vec<String> = missing
+                .iter()
+                .map(|(earlier, later)| format!("{}|{}", earlier, later))
+                .collect();
+            reasons.push(format!("lacks {}", missing.join(", ")));
+        }
+        let update = format!("Update {} ({})", index + 1, line);
+        match (part, pairs.is_empty()) {
+            (1, true) => explanation.line(format_args!(
+                "{} is in order; its middle page is {}",
+                update,
+                pages[pages.len() / 2]
+            )),
+            (1, false) => explanation.line(format_args!("{} {}", update, reasons.join(" and "))),
+            (_, true) => {}
+            (_, false) => {
+                let ordered = reorder(&rules, &pages);
+                explanation.line(format_args!(
+                    "{} {}; in order it is {} and its middle page is {}",
+                    update,
+                    reasons.join(" and "),
+                    ordered
+                        .iter()
+                        .map(usize::to_string)
+                        .collect::<Vec<String>>()
+                        .join(","),
+                    ordered[ordered.len() / 2]
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::aoc_examples;
+
+    #[test]
+    fn test_rule_new_from_line() {
+        let rule = Rule::new_from_line("47|53");
+        assert_eq!(47, rule.number);
+        assert_eq!(vec![53], rule.before);
+    }
+
+    #[test]
+    fn test_rule_accessors() {
+        let rule = Rule::new_from_line("47|53");
+        assert_eq!(47, rule.number());
+        assert_eq!(&[53], rule.before());
+    }
+
+    #[test]
+    fn test_rule_add_before() {
+        let mut rule = Rule::new_from_line("47|53");
+        rule.add_before_from_entry("97|13");
+        assert_eq!(vec![53], rule.before);
+        rule.add_before_from_entry("47|13");
+        assert_eq!(vec![53, 13], rule.before);
+    }
+
+    #[test]
+    fn test_rule_add_rule() {
+        let mut rule = Rule::new_from_line("47|53");
+        let other_rule = Rule::new_from_line("47|13");
+        rule.add_before_from_rule(other_rule);
+        assert_eq!(vec![53, 13], rule.before);
+        let other_rule = Rule::new_from_line("53|29");
+        rule.add_before_from_rule(other_rule);
+        assert_eq!(vec![53, 13], rule.before);
+    }
+
+    aoc_examples! {
+        part1: "example.txt" => 143,
+        part2: "example.txt" => 123,
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate("47|53\n97|13\n\n75,47,61\n").is_empty());
+        assert_eq!(
+            vec![Problem::new(
+                "expected rules and updates separated by a blank line"
+            )],
+            validate("47|53\n75,47,61\n")
+        );
+        assert_eq!(
+            vec![
+                Problem::on_line(1, "expected a rule like 47|53"),
+                Problem::on_line(4, "\"\" is not a number"),
+            ],
+            validate("47\n97|13\n\n75,,61\n")
+        );
+    }
+
+    #[test]
+    fn test_explain() {
+        let input = "47|53\n97|75\n75|47\n75|53\n97|47\n\n75,47,53\n75,97,47\n47,75,53\n";
+        let mut explanation = Explanation::new(10);
+        explain(input, 1, &mut explanation);
+        assert_eq!(
+            [
+                "Update 1 (75,47,53) is in order; its middle page is 47",
+                "Update 2 (75,97,47) breaks 97|75",
+                "Update 3 (47,75,53) breaks 75|47",
+            ],
+            explanation.lines()
+        );
+        let mut explanation = Explanation::new(10);
+        explain(input, 2, &mut explanation);
+        assert_eq!(
+            [
+                "Update 2 (75,97,47) breaks 97|75; in order it is 97,75,47 and its middle page is 75",
+                "Update 3 (47,75,53) breaks 75|47; in order it is 75,47,53 and its middle page is 47",
+            ],
+            explanation.lines()
+        );
+    }
+
+    #[test]
+    fn test_explain_without_rules() {
+        let input = "47|53\n97|75\n75|47\n75|53\n\n75,47,53\n47,12\n75,12,47\n53,47\n";
+        let middles = |part: u8| {
+            let mut explanation = Explanation::new(10);
+            explain(input, part, &mut explanation);
+            explanation
+                .lines()
+                .iter()
+                .filter_map(|line| line.rsplit_once("its middle page is "))
+                .map(|(_, page)| page.parse::<usize>().unwrap())
+                .sum::<usize>()
+        };
+        assert_eq!(part1(input.to_string()), middles(1));
+        assert_eq!(part2(input.to_string()), middles(2));
+        let mut explanation = Explanation::new(10);
+        explain(input, 1, &mut explanation);
+        assert_eq!(
+            [
+                "Update 1 (75,47,53) is in order; its middle page is 47",
+                "Update 2 (47,12) lacks 47|12",
+                "Update 3 (75,12,47) lacks 75|12, 12|47",
+                "Update 4 (53,47) breaks 47|53",
+            ],
+            explanation.lines()
+        );
+    }
+}
@@ -12,15 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{HashMap, HashSet};
-use std::fs::read_to_string;
-
-#[cfg(not(tarpaulin_include))]
-fn main() {
-    let input = read_to_string("input.txt").expect("Unable to read input file");
-    println!("Part 1: {}", part1(input.clone()));
-    println!("Part 2: {}", part2(input));
-}
+use common::{aoc, check_grid, Problem};
+use std::collections::HashSet;
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 enum Direction {
@@ -69,7 +62,7 @@ enum GridContent {
 }
 
 #[derive(Debug, Clone)]
-struct Grid {
+pub struct Grid {
     contents: Vec<Vec<GridContent>>,
     current_position: Coordinate,
     visited: HashSet<Coordinate>,
@@ -77,7 +70,7 @@ struct Grid {
 }
 
 impl Grid {
-    fn new(input: String) -> Self {
+    pub fn new(input: String) -> Self {
         let input = input.trim();
         let mut contents = Vec::new();
         let mut current_position = Coordinate::new(0, 0);
@@ -105,7 +98,12 @@ impl Grid {
         }
     }
 
-    fn run(&mut self) -> usize {
+    /// The guard's starting column and row.
+    pub fn guard(&self) -> (i32, i32) {
+        (self.current_position.x, self.current_position.y)
+    }
+
+    pub fn run(&mut self) -> usize {
         let mut direction = Direction::North;
         loop {
             let next_position = direction.walk(&self.current_position);
@@ -126,10 +124,10 @@ impl Grid {
                 }
             }
         }
-        return self.visited.len();
+        self.visited.len()
     }
 
-    fn run_part2(&mut self) -> usize {
+    pub fn run_part2(&mut self) -> usize {
         let mut direction = Direction::North;
         let mut new_obstacles: HashSet<Coordinate> = HashSet::new();
         let possible_obstacle_grid = self.clone();
@@ -197,22 +195,120 @@ impl Grid {
         }
         new_obstacles.len() - 1
     }
+
+    fn content_at(&self, coordinate: &Coordinate) -> Option<&GridContent> {
+        let x = usize::try_from(coordinate.x).ok()?;
+        let y = usize::try_from(coordinate.y).ok()?;
+        self.contents.get(y)?.get(x)
+    }
+
+    /// Walks the guard until they leave the grid, returning every cell they
+    /// pass in the order they first reach it, or `None` if they loop.
+    fn walk(&self) -> Option<Vec<Coordinate>> {
+        let mut position = self.current_position.clone();
+        let mut direction = Direction::North;
+        let mut states = HashSet::new();
+        let mut cells = Vec::new();
+        let mut seen = HashSet::new();
+        loop {
+            if !states.insert((position.clone(), direction.clone())) {
+                return None;
+            }
+            if seen.insert(position.clone()) {
+                cells.push(position.clone());
+            }
+            let next_position = direction.walk(&position);
+            match self.content_at(&next_position) {
+                None => return Some(cells),
+                Some(GridContent::Empty) => position = next_position,
+                Some(GridContent::Wall) => direction = direction.turn_right(),
+            }
+        }
+    }
+
+    /// Tries an obstacle on each cell of the guard's path and counts those
+    /// that trap them, detecting loops by a repeated position and heading.
+    ///
+    /// Panics if the guard loops without one, which `validate` rejects.
+    pub fn count_loop_obstacles(&self) -> usize {
+        let path = self.walk().expect("The guard loops without an obstacle");
+        path.iter()
+            .filter(|&cell| *cell != self.current_position)
+            .filter(|cell| {
+                let mut grid = self.clone();
+                grid.contents[cell.y as usize][cell.x as usize] = GridContent::Wall;
+                grid.walk().is_none()
+            })
+            .count()
+    }
+}
+
+#[aoc(year = 2024, day = 6, parse)]
+pub fn parse(input: String) -> Grid {
+    Grid::new(input)
+}
+
+/// The lab is a rectangular grid of `.` and `#` with exactly one guard `^`,
+/// who walks out of it.
+#[aoc(year = 2024, day = 6, validate, shape = Grid)]
+pub fn validate(input: &str) -> Vec<Problem> {
+    let mut problems = check_grid(input, |character| ".#^".contains(character));
+    let guards = input.matches('^').count();
+    if 1 != guards {
+        problems.push(Problem::new(format!(
+            "expected exactly one guard '^', found {}",
+            guards
+        )));
+    }
+    if problems.is_empty() && Grid::new(input.to_string()).walk().is_none() {
+        problems.push(Problem::new("the guard loops without leaving the lab"));
+    }
+    problems
 }
 
-fn part1(input: String) -> usize {
+#[aoc(year = 2024, day = 6, part = 1)]
+pub fn part1(input: String) -> usize {
     let mut grid = Grid::new(input);
     grid.run()
 }
 
-fn part2(input: String) -> usize {
+#[aoc(year = 2024, day = 6, part = 2)]
+pub fn part2(input: String) -> usize {
     let mut grid = Grid::new(input);
     grid.run_part2()
 }
 
+/// Redraws the lab with every cell the guard visits marked `X`, like the
+/// puzzle's own example.
+#[aoc(year = 2024, day = 6, visualize)]
+pub fn visualize(input: &str) -> String {
+    let grid = Grid::new(input.to_string());
+    let path: HashSet<Coordinate> = grid.walk().unwrap_or_default().into_iter().collect();
+    let mut picture = String::new();
+    for (y, row) in grid.contents.iter().enumerate() {
+        for (x, content) in row.iter().enumerate() {
+            picture.push(match content {
+                GridContent::Wall => '#',
+                GridContent::Empty if path.contains(&Coordinate::new(x as i32, y as i32)) => 'X',
+                GridContent::Empty => '.',
+            });
+        }
+        picture.push('\n');
+    }
+    picture
+}
+
+/// Same as `part2`, but checks for loops by repeated guard states.
+#[aoc(year = 2024, day = 6, part = 2, variant = "states")]
+pub fn part2_states(input: String) -> usize {
+    Grid::new(input).count_loop_obstacles()
+}
+
 #[cfg(not(tarpaulin_include))]
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::aoc_examples;
 
     #[test]
     fn it_turns_right() {
@@ -237,42 +333,61 @@ mod tests {
     }
 
     #[test]
-    fn it_solves_part1() {
+    fn it_finds_the_guard() {
+        assert_eq!((1, 2), Grid::new("...\n...\n.^.".to_string()).guard());
+    }
+
+    aoc_examples! {
+        part1: "example.txt" => 41,
+        part2: "example.txt" => 6,
+        part2_states: "example.txt" => 6,
+    }
+
+    #[test]
+    fn it_walks_out_of_grids() {
+        let grid = Grid::new("..\n^.".to_string());
+        assert_eq!(
+            Some(vec![Coordinate::new(0, 1), Coordinate::new(0, 0)]),
+            grid.walk()
+        );
+        assert_eq!(None, Grid::new(".#.\n#^#\n.#.".to_string()).walk());
+    }
+
+    #[test]
+    fn it_visualizes_the_path() {
         assert_eq!(
-            41,
-            part1(
-                "....#.....
-.........#
-..........
-..#.......
-.......#..
-..........
-.#..^.....
-........#.
-#.........
-......#..."
-                    .to_string()
-            )
-        )
+            "....#.....
+....XXXXX#
+....X...X.
+..#.X...X.
+..XXXXX#X.
+..X.X.X.X.
+.#XXXXXXX.
+.XXXXXXX#.
+#XXXXXXX..
+......#X..
+",
+            visualize(include_str!("../examples/example.txt"))
+        );
     }
 
     #[test]
-    fn it_solves_part2() {
+    fn it_validates_input() {
+        assert!(validate("..#\n.^.\n").is_empty());
+        assert_eq!(
+            vec![Problem::new("expected exactly one guard '^', found 0")],
+            validate("..#\n...\n")
+        );
+        assert_eq!(
+            vec![
+                Problem::on_line(2, "row has 2 cells, expected 3"),
+                Problem::new("expected exactly one guard '^', found 2"),
+            ],
+            validate("^.#\n.^\n")
+        );
         assert_eq!(
-            6,
-            part2(
-                "....#.....
-.........#
-..........
-..#.......
-.......#..
-..........
-.#..^.....
-........#.
-#.........
-......#..."
-                    .to_string()
-            )
-        )
+            vec![Problem::new("the guard loops without leaving the lab")],
+            validate(".#.\n#^#\n.#.\n")
+        );
     }
 }
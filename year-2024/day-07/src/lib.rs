@@ -0,0 +1,317 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    aoc, check_numbers, check_text, parse_number, with_arithmetic, Answer, Arithmetic, Explanation,
+    Number, Overflow, Problem,
+};
+
+type Operator<N> = fn(&N, &N) -> Result<N, Overflow>;
+
+fn join_two_numbers<N: Number>(first: &N, second: &N) -> Result<N, Overflow> {
+    if first.is_zero() {
+        return Ok(second.clone());
+    }
+    let ten = N::from(10);
+    let mut shift = ten.clone();
+    while shift <= *second {
+        shift = shift.try_mul(&ten)?;
+    }
+    first.try_mul(&shift)?.try_add(second)
+}
+
+/// Every operator only grows its operands unless a zero is still to come, so
+/// a branch that overflows can't reach `solution` and is dropped. With a zero
+/// ahead the overflow is reported instead.
+fn solve_equation<N: Number>(
+    solution: &N,
+    numbers: Vec<N>,
+    enable_join: bool,
+) -> Result<bool, Overflow> {
+    let mut operators: Vec<Operator<N>> = vec![N::try_add, N::try_mul];
+    if enable_join {
+        operators.push(join_two_numbers);
+    }
+    let can_shrink = numbers[2..].iter().any(|number| number.is_zero());
+    for operator in operators {
+        let combined = match operator(&numbers[0], &numbers[1]) {
+            Ok(combined) => combined,
+            Err(Overflow) if !can_shrink => continue,
+            Err(overflow) => return Err(overflow),
+        };
+        if 2 == numbers.len() {
+            if *solution == combined {
+                return Ok(true);
+            }
+            continue;
+        }
+        let mut combined_numbers = vec![combined];
+        combined_numbers.extend_from_slice(&numbers[2..]);
+        if solve_equation(solution, combined_numbers, enable_join)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// The first operators, left to right, that turn `current` and the `rest`
+/// of the numbers into `solution`, trying them in the order `solve_equation`
+/// does. Branches that overflow are dropped.
+fn find_operators<N: Number>(
+    solution: &N,
+    current: N,
+    rest: &[N],
+    enable_join: bool,
+) -> Option<Vec<&'static str>> {
+    let Some((next, rest)) = rest.split_first() else {
+        return (*solution == current).then(Vec::new);
+    };
+    let mut operators: Vec<(&'static str, Operator<N>)> =
+        vec![("+", N::try_add), ("*", N::try_mul)];
+    if enable_join {
+        operators.push(("||", join_two_numbers));
+    }
+    for (symbol, operator) in operators {
+        let Ok(combined) = operator(&current, next) else {
+            continue;
+        };
+        if let Some(mut found) = find_operators(solution, combined, rest, enable_join) {
+            found.insert(0, symbol);
+            return Some(found);
+        }
+    }
+    None
+}
+
+pub fn total_calibration<N: Number>(input: &str, enable_join: bool) -> Result<N, Overflow> {
+    let input = input.trim();
+    let mut total = N::zero();
+    for line in input.lines() {
+        let mut parts = line.split(": ");
+        let solution = parse_number::<N>(parts.next().unwrap())?;
+        let numbers = parts
+            .next()
+            .unwrap()
+            .split(" ")
+            .map(parse_number::<N>)
+            .collect::<Result<Vec<N>, Overflow>>()?;
+        if solve_equation(&solution, numbers, enable_join)? {
+            total = total.try_add(&solution)?;
+        }
+    }
+    Ok(total)
+}
+
+/// Each equation is a test value, a colon, and at least two numbers.
+#[aoc(year = 2024, day = 7, validate, shape = Tokens)]
+pub fn validate(input: &str) -> Vec<Problem> {
+    let mut problems = check_text(input);
+    if !problems.is_empty() {
+        return problems;
+    }
+    for (index, line) in input.trim_end().lines().enumerate() {
+        let Some((solution, numbers)) = line.split_once(':') else {
+            problems.push(Problem::on_line(index + 1, "expected a ':'"));
+            continue;
+        };
+        let numbers: Vec<&str> = numbers.split_whitespace().collect();
+        if numbers.len() < 2 {
+            problems.push(Problem::on_line(
+                index + 1,
+                format!("expected at least two numbers, found {}", numbers.len()),
+            ));
+        }
+        problems.extend(check_numbers(index + 1, [solution]));
+        problems.extend(check_numbers(index + 1, numbers));
+    }
+    problems
+}
+
+#[aoc(year = 2024, day = 7, part = 1)]
+pub fn part1(input: String) -> u64 {
+    total_calibration(&input, false).expect("Calibration overflowed u64")
+}
+
+#[aoc(year = 2024, day = 7, part = 2)]
+pub fn part2(input: String) -> u64 {
+    total_calibration(&input, true).expect("Calibration overflowed u64")
+}
+
+#[aoc(year = 2024, day = 7, part = 1, checked)]
+pub fn part1_checked(input: String, arithmetic: Arithmetic) -> Result<Answer, Overflow> {
+    with_arithmetic!(arithmetic, N => total_calibration::<N>(&input, false))
+}
+
+#[aoc(year = 2024, day = 7, part = 2, checked)]
+pub fn part2_checked(input: String, arithmetic: Arithmetic) -> Result<Answer, Overflow> {
+    with_arithmetic!(arithmetic, N => total_calibration::<N>(&input, true))
+}
+
+/// Shows the operators that make each equation true, or that none do; part 2
+/// may also join numbers with `||`.
+#[aoc(year = 2024, day = 7, explain)]
+pub fn explain(input: &str, part: u8, explanation: &mut Explanation) {
+    for line in input.trim().lines() {
+        let (solution, numbers) = line.split_once(": ").unwrap();
+        let solution = parse_number::<u128>(solution);
+        let numbers = numbers
+            .split(' ')
+            .map(parse_number::<u128>)
+            .collect::<Result<Vec<u128>, Overflow>>();
+        let (Ok(solution), Ok(numbers)) = (solution, numbers) else {
+            explanation.line(format_args!("{} is too large to explain", line));
+            continue;
+        };
+        match find_operators(&solution, numbers[0], &numbers[1..], 2 == part) {
+            Some(operators) => {
+                let mut equation = numbers[0].to_string();
+                for (operator, number) in operators.iter().zip(&numbers[1..]) {
+                    equation.push_str(&format!(" {} {}", operator, number));
+                }
+                explanation.line(format_args!("{} = {}", solution, equation));
+            }
+            None => explanation.line(format_args!("{} can't be made true", line)),
+        }
+    }
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::aoc_examples;
+
+    #[test]
+    fn it_joins_numbers() {
+        assert_eq!(Ok(156u64), join_two_numbers(&15, &6));
+        assert_eq!(Ok(12345u64), join_two_numbers(&12, &345));
+        assert_eq!(Ok(10u64), join_two_numbers(&1, &0));
+        assert_eq!(Ok(100u64), join_two_numbers(&10, &0));
+        assert_eq!(Ok(7u64), join_two_numbers(&0, &7));
+        assert_eq!(Err(Overflow), join_two_numbers(&u64::MAX, &1));
+    }
+
+    #[test]
+    fn it_recursively_solves_equations() {
+        assert_eq!(Ok(true), solve_equation(&190u64, vec![10, 19], false));
+        assert_eq!(Ok(false), solve_equation(&83u64, vec![17, 5], false));
+        assert_eq!(Ok(true), solve_equation(&156u64, vec![15, 6], true));
+        assert_eq!(Ok(true), solve_equation(&7290u64, vec![6, 8, 6, 15], true));
+    }
+
+    #[test]
+    fn it_drops_overflowing_branches() {
+        assert_eq!(
+            Ok(true),
+            solve_equation(&8589934593u64, vec![4294967296, 4294967296, 1], false)
+        );
+        assert_eq!(
+            Ok(false),
+            solve_equation(&5u64, vec![4294967296, 4294967296, 1], true)
+        );
+    }
+
+    #[test]
+    fn it_reports_overflow_before_a_zero() {
+        assert_eq!(
+            Err(Overflow),
+            solve_equation(&0u64, vec![u64::MAX, u64::MAX, 0], false)
+        );
+        assert_eq!(
+            Ok(true),
+            solve_equation(&0u128, vec![u64::MAX as u128, u64::MAX as u128, 0], false)
+        );
+    }
+
+    #[test]
+    fn it_reports_overflowing_totals() {
+        let input = "18446744073709551615: 18446744073709551615 1
+18446744073709551615: 1 18446744073709551615";
+        assert_eq!(Err(Overflow), total_calibration::<u64>(input, false));
+        assert_eq!(
+            Ok(36893488147419103230u128),
+            total_calibration::<u128>(input, false)
+        );
+        assert_eq!(
+            Ok(Answer::from(36893488147419103230u128)),
+            part1_checked(input.to_string(), Arithmetic::Big)
+        );
+        assert_eq!(
+            Err(Overflow),
+            part2_checked("18446744073709551616: 1 1".to_string(), Arithmetic::U64)
+        );
+    }
+
+    aoc_examples! {
+        part1: "example.txt" => 3749,
+        part2: "example.txt" => 11387,
+    }
+
+    #[test]
+    fn it_solves_checked_parts() {
+        let input = include_str!("../examples/example.txt");
+        for arithmetic in [Arithmetic::U64, Arithmetic::U128, Arithmetic::Big] {
+            assert_eq!(
+                Ok(Answer::from(3749)),
+                part1_checked(input.to_string(), arithmetic)
+            );
+            assert_eq!(
+                Ok(Answer::from(11387)),
+                part2_checked(input.to_string(), arithmetic)
+            );
+        }
+    }
+
+    #[test]
+    fn it_validates_input() {
+        assert!(validate("190: 10 19\n3267: 81 40 27\n").is_empty());
+        assert_eq!(
+            vec![
+                Problem::on_line(1, "expected a ':'"),
+                Problem::on_line(2, "expected at least two numbers, found 1"),
+                Problem::on_line(3, "\"x\" is not a number"),
+            ],
+            validate("190 10 19\n3267: 81\n83: 17 x\n")
+        );
+    }
+
+    #[test]
+    fn it_explains_equations() {
+        let input = "190: 10 19\n83: 17 5\n156: 15 6\n292: 11 6 16 20\n";
+        let mut explanation = Explanation::new(10);
+        explain(input, 1, &mut explanation);
+        assert_eq!(
+            [
+                "190 = 10 * 19",
+                "83: 17 5 can't be made true",
+                "156: 15 6 can't be made true",
+                "292 = 11 + 6 * 16 + 20",
+            ],
+            explanation.lines()
+        );
+        let mut explanation = Explanation::new(10);
+        explain(input, 2, &mut explanation);
+        assert_eq!("156 = 15 || 6", explanation.lines()[2]);
+        let mut explanation = Explanation::new(10);
+        explain(
+            "1: 340282366920938463463374607431768211456 1",
+            1,
+            &mut explanation,
+        );
+        assert_eq!(
+            ["1: 340282366920938463463374607431768211456 1 is too large to explain"],
+            explanation.lines()
+        );
+    }
+}
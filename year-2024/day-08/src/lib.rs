@@ -12,19 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use common::{aoc, check_grid, Problem};
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
-use std::fs::read_to_string;
-
-#[cfg(not(tarpaulin_include))]
-fn main() {
-    let input = read_to_string("input.txt").expect("Unable to read input file");
-    println!("Part 1: {}", part1(input.clone()));
-    println!("Part 2: {}", part2(input));
-}
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
-struct Coordinate {
+pub struct Coordinate {
     x: i32,
     y: i32,
 }
@@ -35,7 +28,8 @@ impl Coordinate {
     }
 }
 
-fn parse_part1_map(input: String) -> (HashMap<char, Vec<Coordinate>>, i32, i32) {
+#[aoc(year = 2024, day = 8, parse)]
+pub fn parse_part1_map(input: String) -> (HashMap<char, Vec<Coordinate>>, i32, i32) {
     let mut map = HashMap::new();
     let lines = input.trim().split("\n");
     let mut height = 0;
@@ -53,7 +47,16 @@ fn parse_part1_map(input: String) -> (HashMap<char, Vec<Coordinate>>, i32, i32)
     (map, width as i32, height as i32)
 }
 
-fn part1(input: String) -> usize {
+/// The map is a rectangular grid of `.` and single-character antennas.
+#[aoc(year = 2024, day = 8, validate, shape = Grid)]
+pub fn validate(input: &str) -> Vec<Problem> {
+    check_grid(input, |character| {
+        '.' == character || character.is_ascii_alphanumeric()
+    })
+}
+
+#[aoc(year = 2024, day = 8, part = 1)]
+pub fn part1(input: String) -> usize {
     let mut antinodes: HashSet<Coordinate> = HashSet::new();
     let (map, width, height) = parse_part1_map(input);
     for key in map.keys() {
@@ -89,7 +92,8 @@ fn part1(input: String) -> usize {
     antinodes.len()
 }
 
-fn part2(input: String) -> usize {
+/// Finds every antinode once resonant harmonics are taken into account.
+fn resonant_antinodes(input: String) -> HashSet<Coordinate> {
     let mut antinodes: HashSet<Coordinate> = HashSet::new();
     let (map, width, height) = parse_part1_map(input);
     for key in map.keys() {
@@ -118,14 +122,39 @@ fn part2(input: String) -> usize {
             }
         }
     }
+    antinodes
+}
 
-    antinodes.len()
+#[aoc(year = 2024, day = 8, part = 2)]
+pub fn part2(input: String) -> usize {
+    resonant_antinodes(input).len()
+}
+
+/// Redraws the map with every resonant antinode that is not an antenna
+/// marked `#`, like the puzzle's own example.
+#[aoc(year = 2024, day = 8, visualize)]
+pub fn visualize(input: &str) -> String {
+    let antinodes = resonant_antinodes(input.to_string());
+    let mut picture = String::new();
+    for (y, line) in input.trim().lines().enumerate() {
+        for (x, character) in line.chars().enumerate() {
+            let antinode = antinodes.contains(&Coordinate::new(x as i32, y as i32));
+            picture.push(if '.' == character && antinode {
+                '#'
+            } else {
+                character
+            });
+        }
+        picture.push('\n');
+    }
+    picture
 }
 
 #[cfg(not(tarpaulin_include))]
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::aoc_examples;
 
     #[test]
     fn it_creates_new_coordinates() {
@@ -154,65 +183,41 @@ mod tests {
         );
         assert_eq!(
             (expected, 11, 11),
-            parse_part1_map(
-                "............
-........0...
-.....0......
-.......0....
-....0.......
-......A.....
-............
-............
-........A...
-.........A..
-............
-............"
-                    .to_string()
-            )
+            parse_part1_map(include_str!("../examples/example.txt").to_string())
         );
     }
 
+    aoc_examples! {
+        part1: "example.txt" => 14,
+        part2: "example.txt" => 34,
+    }
+
     #[test]
-    fn it_solves_part1() {
+    fn it_visualizes_antinodes() {
         assert_eq!(
-            14,
-            part1(
-                "............
-........0...
-.....0......
-.......0....
-....0.......
-......A.....
-............
-............
-........A...
-.........A..
-............
-............"
-                    .to_string()
-            )
+            "##....#....#
+.#.#....0...
+..#.#0....#.
+..##...0....
+....0....#..
+.#...#A....#
+...#..#.....
+#....#.#....
+..#.....A...
+....#....A..
+.#........#.
+...#......##
+",
+            visualize(include_str!("../examples/example.txt"))
         );
     }
 
     #[test]
-    fn it_solves_part2() {
+    fn it_validates_input() {
+        assert!(validate("..0.\n.A..\n").is_empty());
         assert_eq!(
-            34,
-            part2(
-                "............
-........0...
-.....0......
-.......0....
-....0.......
-......A.....
-............
-............
-........A...
-.........A..
-............
-............"
-                    .to_string()
-            )
+            vec![Problem::on_line(2, "unexpected '#' in column 1")],
+            validate("..0.\n#A..\n")
         );
     }
 }
@@ -12,14 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::fs::read_to_string;
-
-#[cfg(not(tarpaulin_include))]
-fn main() {
-    let input = read_to_string("input.txt").expect("Unable to read input file");
-    println!("Part 1: {}", part1(input.clone()));
-    println!("Part 2: {}", part2(input));
-}
+use common::{aoc, check_text, Problem};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 enum File {
@@ -33,8 +26,9 @@ enum Partition {
     Empty(usize),
 }
 
-fn part1(input: String) -> usize {
-    let numbers: Vec<usize> = input
+#[aoc(year = 2024, day = 9, parse)]
+pub fn parse_disk_map(input: String) -> Vec<usize> {
+    input
         .trim()
         .chars()
         .map(|x| {
@@ -42,7 +36,38 @@ fn part1(input: String) -> usize {
                 .parse::<usize>()
                 .expect("Unable to parse number")
         })
-        .collect();
+        .collect()
+}
+
+/// The disk map is a single line of digits.
+#[aoc(year = 2024, day = 9, validate, shape = Characters)]
+pub fn validate(input: &str) -> Vec<Problem> {
+    let mut problems = check_text(input);
+    if !problems.is_empty() {
+        return problems;
+    }
+    let lines = input.trim_end().lines().count();
+    if 1 != lines {
+        problems.push(Problem::new(format!("expected one line, found {}", lines)));
+    }
+    for (index, line) in input.trim_end().lines().enumerate() {
+        if let Some((column, character)) = line
+            .chars()
+            .enumerate()
+            .find(|(_, character)| !character.is_ascii_digit())
+        {
+            problems.push(Problem::on_line(
+                index + 1,
+                format!("unexpected {:?} in column {}", character, column + 1),
+            ));
+        }
+    }
+    problems
+}
+
+#[aoc(year = 2024, day = 9, part = 1)]
+pub fn part1(input: String) -> usize {
+    let numbers = parse_disk_map(input);
     let mut index = 0;
     let mut is_block = true;
     let mut filesystem: Vec<File> = Vec::new();
@@ -88,16 +113,9 @@ fn part1(input: String) -> usize {
     })
 }
 
-fn part2(input: String) -> usize {
-    let numbers: Vec<usize> = input
-        .trim()
-        .chars()
-        .map(|x| {
-            x.to_string()
-                .parse::<usize>()
-                .expect("Unable to parse number")
-        })
-        .collect();
+#[aoc(year = 2024, day = 9, part = 2)]
+pub fn part2(input: String) -> usize {
+    let numbers = parse_disk_map(input);
     let mut index = 0;
     let mut is_block = true;
     let mut filesystem: Vec<Partition> = Vec::new();
@@ -174,14 +192,27 @@ fn part2(input: String) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::aoc_examples;
 
     #[test]
-    fn it_solves_part1() {
-        assert_eq!(1928, part1("2333133121414131402".to_string()))
+    fn it_parses_disk_maps() {
+        assert_eq!(vec![1, 2, 3, 4, 5], parse_disk_map("12345\n".to_string()));
+    }
+
+    aoc_examples! {
+        part1: "example.txt" => 1928,
+        part2: "example.txt" => 2858,
     }
 
     #[test]
-    fn it_solves_part2() {
-        assert_eq!(2858, part2("2333133121414131402".to_string()))
+    fn it_validates_input() {
+        assert!(validate("2333133121414131402\n").is_empty());
+        assert_eq!(
+            vec![
+                Problem::new("expected one line, found 2"),
+                Problem::on_line(1, "unexpected 'x' in column 3"),
+            ],
+            validate("23x3\n12\n")
+        );
     }
 }
@@ -12,15 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use common::{aoc, check_grid, Problem};
 use std::collections::HashSet;
-use std::fs::read_to_string;
-
-#[cfg(not(tarpaulin_include))]
-fn main() {
-    let input = read_to_string("input.txt").expect("Unable to read input file");
-    println!("Part 1: {}", part1(input.clone()));
-    println!("Part 2: {}", part2(input));
-}
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 struct Coordinate {
@@ -28,7 +21,7 @@ struct Coordinate {
     y: usize,
 }
 
-fn get_neighbors(coordinate: Coordinate, grid: &Vec<Vec<usize>>) -> HashSet<Coordinate> {
+fn get_neighbors(coordinate: Coordinate, grid: &[Vec<usize>]) -> HashSet<Coordinate> {
     let mut neighbors = HashSet::new();
     if 0 < coordinate.x
         && grid[coordinate.y][coordinate.x - 1] == grid[coordinate.y][coordinate.x] + 1
@@ -65,7 +58,7 @@ fn get_neighbors(coordinate: Coordinate, grid: &Vec<Vec<usize>>) -> HashSet<Coor
     neighbors
 }
 
-fn find_trail_ends(start: Coordinate, grid: &Vec<Vec<usize>>) -> HashSet<Coordinate> {
+fn find_trail_ends(start: Coordinate, grid: &[Vec<usize>]) -> HashSet<Coordinate> {
     let mut trail_ends = HashSet::new();
     if 9 == grid[start.y][start.x] {
         trail_ends.insert(start);
@@ -74,10 +67,17 @@ fn find_trail_ends(start: Coordinate, grid: &Vec<Vec<usize>>) -> HashSet<Coordin
             trail_ends.extend(find_trail_ends(neighbor, grid));
         }
     }
-    return trail_ends;
+    trail_ends
 }
 
-fn part1(input: String) -> usize {
+/// The topographic map is a rectangular grid of heights from 0 to 9.
+#[aoc(year = 2024, day = 10, validate, shape = Grid)]
+pub fn validate(input: &str) -> Vec<Problem> {
+    check_grid(input, |character| character.is_ascii_digit())
+}
+
+#[aoc(year = 2024, day = 10, part = 1)]
+pub fn part1(input: String) -> usize {
     let mut grid: Vec<Vec<usize>> = Vec::new();
     let mut trailheads: Vec<Coordinate> = Vec::new();
     for (y, line) in input.trim().lines().enumerate() {
@@ -96,7 +96,7 @@ fn part1(input: String) -> usize {
     trails
 }
 
-fn count_trails(start: Coordinate, grid: &Vec<Vec<usize>>) -> usize {
+fn count_trails(start: Coordinate, grid: &[Vec<usize>]) -> usize {
     let mut trails = 0;
     if 9 == grid[start.y][start.x] {
         trails += 1;
@@ -105,10 +105,11 @@ fn count_trails(start: Coordinate, grid: &Vec<Vec<usize>>) -> usize {
             trails += count_trails(neighbor, grid);
         }
     }
-    return trails;
+    trails
 }
 
-fn part2(input: String) -> usize {
+#[aoc(year = 2024, day = 10, part = 2)]
+pub fn part2(input: String) -> usize {
     let mut grid: Vec<Vec<usize>> = Vec::new();
     let mut trailheads: Vec<Coordinate> = Vec::new();
     for (y, line) in input.trim().lines().enumerate() {
@@ -127,10 +128,59 @@ fn part2(input: String) -> usize {
     trails
 }
 
+/// Marks every cell on a trail from `start` to a height of 9, returning
+/// whether there is one.
+fn mark_trails(start: Coordinate, grid: &[Vec<usize>], on_trail: &mut HashSet<Coordinate>) -> bool {
+    let mut found = 9 == grid[start.y][start.x];
+    for neighbor in get_neighbors(start.clone(), grid) {
+        found |= mark_trails(neighbor, grid, on_trail);
+    }
+    if found {
+        on_trail.insert(start);
+    }
+    found
+}
+
+/// Redraws the map with every height that is not on a hiking trail
+/// replaced by `.`, like the puzzle's own examples.
+#[aoc(year = 2024, day = 10, visualize)]
+pub fn visualize(input: &str) -> String {
+    let grid: Vec<Vec<usize>> = input
+        .trim()
+        .lines()
+        .map(|line| {
+            line.chars()
+                .map(|character| character.to_digit(10).unwrap() as usize)
+                .collect()
+        })
+        .collect();
+    let mut on_trail = HashSet::new();
+    for (y, row) in grid.iter().enumerate() {
+        for (x, height) in row.iter().enumerate() {
+            if 0 == *height {
+                mark_trails(Coordinate { x, y }, &grid, &mut on_trail);
+            }
+        }
+    }
+    let mut picture = String::new();
+    for (y, row) in grid.iter().enumerate() {
+        for (x, height) in row.iter().enumerate() {
+            picture.push(if on_trail.contains(&Coordinate { x, y }) {
+                char::from_digit(*height as u32, 10).unwrap()
+            } else {
+                '.'
+            });
+        }
+        picture.push('\n');
+    }
+    picture
+}
+
 #[cfg(not(tarpaulin_include))]
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::aoc_examples;
 
     #[test]
     fn it_finds_neighbors() {
@@ -145,49 +195,23 @@ mod tests {
         assert_eq!(expected, get_neighbors(coordinate, &grid));
     }
 
+    aoc_examples! {
+        part1: "small.txt" => 1,
+        part1: "example.txt" => 36,
+        part2: "example.txt" => 81,
+    }
+
     #[test]
-    fn it_solves_part1() {
-        assert_eq!(
-            1,
-            part1(
-                "0123
-1234
-8765
-9876"
-                    .to_string()
-            )
-        );
-        assert_eq!(
-            36,
-            part1(
-                "89010123
-78121874
-87430965
-96549874
-45678903
-32019012
-01329801
-10456732"
-                    .to_string()
-            )
-        )
+    fn it_visualizes_trails() {
+        assert_eq!("0123\n9854\n876.\n", visualize("0123\n9854\n8767\n"));
     }
 
     #[test]
-    fn it_solves_part2() {
+    fn it_validates_input() {
+        assert!(validate("0123\n1234\n").is_empty());
         assert_eq!(
-            81,
-            part2(
-                "89010123
-78121874
-87430965
-96549874
-45678903
-32019012
-01329801
-10456732"
-                    .to_string()
-            )
+            vec![Problem::on_line(1, "unexpected '.' in column 2")],
+            validate("0.23\n1234\n")
         );
     }
 }
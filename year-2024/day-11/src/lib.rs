@@ -0,0 +1,161 @@
+// Copyright 2024 CJ Harries
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common::{
+    aoc, check_numbers, check_text, parse_number, with_arithmetic, Answer, Arithmetic, Memo,
+    Number, Overflow, Problem,
+};
+
+type BlinkMemo<N> = Memo<(N, usize), N>;
+
+fn blink<N: Number>(number: N, remaining: usize, memo: &mut BlinkMemo<N>) -> Result<N, Overflow> {
+    if 0 == remaining {
+        return Ok(N::one());
+    }
+    memo.try_call((number.clone(), remaining), |memo| {
+        let stringified = number.to_string();
+        if number.is_zero() {
+            blink(N::one(), remaining - 1, memo)
+        } else if stringified.len().is_multiple_of(2) {
+            let half = stringified.len() / 2;
+            let first_half = parse_number::<N>(&stringified[0..half])?;
+            let second_half = parse_number::<N>(&stringified[half..])?;
+            blink(first_half, remaining - 1, memo)?.try_add(&blink(
+                second_half,
+                remaining - 1,
+                memo,
+            )?)
+        } else {
+            blink(number.try_mul(&N::from(2024))?, remaining - 1, memo)
+        }
+    })
+}
+
+/// Counts stones with a fresh cache, so nothing is shared between inputs.
+pub fn count_stones<N: Number>(input: &str, blinks: usize) -> Result<N, Overflow> {
+    let mut memo = Memo::new();
+    input
+        .trim()
+        .split_ascii_whitespace()
+        .try_fold(N::zero(), |total, stone| {
+            total.try_add(&blink(parse_number::<N>(stone)?, blinks, &mut memo)?)
+        })
+}
+
+/// The stones are one line of numbers, which may be too large for `u64`.
+#[aoc(year = 2024, day = 11, validate, shape = Tokens)]
+pub fn validate(input: &str) -> Vec<Problem> {
+    let mut problems = check_text(input);
+    if !problems.is_empty() {
+        return problems;
+    }
+    let lines = input.trim_end().lines().count();
+    if 1 != lines {
+        problems.push(Problem::new(format!("expected one line, found {}", lines)));
+    }
+    for (index, line) in input.trim_end().lines().enumerate() {
+        problems.extend(check_numbers(index + 1, line.split_whitespace()));
+    }
+    problems
+}
+
+#[aoc(year = 2024, day = 11, part = 1)]
+pub fn part1(input: String) -> u64 {
+    count_stones(&input, 25).expect("Stone count overflowed u64")
+}
+
+#[aoc(year = 2024, day = 11, part = 2)]
+pub fn part2(input: String) -> u64 {
+    count_stones(&input, 75).expect("Stone count overflowed u64")
+}
+
+#[aoc(year = 2024, day = 11, part = 1, checked)]
+pub fn part1_checked(input: String, arithmetic: Arithmetic) -> Result<Answer, Overflow> {
+    with_arithmetic!(arithmetic, N => count_stones::<N>(&input, 25))
+}
+
+#[aoc(year = 2024, day = 11, part = 2, checked)]
+pub fn part2_checked(input: String, arithmetic: Arithmetic) -> Result<Answer, Overflow> {
+    with_arithmetic!(arithmetic, N => count_stones::<N>(&input, 75))
+}
+
+#[cfg(not(tarpaulin_include))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::aoc_examples;
+
+    #[test]
+    fn it_blinks() {
+        let mut memo = Memo::new();
+        assert_eq!(Ok(1u64), blink(0, 1, &mut memo));
+        assert_eq!(Ok(2u64), blink(10, 1, &mut memo));
+        assert_eq!(Ok(1u64), blink(1, 1, &mut memo));
+        assert_eq!(Ok(4u64), blink(1, 3, &mut memo));
+        assert_eq!(Some(4), memo.get(&(1, 3)));
+    }
+
+    #[test]
+    fn it_reuses_cached_blinks() {
+        let mut memo = Memo::new();
+        assert_eq!(Ok(7u64), blink(125, 6, &mut memo));
+        let misses = memo.stats().misses;
+        assert_eq!(Ok(7u64), blink(125, 6, &mut memo));
+        assert_eq!(misses, memo.stats().misses);
+        assert!(memo.stats().hits > 0);
+    }
+
+    #[test]
+    fn it_reports_overflowing_stones() {
+        assert_eq!(Err(Overflow), count_stones::<u64>("9999999999999999999", 1));
+        assert_eq!(Ok(1u128), count_stones::<u128>("9999999999999999999", 1));
+        assert_eq!(
+            Err(Overflow),
+            part1_checked(
+                "9999999999999999999999999999999999999".to_string(),
+                Arithmetic::U128
+            )
+        );
+        assert_eq!(
+            Ok(Answer::from(55312)),
+            part1_checked("125 17".to_string(), Arithmetic::Big)
+        );
+    }
+
+    #[test]
+    fn it_solves_checked_part2() {
+        for arithmetic in [Arithmetic::U64, Arithmetic::U128, Arithmetic::Big] {
+            assert_eq!(
+                Ok(Answer::from(65601038650482u64)),
+                part2_checked("125 17".to_string(), arithmetic)
+            );
+        }
+    }
+
+    aoc_examples! {
+        part1: "example.txt" => 55312,
+        part2: "example.txt" => 65601038650482,
+    }
+
+    #[test]
+    fn it_validates_input() {
+        assert!(validate("125 17\n").is_empty());
+        assert!(validate("99999999999999999999999 0\n").is_empty());
+        assert_eq!(
+            vec![Problem::on_line(1, "\"1.5\" is not a number")],
+            validate("125 1.5\n")
+        );
+        assert_eq!(vec![Problem::new("input is empty")], validate(""));
+    }
+}